@@ -0,0 +1,97 @@
+use std::{alloc::{GlobalAlloc, Layout, System}, hint::black_box, sync::atomic::{AtomicUsize, Ordering}, time::Instant};
+
+use bevy_editor_experiment_lib::fgr::*;
+
+struct CountingAlloc;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+struct Ctx {
+    fgr_ctx: FgrCtx<Ctx>,
+}
+
+impl HasFgrCtx for Ctx {
+    fn fgr_ctx<'a>(&'a mut self) -> impl std::ops::DerefMut<Target=FgrCtx<Ctx>> + 'a {
+        &mut self.fgr_ctx
+    }
+}
+
+const ITERATIONS: usize = 1_000_000;
+
+fn bench(name: &str, ctx: &mut Ctx, mut read: impl FnMut(&mut Ctx) -> u64) -> usize {
+    // the first read may set things up, such as a node's value on its first evaluation.
+    black_box(read(ctx));
+    let allocations_before = ALLOCATIONS.load(Ordering::Relaxed);
+    let start = Instant::now();
+    let mut sum = 0u64;
+    for _ in 0..ITERATIONS {
+        sum = sum.wrapping_add(read(ctx));
+    }
+    let elapsed = start.elapsed();
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed) - allocations_before;
+    black_box(sum);
+    println!(
+        "{:<38} {:>8.2} ns/read {:>10} allocations",
+        name,
+        elapsed.as_nanos() as f64 / ITERATIONS as f64,
+        allocations,
+    );
+    allocations
+}
+
+fn main() {
+    let mut ctx = Ctx { fgr_ctx: FgrCtx::new() };
+    let ctx = &mut ctx;
+    let signal = Signal::new(ctx, 42u64);
+    let (memo, derived, mut scope) = ctx.fgr_create_root(|ctx, scope| {
+        let memo = Memo::new(ctx, {
+            let signal = signal.clone();
+            move |ctx| *signal.value(ctx) + 1
+        });
+        let derived = signal.derive(|x| *x + 2);
+        (memo, derived, scope)
+    });
+    let pair = Signal::new(ctx, (1u64, 2u64));
+    let selected = pair.select(|(_, second)| second);
+    let selected_twice = selected.select(|second| second);
+    let selected_derived = derived.select(|x| x);
+    let boxed_signal: BoxedAccessor<Ctx, u64> = signal.clone().into();
+    let boxed_memo: BoxedAccessor<Ctx, u64> = memo.clone().into();
+    let boxed_const: BoxedAccessor<Ctx, u64> = ConstAccessor::new(7u64).into();
+    bench("Signal::value", ctx, |ctx| *signal.value(ctx));
+    let mut zero_allocation_reads = [
+        bench("BoxedAccessor (signal)", ctx, |ctx| *boxed_signal.value(ctx)),
+        bench("BoxedAccessor (memo)", ctx, |ctx| *boxed_memo.value(ctx)),
+        bench("BoxedAccessor (const)", ctx, |ctx| *boxed_const.value(ctx)),
+        bench("BoxedAccessor (derived)", ctx, |ctx| *derived.value(ctx)),
+        // a second read while the first one is alive
+        bench("BoxedAccessor (derived, held)", ctx, |ctx| {
+            let held = derived.value(ctx);
+            *held + *derived.value(ctx)
+        }),
+        bench("BoxedAccessor (select)", ctx, |ctx| *selected.value(ctx)),
+        bench("BoxedAccessor (select x2)", ctx, |ctx| *selected_twice.value(ctx)),
+        bench("BoxedAccessor (select derived)", ctx, |ctx| *selected_derived.value(ctx)),
+    ].into_iter();
+    // a select over a derived accessor boxes the value of a read overlapping a live one
+    bench("BoxedAccessor (select derived, held)", ctx, |ctx| {
+        let held = selected_derived.value(ctx);
+        *held + *selected_derived.value(ctx)
+    });
+    scope.dispose(ctx);
+    assert!(zero_allocation_reads.all(|allocations| allocations == 0), "BoxedAccessor reads must not allocate");
+}
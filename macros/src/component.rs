@@ -0,0 +1,299 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{spanned::Spanned, Attribute, Expr, FnArg, Ident, ItemFn, Pat, Type, Visibility};
+
+// #[component] fn text_box(world: &mut World, #[prop(optional)] width: MaybeReactive<Val>) -> Entity
+//
+// becomes a unit struct `TextBox`, a `TextBoxProps` struct with a field and a setter per prop, and
+// `UiComponent<TextBoxProps>` and `ViewElement` calling the function with the props taken apart.
+// A prop called `children` receives the children written inside the component in `view!`.
+//
+// `view!` starts from the props' defaults, so components with required props shadow
+// `DefaultProps::default_props` with a function whose bound never holds, with a diagnostic
+// naming the props.
+//
+// `view!` sets `on:event={handler}` with `set_on_event`, generated for every `on_event` prop of
+// the component. Events it has no prop for go to the prop marked `#[prop(events)]`, through
+// `EventProps` and the setter traits of the events type, such as `PointerEvents`. Inherent
+// methods are picked before trait methods, so the component's own props come first.
+//
+// With `#[component(reflect)]` there is also a reflected `TextBoxPropsData` with a field per prop
+// of the prop's `DataProp::Data` type, and `DataProps` building the props from it, so the
+// component can be registered in the `UiComponentRegistry` as it is.
+
+struct Prop {
+    name: Ident,
+    ty: Type,
+    default: Option<Expr>,
+    events: bool,
+}
+
+pub fn expand(mut item: ItemFn, reflect: bool) -> syn::Result<TokenStream> {
+    if !item.sig.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &item.sig.generics,
+            "generic components are not supported, implement `UiComponent` by hand",
+        ));
+    }
+    let mut inputs = item.sig.inputs.iter_mut();
+    let Some(FnArg::Typed(world_arg)) = inputs.next() else {
+        return Err(syn::Error::new_spanned(&item.sig, "a component takes `world: &mut World` as its first argument"));
+    };
+    let world_ty = world_arg.ty.clone();
+    let mut props = Vec::new();
+    for input in inputs {
+        let FnArg::Typed(arg) = input else {
+            return Err(syn::Error::new_spanned(input, "components can not take `self`"));
+        };
+        let Pat::Ident(pat) = &*arg.pat else {
+            return Err(syn::Error::new_spanned(&arg.pat, "props must be plain identifiers"));
+        };
+        let (default, events) = take_prop_attr(&mut arg.attrs)?;
+        props.push(Prop {
+            name: pat.ident.clone(),
+            ty: (*arg.ty).clone(),
+            default,
+            events,
+        });
+    }
+    let mut events = props.iter().filter(|prop| prop.events);
+    let events_prop = events.next();
+    if let Some(prop) = events.next() {
+        return Err(syn::Error::new_spanned(&prop.name, "only one prop can take the events"));
+    }
+
+    let lib = quote!(::bevy_editor_experiment_lib);
+    let vis = std::mem::replace(&mut item.vis, Visibility::Inherited);
+    let (docs, attrs): (Vec<Attribute>, Vec<Attribute>) = std::mem::take(&mut item.attrs)
+        .into_iter()
+        .partition(|attr| attr.path().is_ident("doc"));
+    item.attrs = attrs;
+    let fn_name = &item.sig.ident;
+    let output = &item.sig.output;
+    let component = Ident::new(&pascal_case(&fn_name.to_string()), fn_name.span());
+    let props_name = format_ident!("{}Props", component);
+
+    let names: Vec<&Ident> = props.iter().map(|prop| &prop.name).collect();
+    let types: Vec<&Type> = props.iter().map(|prop| &prop.ty).collect();
+    let required: Vec<&Prop> = props.iter().filter(|prop| prop.default.is_none()).collect();
+    let required_names: Vec<&Ident> = required.iter().map(|prop| &prop.name).collect();
+    let required_types: Vec<&Type> = required.iter().map(|prop| &prop.ty).collect();
+    let initializers: Vec<TokenStream> = props
+        .iter()
+        .map(|prop| {
+            let name = &prop.name;
+            match &prop.default {
+                Some(default) => quote!(#name: #default),
+                None => quote!(#name: ::core::convert::Into::into(#name)),
+            }
+        })
+        .collect();
+    let new_doc = if required.is_empty() {
+        "Props with every prop set to its default."
+    } else {
+        "Props from the required props, the others start at their defaults."
+    };
+    let set_children = names.iter().any(|name| *name == "children").then(|| {
+        quote! {
+            fn set_children(props: &mut #props_name, children: #lib::ui::Slot) -> ::core::option::Option<#lib::ui::Slot> {
+                props.children = children;
+                ::core::option::Option::None
+            }
+        }
+    });
+    let event_props = events_prop.map(|prop| {
+        let Prop { name, ty, .. } = prop;
+        quote! {
+            impl #lib::ui::EventProps for #props_name {
+                type Events = #ty;
+
+                fn events_mut(&mut self) -> &mut #ty {
+                    &mut self.#name
+                }
+            }
+        }
+    });
+    let event_setters: Vec<TokenStream> = props
+        .iter()
+        .filter(|prop| prop.name.to_string().starts_with("on_"))
+        .map(|Prop { name, ty, .. }| {
+            let setter = format_ident!("set_{}", name);
+            quote! {
+                #[doc(hidden)]
+                pub fn #setter(&mut self, #name: #ty) {
+                    self.#name = #name;
+                }
+            }
+        })
+        .collect();
+    let defaults = match required.is_empty() {
+        true => quote! {
+            impl ::core::default::Default for #props_name {
+                fn default() -> Self {
+                    Self::new()
+                }
+            }
+        },
+        false => {
+            let required_trait = format_ident!("__{}RequiredProps", component);
+            let listed = required_names.iter().map(|name| format!("`{}`", name)).collect::<Vec<_>>().join(", ");
+            let message = match required.len() {
+                1 => format!("`view!` can not build `<{}>`, its prop {} has no default", component, listed),
+                _ => format!("`view!` can not build `<{}>`, its props {} have no default", component, listed),
+            };
+            let note = format!(
+                "give the props a default with `#[prop(optional)]` or `#[prop(default = ..)]`, or build it with `{}::run(world, {}::new(..))`",
+                component, props_name,
+            );
+            quote! {
+                #[doc(hidden)]
+                #[diagnostic::on_unimplemented(message = #message, label = "props without a default", note = #note)]
+                #vis trait #required_trait {
+                    fn props() -> #props_name;
+                }
+
+                impl #component {
+                    /// Shadows `DefaultProps::default_props` for `view!`, which can not fill in the
+                    /// required props, so using the component there names them.
+                    #[doc(hidden)]
+                    #vis fn default_props<T: #required_trait>() -> #props_name {
+                        T::props()
+                    }
+                }
+            }
+        }
+    };
+    let view_element = quote! {
+        impl #lib::ui::ViewElement for #component {
+            type Props = #props_name;
+
+            fn build(world: #world_ty, props: #props_name) -> #lib::ui::__view::Entity {
+                <#component as #lib::ui::UiComponent<#props_name>>::run(world, props)
+            }
+
+            #set_children
+        }
+    };
+
+    let data = reflect.then(|| {
+        if let Some(prop) = required.first() {
+            return Err(syn::Error::new_spanned(&prop.name, "`#[component(reflect)]` needs a default for every prop"));
+        }
+        let data_name = format_ident!("{}Data", props_name);
+        let set_children = match names.iter().any(|name| *name == "children") {
+            true => quote!(props.children = children;),
+            false => quote!(let _ = children;),
+        };
+        Ok(quote! {
+            #[doc = concat!("[`", stringify!(#props_name), "`] as plain data, for the `UiComponentRegistry`.")]
+            #[derive(::bevy::reflect::Reflect, ::core::clone::Clone, ::core::default::Default, ::core::cmp::PartialEq)]
+            #vis struct #data_name {
+                #(pub #names: <#types as #lib::ui::DataProp>::Data,)*
+            }
+
+            impl #lib::ui::DataProps for #props_name {
+                type Data = #data_name;
+
+                fn from_data(world: #world_ty, data: &#lib::ui::MaybeReactive<#data_name>, children: #lib::ui::Slot) -> Self {
+                    let mut props = Self::new();
+                    #({
+                        let field = #lib::ui::prop(world, data, |data: &#data_name| data.#names.clone());
+                        #lib::ui::DataProp::set_from_data(&mut props.#names, world, field);
+                    })*
+                    #set_children
+                    props
+                }
+            }
+        })
+    }).transpose()?;
+
+    Ok(quote! {
+        #(#docs)*
+        #vis struct #component;
+
+        #[doc = concat!("Props of [`", stringify!(#component), "`].")]
+        #vis struct #props_name {
+            #(pub #names: #types,)*
+        }
+
+        impl #props_name {
+            #[doc = #new_doc]
+            pub fn new(#(#required_names: impl ::core::convert::Into<#required_types>),*) -> Self {
+                Self {
+                    #(#initializers,)*
+                }
+            }
+
+            #(
+                pub fn #names(mut self, #names: impl ::core::convert::Into<#types>) -> Self {
+                    self.#names = ::core::convert::Into::into(#names);
+                    self
+                }
+            )*
+
+            #(#event_setters)*
+        }
+
+        #event_props
+
+        #defaults
+
+        #view_element
+
+        #data
+
+        impl #lib::ui::UiComponent<#props_name> for #component {
+            fn run(world: #world_ty, props: #props_name) #output {
+                // every prop is an argument, however many there are
+                #[allow(clippy::too_many_arguments)]
+                #item
+
+                let #props_name { #(#names),* } = props;
+                #fn_name(world, #(#names),*)
+            }
+        }
+    })
+}
+
+/// Removes the `#[prop(..)]` attribute and returns the prop's default, if it has one, and whether
+/// it takes the events.
+fn take_prop_attr(attrs: &mut Vec<Attribute>) -> syn::Result<(Option<Expr>, bool)> {
+    let mut default = None;
+    let mut events = false;
+    let mut result = Ok(());
+    attrs.retain(|attr| {
+        if !attr.path().is_ident("prop") {
+            return true;
+        }
+        let parsed = attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("optional") {
+                default = Some(syn::parse_quote_spanned!(meta.path.span()=> ::core::default::Default::default()));
+                Ok(())
+            } else if meta.path.is_ident("default") {
+                default = Some(meta.value()?.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("events") {
+                events = true;
+                Ok(())
+            } else {
+                Err(meta.error("expected `optional`, `default = ..` or `events`"))
+            }
+        });
+        if let Err(err) = parsed {
+            result = Err(err);
+        }
+        false
+    });
+    result.map(|()| (default, events))
+}
+
+fn pascal_case(name: &str) -> String {
+    name.split('_')
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            let first = chars.next().map(|c| c.to_uppercase().to_string()).unwrap_or_default();
+            first + chars.as_str()
+        })
+        .collect()
+}
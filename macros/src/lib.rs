@@ -0,0 +1,28 @@
+//! Procedural macros for `bevy_editor_experiment_lib`. Use them through the re-exports in its
+//! `ui` module, the expansions refer to that crate by name.
+
+use proc_macro::TokenStream;
+
+mod component;
+mod view;
+
+/// Builds a tree of components and bundles, see `bevy_editor_experiment_lib::ui::view`.
+#[proc_macro]
+pub fn view(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as view::ViewInput);
+    view::expand(input).into()
+}
+
+/// Turns a function building a component into the component, its props struct and the
+/// `UiComponent` impl, see `bevy_editor_experiment_lib::ui::component`. `#[component(reflect)]`
+/// also gives the props a plain data version, for the `UiComponentRegistry`.
+#[proc_macro_attribute]
+pub fn component(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let reflect = match syn::parse_macro_input!(attr as Option<syn::Ident>) {
+        None => false,
+        Some(ident) if ident == "reflect" => true,
+        Some(ident) => return syn::Error::new(ident.span(), "expected `reflect` or nothing").to_compile_error().into(),
+    };
+    let item = syn::parse_macro_input!(item as syn::ItemFn);
+    component::expand(item, reflect).unwrap_or_else(syn::Error::into_compile_error).into()
+}
@@ -0,0 +1,362 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote, quote_spanned, ToTokens};
+use syn::{
+    braced, parenthesized,
+    parse::{Parse, ParseStream},
+    punctuated::Punctuated,
+    spanned::Spanned,
+    token, Expr, ExprBlock, ExprCall, ExprLit, ExprPath, Ident, Lit, LitStr, Path, Stmt, Token,
+};
+
+// view! { world, <Element attr=value on:event={handler} bind:field={accessor}> children </Element> }
+//
+// Elements are resolved through the `ViewElement` trait, which gives the props type to fill in
+// and how to build it. `Show` and `For` are control flow, `Show` gets its children lazily.
+
+pub struct ViewInput {
+    world: Ident,
+    root: Element,
+}
+
+struct Element {
+    name: Path,
+    attrs: Vec<Attr>,
+    children: Vec<Child>,
+}
+
+enum AttrKind {
+    Prop,
+    On,
+    Bind,
+}
+
+struct Attr {
+    kind: AttrKind,
+    name: Ident,
+    value: Expr,
+}
+
+enum Child {
+    Element(Element),
+    Slot(Ident, Vec<Child>),
+    Text(LitStr),
+    Block(Expr),
+}
+
+impl Parse for ViewInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let world = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let root = input.parse()?;
+        if !input.is_empty() {
+            return Err(input.error("a view has a single root element"));
+        }
+        Ok(Self { world, root })
+    }
+}
+
+impl Parse for Element {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        input.parse::<Token![<]>()?;
+        let name = Path::parse_mod_style(input)?;
+        let mut attrs = Vec::new();
+        while !input.peek(Token![/]) && !input.peek(Token![>]) {
+            attrs.push(input.parse()?);
+        }
+        let mut children = Vec::new();
+        if input.peek(Token![/]) {
+            input.parse::<Token![/]>()?;
+            input.parse::<Token![>]>()?;
+            return Ok(Self { name, attrs, children });
+        }
+        input.parse::<Token![>]>()?;
+        while !(input.peek(Token![<]) && input.peek2(Token![/])) {
+            if input.is_empty() {
+                return Err(syn::Error::new_spanned(&name, format!("`<{}>` is never closed", path_string(&name))));
+            }
+            children.push(input.parse()?);
+        }
+        input.parse::<Token![<]>()?;
+        input.parse::<Token![/]>()?;
+        let closing = Path::parse_mod_style(input)?;
+        if path_string(&closing) != path_string(&name) {
+            return Err(syn::Error::new_spanned(
+                &closing,
+                format!("closing tag `</{}>` does not match `<{}>`", path_string(&closing), path_string(&name)),
+            ));
+        }
+        input.parse::<Token![>]>()?;
+        Ok(Self { name, attrs, children })
+    }
+}
+
+impl Parse for Attr {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut name: Ident = input.parse()?;
+        let mut kind = AttrKind::Prop;
+        if input.peek(Token![:]) && !input.peek(Token![::]) {
+            kind = match name.to_string().as_str() {
+                "on" => AttrKind::On,
+                "bind" => AttrKind::Bind,
+                _ => return Err(syn::Error::new_spanned(&name, "expected `on:` or `bind:`")),
+            };
+            input.parse::<Token![:]>()?;
+            name = input.parse()?;
+        }
+        input.parse::<Token![=]>()?;
+        let value = parse_attr_value(input)?;
+        Ok(Self { kind, name, value })
+    }
+}
+
+/// Attribute values are literals, `{ blocks }` or paths with an optional call, anything else
+/// has to be wrapped in braces as `>` and `/` would be ambiguous.
+fn parse_attr_value(input: ParseStream) -> syn::Result<Expr> {
+    if input.peek(token::Brace) {
+        return Ok(unwrap_block(input.parse()?));
+    }
+    if input.peek(Lit) {
+        return Ok(Expr::Lit(ExprLit { attrs: Vec::new(), lit: input.parse()? }));
+    }
+    if !input.peek(Ident) && !input.peek(Token![::]) {
+        return Err(input.error("expected a literal, a path or a `{ block }`"));
+    }
+    let path = Expr::Path(ExprPath { attrs: Vec::new(), qself: None, path: Path::parse_mod_style(input)? });
+    if !input.peek(token::Paren) {
+        return Ok(path);
+    }
+    let content;
+    let paren_token = parenthesized!(content in input);
+    Ok(Expr::Call(ExprCall {
+        attrs: Vec::new(),
+        func: Box::new(path),
+        paren_token,
+        args: Punctuated::parse_terminated(&content)?,
+    }))
+}
+
+impl Parse for Child {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        if is_slot(input) {
+            parse_slot(input)
+        } else if input.peek(Token![<]) {
+            Ok(Child::Element(input.parse()?))
+        } else if input.peek(LitStr) {
+            Ok(Child::Text(input.parse()?))
+        } else if input.peek(token::Brace) {
+            let content;
+            let brace_token = braced!(content in input);
+            Ok(Child::Block(unwrap_block(ExprBlock {
+                attrs: Vec::new(),
+                label: None,
+                block: syn::Block { brace_token, stmts: content.call(syn::Block::parse_within)? },
+            })))
+        } else {
+            Err(input.error("expected an element, a string literal or a `{ block }`"))
+        }
+    }
+}
+
+/// `{ expr }` is emitted as just `expr`, the braces are only there for the parser and would
+/// trip the `unused_braces` lint in the expansion.
+fn unwrap_block(block: ExprBlock) -> Expr {
+    match block.block.stmts.as_slice() {
+        [Stmt::Expr(expr, None)] => expr.clone(),
+        _ => Expr::Block(block),
+    }
+}
+
+/// Closures, bare or ending a block that clones what they capture, become derived props.
+fn is_closure(expr: &Expr) -> bool {
+    match expr {
+        Expr::Closure(_) => true,
+        Expr::Block(block) => matches!(block.block.stmts.last(), Some(Stmt::Expr(Expr::Closure(_), None))),
+        _ => false,
+    }
+}
+
+fn is_slot(input: ParseStream) -> bool {
+    let fork = input.fork();
+    fork.parse::<Token![<]>().is_ok()
+        && fork.parse::<Ident>().is_ok_and(|ident| ident == "slot")
+        && fork.peek(Token![:])
+        && !fork.peek(Token![::])
+}
+
+fn parse_slot(input: ParseStream) -> syn::Result<Child> {
+    input.parse::<Token![<]>()?;
+    input.parse::<Ident>()?;
+    input.parse::<Token![:]>()?;
+    let name: Ident = input.parse()?;
+    input.parse::<Token![>]>()?;
+    let mut children = Vec::new();
+    while !(input.peek(Token![<]) && input.peek2(Token![/])) {
+        if input.is_empty() {
+            return Err(syn::Error::new_spanned(&name, format!("`<slot:{}>` is never closed", name)));
+        }
+        children.push(input.parse()?);
+    }
+    input.parse::<Token![<]>()?;
+    input.parse::<Token![/]>()?;
+    let slot: Ident = input.parse()?;
+    input.parse::<Token![:]>()?;
+    let closing: Ident = input.parse()?;
+    if slot != "slot" || closing != name {
+        return Err(syn::Error::new_spanned(&closing, format!("expected `</slot:{}>`", name)));
+    }
+    input.parse::<Token![>]>()?;
+    Ok(Child::Slot(name, children))
+}
+
+fn path_string(path: &Path) -> String {
+    path.to_token_stream().to_string().replace(' ', "")
+}
+
+fn lib() -> TokenStream {
+    quote!(::bevy_editor_experiment_lib)
+}
+
+pub fn expand(input: ViewInput) -> TokenStream {
+    match expand_element(&input.world, &input.root) {
+        Ok(tokens) => tokens,
+        Err(err) => err.to_compile_error(),
+    }
+}
+
+fn expand_element(world: &Ident, element: &Element) -> syn::Result<TokenStream> {
+    let lib = lib();
+    let name = &element.name;
+    // recognised by name, the type is checked so another `Show` is an error rather than lazy
+    let expanded = match name.segments.last().map(|segment| segment.ident.to_string()).as_deref() {
+        Some("For") => expand_for(world, element)?,
+        Some("Show") => expand_component(world, element, true)?,
+        _ => return expand_component(world, element, false),
+    };
+    Ok(quote! {
+        {
+            #lib::ui::__view::control_flow::<#name>();
+            #expanded
+        }
+    })
+}
+
+fn expand_component(world: &Ident, element: &Element, lazy_children: bool) -> syn::Result<TokenStream> {
+    let lib = lib();
+    let name = &element.name;
+    let mut props = Vec::new();
+    let mut binds = Vec::new();
+    for (index, attr) in element.attrs.iter().enumerate() {
+        let value = &attr.value;
+        match attr.kind {
+            AttrKind::Prop if is_closure(value) => {
+                let field = &attr.name;
+                props.push(quote!(__props.#field = #lib::ui::__view::derived(&__props.#field, #value);));
+            }
+            AttrKind::Prop => {
+                let field = &attr.name;
+                props.push(quote!(__props.#field = ::core::convert::Into::into(#value);));
+            }
+            AttrKind::On => {
+                let setter = format_ident!("set_on_{}", attr.name, span = attr.name.span());
+                props.push(quote! {
+                    {
+                        #[allow(unused_imports)]
+                        use #lib::ui::__view::events::*;
+                        __props.#setter(::core::option::Option::Some(::std::boxed::Box::new(#value)));
+                    }
+                });
+            }
+            AttrKind::Bind => {
+                let field = &attr.name;
+                let binding = format_ident!("__bind_{}", index);
+                props.push(quote! {
+                    let #binding = #value;
+                    __props.#field = #lib::ui::__view::read_untracked(#world, &#binding);
+                });
+                binds.push(quote!(#lib::ui::__view::bind(#world, __entity, #binding);));
+            }
+        }
+    }
+    for child in &element.children {
+        let Child::Slot(slot, slot_children) = child else { continue; };
+        if lazy_children {
+            return Err(syn::Error::new_spanned(slot, "slots can only be passed to components that render their children once"));
+        }
+        let slot_children = expand_children(world, &slot_children.iter().collect::<Vec<_>>())?;
+        props.push(quote!(__props.#slot = #lib::ui::Slot::from(#slot_children);));
+    }
+    let children: Vec<&Child> = element.children.iter().filter(|child| !matches!(child, Child::Slot(..))).collect();
+    if lazy_children {
+        let children = expand_children(world, &children)?;
+        props.push(quote! {
+            __props.children = #lib::ui::Slot::new(move |#world: &mut #lib::ui::__view::World| #children);
+        });
+    }
+    let (take_children, mount_children) = if lazy_children || children.is_empty() {
+        (quote!(), quote!())
+    } else {
+        let children = expand_children(world, &children)?;
+        (
+            quote!(let __children = <#name as #lib::ui::ViewElement>::set_children(&mut __props, #lib::ui::Slot::from(#children));),
+            quote!(#lib::ui::__view::mount(#world, __entity, __children);),
+        )
+    };
+    // spanned so components with required props are reported at their name
+    let default_props = quote_spanned! {name.span()=>
+        {
+            use #lib::ui::__view::DefaultProps as _;
+            #name::default_props::<()>()
+        }
+    };
+    Ok(quote! {
+        {
+            let mut __props = #default_props;
+            #(#props)*
+            #take_children
+            let __entity = <#name as #lib::ui::ViewElement>::build(#world, __props);
+            #(#binds)*
+            #mount_children
+            __entity
+        }
+    })
+}
+
+fn expand_for(world: &Ident, element: &Element) -> syn::Result<TokenStream> {
+    let lib = lib();
+    let mut each = None;
+    let mut render = None;
+    for attr in &element.attrs {
+        match (&attr.kind, attr.name.to_string().as_str()) {
+            (AttrKind::Prop, "each") => each = Some(&attr.value),
+            (AttrKind::Prop, "render") => render = Some(&attr.value),
+            _ => return Err(syn::Error::new_spanned(&attr.name, "`For` only takes `each` and `render`")),
+        }
+    }
+    if !element.children.is_empty() {
+        return Err(syn::Error::new_spanned(&element.name, "`For` renders its items with `render`, it takes no children"));
+    }
+    let (Some(each), Some(render)) = (each, render) else {
+        return Err(syn::Error::new_spanned(&element.name, "`For` needs both `each` and `render`"));
+    };
+    Ok(quote!(#lib::ui::__view::for_each(#world, #each, #render)))
+}
+
+fn expand_children(world: &Ident, children: &[&Child]) -> syn::Result<TokenStream> {
+    let lib = lib();
+    let children = children
+        .iter()
+        .map(|child| match child {
+            Child::Element(element) => expand_element(world, element),
+            Child::Text(text) => Ok(quote!(#lib::ui::__view::text(#world, #text))),
+            Child::Block(block) => Ok(block.to_token_stream()),
+            Child::Slot(name, _) => Err(syn::Error::new_spanned(name, "slots go directly inside the component they fill")),
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
+    Ok(quote! {
+        {
+            let mut __children = ::std::vec::Vec::new();
+            #(#lib::ui::ViewChildren::push_into(#children, &mut __children);)*
+            __children
+        }
+    })
+}
@@ -0,0 +1,167 @@
+use std::sync::RwLock;
+
+use super::{boxed_accessor::{BoxedAccessorImpl, BoxedAccessorRef, BoxedAccessorRefInner, ErasedRef, ErasedValue}, Accessor, BoxedAccessor, HasFgrCtx, Memo};
+
+/// Combinators for turning any accessor into a derived `BoxedAccessor` that can be passed
+/// straight into component props.
+///
+/// `map`, `map_with_diff` and `zip` create a `Memo` in the current scope, so they need the
+/// context. `select` and `derive` are cheap and non-memoized: they are re-evaluated on every
+/// read and track whatever the source tracks.
+pub trait AccessorExt<CTX, A>: Accessor<CTX, A> + Clone + Send + Sync + 'static {
+    fn map<B, F>(&self, ctx: &mut CTX, map_fn: F) -> BoxedAccessor<CTX, B>
+    where
+        B: PartialEq + Send + Sync + 'static,
+        F: Fn(&A) -> B + Send + Sync + 'static;
+
+    fn map_with_diff<B, F, C>(&self, ctx: &mut CTX, map_fn: F, compare_fn: C) -> BoxedAccessor<CTX, B>
+    where
+        B: Send + Sync + 'static,
+        F: Fn(&A) -> B + Send + Sync + 'static,
+        C: FnMut(&B, &B) -> bool + Send + Sync + 'static;
+
+    fn zip<B, OTHER>(&self, ctx: &mut CTX, other: &OTHER) -> BoxedAccessor<CTX, (A, B)>
+    where
+        A: Clone + PartialEq,
+        B: Clone + PartialEq + Send + Sync + 'static,
+        OTHER: Accessor<CTX, B> + Clone + Send + Sync + 'static;
+
+    fn select<B, F>(&self, select_fn: F) -> BoxedAccessor<CTX, B>
+    where
+        Self: Into<BoxedAccessor<CTX, A>>,
+        B: 'static,
+        F: Fn(&A) -> &B + Send + Sync + 'static;
+
+    fn derive<B, F>(&self, derive_fn: F) -> BoxedAccessor<CTX, B>
+    where
+        B: Send + Sync + 'static,
+        F: Fn(&A) -> B + Send + Sync + 'static;
+}
+
+impl<CTX, A, S> AccessorExt<CTX, A> for S
+where
+    CTX: HasFgrCtx + 'static,
+    A: Send + Sync + 'static,
+    S: Accessor<CTX, A> + Clone + Send + Sync + 'static,
+{
+    fn map<B, F>(&self, ctx: &mut CTX, map_fn: F) -> BoxedAccessor<CTX, B>
+    where
+        B: PartialEq + Send + Sync + 'static,
+        F: Fn(&A) -> B + Send + Sync + 'static,
+    {
+        self.map_with_diff(ctx, map_fn, |a, b| a == b)
+    }
+
+    fn map_with_diff<B, F, C>(&self, ctx: &mut CTX, map_fn: F, compare_fn: C) -> BoxedAccessor<CTX, B>
+    where
+        B: Send + Sync + 'static,
+        F: Fn(&A) -> B + Send + Sync + 'static,
+        C: FnMut(&B, &B) -> bool + Send + Sync + 'static,
+    {
+        let source = self.clone();
+        Memo::new_with_diff(ctx, move |ctx| map_fn(&*source.value(ctx)), compare_fn).into()
+    }
+
+    fn zip<B, OTHER>(&self, ctx: &mut CTX, other: &OTHER) -> BoxedAccessor<CTX, (A, B)>
+    where
+        A: Clone + PartialEq,
+        B: Clone + PartialEq + Send + Sync + 'static,
+        OTHER: Accessor<CTX, B> + Clone + Send + Sync + 'static,
+    {
+        let source = self.clone();
+        let other = other.clone();
+        Memo::new(ctx, move |ctx| {
+            let a = source.value(ctx).clone();
+            let b = other.value(ctx).clone();
+            (a, b)
+        }).into()
+    }
+
+    fn select<B, F>(&self, select_fn: F) -> BoxedAccessor<CTX, B>
+    where
+        Self: Into<BoxedAccessor<CTX, A>>,
+        B: 'static,
+        F: Fn(&A) -> &B + Send + Sync + 'static,
+    {
+        BoxedAccessor::from_dyn(SelectAccessor {
+            source: self.clone().into(),
+            select_fn,
+        })
+    }
+
+    fn derive<B, F>(&self, derive_fn: F) -> BoxedAccessor<CTX, B>
+    where
+        B: Send + Sync + 'static,
+        F: Fn(&A) -> B + Send + Sync + 'static,
+    {
+        let source = self.clone();
+        BoxedAccessor::derived(move |ctx| derive_fn(&*source.value(ctx)))
+    }
+}
+
+impl<CTX: 'static, A: Send + Sync + 'static> BoxedAccessor<CTX, A> {
+    /// A non-memoized accessor that re-runs `derive_fn` on every read.
+    pub fn derived(derive_fn: impl Fn(&mut CTX) -> A + Send + Sync + 'static) -> Self {
+        BoxedAccessor::from_dyn(DerivedAccessor {
+            derive_fn,
+            selected: RwLock::new(None),
+        })
+    }
+}
+
+/// Holds the source's read with its type erased and projects from it on every deref, so a
+/// select of a select holds just the one read of the node at the root.
+struct SelectAccessor<CTX, A, F> {
+    source: BoxedAccessor<CTX, A>,
+    select_fn: F,
+}
+
+impl<CTX, A, B, F> BoxedAccessorImpl<CTX, B> for SelectAccessor<CTX, A, F>
+where
+    CTX: HasFgrCtx + 'static,
+    A: Send + Sync + 'static,
+    F: Fn(&A) -> &B + Send + Sync + 'static,
+{
+    fn value<'a>(&'a self, ctx: &mut CTX) -> BoxedAccessorRef<'a, CTX, B> {
+        BoxedAccessorRef::new(BoxedAccessorRefInner::Projected(self.source.erased_value(ctx), self))
+    }
+
+    fn erased_value<'a>(&'a self, ctx: &mut CTX) -> ErasedRef<'a> {
+        self.source.erased_value(ctx)
+    }
+
+    fn project<'a>(&'a self, source: &'a ErasedValue) -> &'a B {
+        (self.select_fn)(self.source.project(source))
+    }
+}
+
+struct DerivedAccessor<F, A> {
+    derive_fn: F,
+    /// The value of a read by a select, which borrows it from here until the read is dropped. A
+    /// select read while another one is alive gets its own boxed value instead.
+    selected: RwLock<Option<A>>,
+}
+
+impl<CTX, A, F> BoxedAccessorImpl<CTX, A> for DerivedAccessor<F, A>
+where
+    A: Send + Sync + 'static,
+    F: Fn(&mut CTX) -> A,
+{
+    fn value<'a>(&'a self, ctx: &mut CTX) -> BoxedAccessorRef<'a, CTX, A> {
+        BoxedAccessorRef::new(BoxedAccessorRefInner::Owned((self.derive_fn)(ctx)))
+    }
+
+    fn erased_value<'a>(&'a self, ctx: &mut CTX) -> ErasedRef<'a> {
+        let value = Some((self.derive_fn)(ctx));
+        let Ok(mut selected) = self.selected.try_write() else {
+            return ErasedRef::Owned(Box::new(value));
+        };
+        *selected = value;
+        drop(selected);
+        ErasedRef::slot(&self.selected)
+    }
+
+    fn project<'a>(&'a self, source: &'a ErasedValue) -> &'a A {
+        source.downcast_ref::<Option<A>>().unwrap().as_ref().unwrap()
+    }
+}
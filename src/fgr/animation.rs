@@ -0,0 +1,221 @@
+use std::time::Duration;
+
+use bevy::{color::{Color, LinearRgba}, math::Vec2, prelude::World, ui::Val};
+
+use super::{time::now, Accessor, FgrCtx, FgrExtensionMethods, Memo};
+
+// Animations are memos over a target accessor. While moving they also read the update flag
+// signal, so they are re-evaluated on every `fgr_update`; once settled they stop reading it and
+// drop out of the per frame work entirely until the target changes again.
+
+/// A value that can be tweened and sprung.
+pub trait Animatable: Clone + PartialEq + Send + Sync + 'static {
+    fn lerp(&self, other: &Self, t: f32) -> Self;
+
+    /// `self + other * scale`, or `None` when the two can not be combined (e.g. `Val::Px` and
+    /// `Val::Percent`), in which case springs jump straight to their target.
+    fn add_scaled(&self, other: &Self, scale: f32) -> Option<Self>;
+
+    fn distance(&self, other: &Self) -> f32;
+}
+
+impl Animatable for f32 {
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        self + (other - self) * t
+    }
+
+    fn add_scaled(&self, other: &Self, scale: f32) -> Option<Self> {
+        Some(self + other * scale)
+    }
+
+    fn distance(&self, other: &Self) -> f32 {
+        (self - other).abs()
+    }
+}
+
+impl Animatable for Vec2 {
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        Vec2::lerp(*self, *other, t)
+    }
+
+    fn add_scaled(&self, other: &Self, scale: f32) -> Option<Self> {
+        Some(*self + *other * scale)
+    }
+
+    fn distance(&self, other: &Self) -> f32 {
+        Vec2::distance(*self, *other)
+    }
+}
+
+/// Colors are animated in linear RGBA.
+impl Animatable for Color {
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        let a = self.to_linear();
+        let b = other.to_linear();
+        Color::LinearRgba(a * (1.0 - t) + b * t)
+    }
+
+    fn add_scaled(&self, other: &Self, scale: f32) -> Option<Self> {
+        Some(Color::LinearRgba(self.to_linear() + other.to_linear() * scale))
+    }
+
+    fn distance(&self, other: &Self) -> f32 {
+        let LinearRgba { red, green, blue, alpha } = self.to_linear() - other.to_linear();
+        red.abs().max(green.abs()).max(blue.abs()).max(alpha.abs())
+    }
+}
+
+/// Only values of the same unit are interpolated, mixed units switch at the end of a tween.
+impl Animatable for Val {
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        match val_pair(self, other) {
+            Some((a, b, unit)) => unit(a.lerp(&b, t)),
+            None if t >= 1.0 => *other,
+            None => *self,
+        }
+    }
+
+    fn add_scaled(&self, other: &Self, scale: f32) -> Option<Self> {
+        match (self, other) {
+            (Val::Auto, Val::Auto) => Some(Val::Auto),
+            _ => val_pair(self, other).map(|(a, b, unit)| unit(a + b * scale)),
+        }
+    }
+
+    fn distance(&self, other: &Self) -> f32 {
+        match (self, other) {
+            (Val::Auto, Val::Auto) => 0.0,
+            _ => val_pair(self, other).map(|(a, b, _)| (a - b).abs()).unwrap_or(f32::INFINITY),
+        }
+    }
+}
+
+type ValUnit = fn(f32) -> Val;
+
+fn val_pair(a: &Val, b: &Val) -> Option<(f32, f32, ValUnit)> {
+    match (*a, *b) {
+        (Val::Px(a), Val::Px(b)) => Some((a, b, Val::Px)),
+        (Val::Percent(a), Val::Percent(b)) => Some((a, b, Val::Percent)),
+        (Val::Vw(a), Val::Vw(b)) => Some((a, b, Val::Vw)),
+        (Val::Vh(a), Val::Vh(b)) => Some((a, b, Val::Vh)),
+        (Val::VMin(a), Val::VMin(b)) => Some((a, b, Val::VMin)),
+        (Val::VMax(a), Val::VMax(b)) => Some((a, b, Val::VMax)),
+        _ => None,
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Easing {
+    #[default]
+    Linear,
+    EaseIn,
+    EaseOut,
+    EaseInOut,
+}
+
+impl Easing {
+    pub fn apply(&self, t: f32) -> f32 {
+        match self {
+            Easing::Linear => t,
+            Easing::EaseIn => t * t * t,
+            Easing::EaseOut => 1.0 - (1.0 - t).powi(3),
+            Easing::EaseInOut => {
+                if t < 0.5 {
+                    4.0 * t * t * t
+                } else {
+                    1.0 - (-2.0 * t + 2.0).powi(3) / 2.0
+                }
+            }
+        }
+    }
+}
+
+/// Animates from the current value to every new value of `target` over `duration`. A change
+/// mid-animation starts the next tween from wherever the current one got to.
+pub fn tween<A: Animatable>(world: &mut World, target: impl Accessor<World, A> + Send + Sync + 'static, duration: Duration, easing: Easing) -> Memo<World, A> {
+    struct TweenState<A> {
+        from: A,
+        to: A,
+        /// `None` when settled.
+        start: Option<Duration>,
+    }
+    let frame = FgrCtx::update_flag_signal(world);
+    let init = world.fgr_untrack(|world| target.value(world).clone());
+    let mut state = TweenState {
+        from: init.clone(),
+        to: init.clone(),
+        start: None,
+    };
+    Memo::new_with_prev(world, init, move |world, prev| {
+        let target = target.value(world).clone();
+        let now = now(world);
+        if target != state.to {
+            state.from = prev.clone();
+            state.to = target;
+            state.start = Some(now);
+        }
+        let Some(start) = state.start else { return state.to.clone(); };
+        let t = if duration.is_zero() {
+            1.0
+        } else {
+            (now.saturating_sub(start).as_secs_f32() / duration.as_secs_f32()).min(1.0)
+        };
+        if t >= 1.0 {
+            state.start = None;
+            return state.to.clone();
+        }
+        let _ = *frame.value(world);
+        state.from.lerp(&state.to, easing.apply(t))
+    })
+}
+
+/// Animates towards `target` as a damped spring. The spring keeps its velocity when the target
+/// changes mid-flight.
+pub fn spring<A: Animatable>(world: &mut World, target: impl Accessor<World, A> + Send + Sync + 'static, stiffness: f32, damping: f32) -> Memo<World, A> {
+    const SETTLE_DISTANCE: f32 = 0.001;
+    // Larger frame gaps (e.g. a stall while loading) are treated as this long, so the
+    // integration stays stable.
+    const MAX_STEP: f32 = 1.0 / 30.0;
+    struct SpringState<A> {
+        position: A,
+        velocity: A,
+        /// `None` when settled.
+        last_time: Option<Duration>,
+    }
+    let frame = FgrCtx::update_flag_signal(world);
+    let init = world.fgr_untrack(|world| target.value(world).clone());
+    let mut state = SpringState {
+        position: init.clone(),
+        velocity: init.add_scaled(&init, -1.0).unwrap_or(init.clone()),
+        last_time: None,
+    };
+    Memo::new_with_prev(world, init, move |world, _prev| {
+        let target = target.value(world).clone();
+        let now = now(world);
+        let dt = state.last_time.map(|last_time| now.saturating_sub(last_time).as_secs_f32().min(MAX_STEP)).unwrap_or(0.0);
+        state.last_time = Some(now);
+        let step = target.add_scaled(&state.position, -1.0).and_then(|displacement| {
+            let zero = displacement.add_scaled(&displacement, -1.0)?;
+            let acceleration = zero.add_scaled(&displacement, stiffness)?.add_scaled(&state.velocity, -damping)?;
+            let velocity = state.velocity.add_scaled(&acceleration, dt)?;
+            let position = state.position.add_scaled(&velocity, dt)?;
+            Some((position, velocity, zero))
+        });
+        let Some((position, velocity, zero)) = step else {
+            state.position = target.clone();
+            state.velocity = target.add_scaled(&target, -1.0).unwrap_or(target.clone());
+            state.last_time = None;
+            return target;
+        };
+        if position.distance(&target) < SETTLE_DISTANCE && velocity.distance(&zero) < SETTLE_DISTANCE {
+            state.position = target.clone();
+            state.velocity = zero;
+            state.last_time = None;
+            return target;
+        }
+        state.position = position.clone();
+        state.velocity = velocity;
+        let _ = *frame.value(world);
+        position
+    })
+}
@@ -0,0 +1,206 @@
+use std::{any::Any, ops::Deref, sync::{Arc, RwLock, RwLockReadGuard}};
+
+use super::{Accessor, ConstAccessor, HasFgrCtx, Memo, MemoImpl, Signal, SignalImpl};
+
+/// A type-erased accessor, used for component props.
+///
+/// Signals, memos and constants are stored directly so reading them only takes the node's read
+/// lock, the same as calling `value` on the concrete type. Anything else (derived accessors from
+/// `AccessorExt`) goes through a vtable that hands back a guard. None of these reads allocate:
+/// a derived accessor hands back its value in the guard, and a select holds its source's read.
+/// The one exception is a select over a derived accessor read while another read of it is alive,
+/// which boxes its value.
+pub struct BoxedAccessor<CTX, A>(BoxedAccessorInner<CTX, A>);
+
+enum BoxedAccessorInner<CTX, A> {
+    Signal(Signal<CTX, A>),
+    Memo(Memo<CTX, A>),
+    Const(ConstAccessor<A>),
+    Dyn(Arc<dyn BoxedAccessorImpl<CTX, A> + Send + Sync>),
+}
+
+pub(super) trait BoxedAccessorImpl<CTX, A> {
+    fn value<'a>(&'a self, ctx: &mut CTX) -> BoxedAccessorRef<'a, CTX, A>;
+
+    /// The same read with the guard's type erased, for `select`s over this accessor.
+    fn erased_value<'a>(&'a self, ctx: &mut CTX) -> ErasedRef<'a>;
+
+    /// Finds the value in what `erased_value` returned.
+    fn project<'a>(&'a self, source: &'a ErasedValue) -> &'a A;
+}
+
+pub(super) type ErasedValue = dyn Any + Send + Sync;
+
+/// A read of whatever the node at the root of a chain of `select`s stores, with its type erased,
+/// so a select can hold on to it whatever the type of its source.
+pub(super) enum ErasedRef<'a> {
+    Lock(RwLockReadGuard<'a, ErasedValue>),
+    Ref(&'a ErasedValue),
+    Owned(Box<ErasedValue>),
+    Slot(SlotRef<'a>),
+}
+
+impl<'a> ErasedRef<'a> {
+    pub(super) fn lock<T: Send + Sync + 'static>(lock: &'a RwLock<T>) -> Self {
+        let lock: &'a RwLock<ErasedValue> = lock;
+        ErasedRef::Lock(lock.read().unwrap())
+    }
+
+    /// Reads the value in `slot`, which is emptied once the read is dropped.
+    pub(super) fn slot<T: Send + Sync + 'static>(slot: &'a RwLock<Option<T>>) -> Self {
+        let lock: &'a RwLock<ErasedValue> = slot;
+        ErasedRef::Slot(SlotRef { guard: Some(lock.read().unwrap()), slot })
+    }
+}
+
+/// A read of a value kept in a slot only for as long as it is read, see `ErasedRef::slot`.
+pub(super) struct SlotRef<'a> {
+    guard: Option<RwLockReadGuard<'a, ErasedValue>>,
+    slot: &'a (dyn ClearSlot + Send + Sync),
+}
+
+impl Drop for SlotRef<'_> {
+    fn drop(&mut self) {
+        self.guard = None;
+        self.slot.clear();
+    }
+}
+
+trait ClearSlot {
+    fn clear(&self);
+}
+
+impl<T> ClearSlot for RwLock<Option<T>> {
+    fn clear(&self) {
+        if let Ok(mut slot) = self.try_write() {
+            *slot = None;
+        }
+    }
+}
+
+impl<'a> Deref for ErasedRef<'a> {
+    type Target = ErasedValue;
+
+    fn deref(&self) -> &Self::Target {
+        match self {
+            ErasedRef::Lock(guard) => &**guard,
+            ErasedRef::Ref(value) => *value,
+            ErasedRef::Owned(value) => &**value,
+            ErasedRef::Slot(slot) => &**slot.guard.as_ref().unwrap(),
+        }
+    }
+}
+
+/// Guard returned when reading a `BoxedAccessor`. Holds the source node's read lock, so do not
+/// keep it across updates of that node.
+pub struct BoxedAccessorRef<'a, CTX, A>(BoxedAccessorRefInner<'a, CTX, A>);
+
+pub(super) enum BoxedAccessorRefInner<'a, CTX, A> {
+    Signal(RwLockReadGuard<'a, SignalImpl<CTX, A>>),
+    Memo(RwLockReadGuard<'a, MemoImpl<CTX, A>>),
+    Ref(&'a A),
+    Owned(A),
+    Projected(ErasedRef<'a>, &'a (dyn BoxedAccessorImpl<CTX, A> + Send + Sync)),
+}
+
+impl<'a, CTX, A> BoxedAccessorRef<'a, CTX, A> {
+    pub(super) fn new(inner: BoxedAccessorRefInner<'a, CTX, A>) -> Self {
+        Self(inner)
+    }
+}
+
+impl<'a, CTX, A> Deref for BoxedAccessorRef<'a, CTX, A> {
+    type Target = A;
+
+    fn deref(&self) -> &Self::Target {
+        match &self.0 {
+            BoxedAccessorRefInner::Signal(impl_) => &impl_.value,
+            BoxedAccessorRefInner::Memo(impl_) => impl_.value.as_ref().unwrap(),
+            BoxedAccessorRefInner::Ref(value) => value,
+            BoxedAccessorRefInner::Owned(value) => value,
+            BoxedAccessorRefInner::Projected(source, impl_) => impl_.project(&**source),
+        }
+    }
+}
+
+impl<CTX, A> BoxedAccessor<CTX, A> {
+    pub(super) fn from_dyn(impl_: impl BoxedAccessorImpl<CTX, A> + Send + Sync + 'static) -> Self {
+        Self(BoxedAccessorInner::Dyn(Arc::new(impl_)))
+    }
+}
+
+impl<CTX: HasFgrCtx + 'static, A: Send + Sync + 'static> BoxedAccessor<CTX, A> {
+    pub fn value<'a>(&'a self, ctx: &mut CTX) -> BoxedAccessorRef<'a, CTX, A> {
+        match &self.0 {
+            BoxedAccessorInner::Signal(signal) => BoxedAccessorRef(BoxedAccessorRefInner::Signal(signal.read(ctx))),
+            BoxedAccessorInner::Memo(memo) => BoxedAccessorRef(BoxedAccessorRefInner::Memo(memo.read(ctx))),
+            BoxedAccessorInner::Const(value) => BoxedAccessorRef(BoxedAccessorRefInner::Ref(&*value.0)),
+            BoxedAccessorInner::Dyn(impl_) => impl_.value(ctx),
+        }
+    }
+
+    pub fn with_value<R, CALLBACK: FnOnce(&A) -> R>(&self, ctx: &mut CTX, callback: CALLBACK) -> R {
+        callback(&self.value(ctx))
+    }
+
+    pub(super) fn erased_value<'a>(&'a self, ctx: &mut CTX) -> ErasedRef<'a> {
+        match &self.0 {
+            BoxedAccessorInner::Signal(signal) => {
+                signal.track(ctx);
+                ErasedRef::lock(&*signal.impl_)
+            }
+            BoxedAccessorInner::Memo(memo) => {
+                memo.track(ctx);
+                ErasedRef::lock(&*memo.impl_)
+            }
+            BoxedAccessorInner::Const(value) => ErasedRef::Ref(&*value.0),
+            BoxedAccessorInner::Dyn(impl_) => impl_.erased_value(ctx),
+        }
+    }
+
+    /// Finds the value in what `erased_value` returned. Only the root of a chain of selects
+    /// downcasts, every select above it projects from its source's typed value.
+    pub(super) fn project<'a>(&'a self, source: &'a ErasedValue) -> &'a A {
+        match &self.0 {
+            BoxedAccessorInner::Signal(_) => &source.downcast_ref::<SignalImpl<CTX, A>>().unwrap().value,
+            BoxedAccessorInner::Memo(_) => source.downcast_ref::<MemoImpl<CTX, A>>().unwrap().value.as_ref().unwrap(),
+            BoxedAccessorInner::Const(_) => source.downcast_ref::<A>().unwrap(),
+            BoxedAccessorInner::Dyn(impl_) => impl_.project(source),
+        }
+    }
+}
+
+impl<CTX, A> Clone for BoxedAccessor<CTX, A> {
+    fn clone(&self) -> Self {
+        Self(match &self.0 {
+            BoxedAccessorInner::Signal(signal) => BoxedAccessorInner::Signal(signal.clone()),
+            BoxedAccessorInner::Memo(memo) => BoxedAccessorInner::Memo(memo.clone()),
+            BoxedAccessorInner::Const(value) => BoxedAccessorInner::Const(value.clone()),
+            BoxedAccessorInner::Dyn(impl_) => BoxedAccessorInner::Dyn(Arc::clone(impl_)),
+        })
+    }
+}
+
+impl<CTX, A> From<Signal<CTX, A>> for BoxedAccessor<CTX, A> {
+    fn from(signal: Signal<CTX, A>) -> Self {
+        Self(BoxedAccessorInner::Signal(signal))
+    }
+}
+
+impl<CTX, A> From<Memo<CTX, A>> for BoxedAccessor<CTX, A> {
+    fn from(memo: Memo<CTX, A>) -> Self {
+        Self(BoxedAccessorInner::Memo(memo))
+    }
+}
+
+impl<CTX, A> From<ConstAccessor<A>> for BoxedAccessor<CTX, A> {
+    fn from(value: ConstAccessor<A>) -> Self {
+        Self(BoxedAccessorInner::Const(value))
+    }
+}
+
+impl<CTX: HasFgrCtx + 'static, A: Send + Sync + 'static> Accessor<CTX, A> for BoxedAccessor<CTX, A> {
+    fn value<'a>(&'a self, ctx: &mut CTX) -> impl Deref<Target=A> + 'a {
+        self.value(ctx)
+    }
+}
@@ -0,0 +1,215 @@
+use std::{any::TypeId, fmt, ops::{Deref, DerefMut}, sync::RwLockReadGuard};
+
+use bevy::{app::{App, Plugin, Update}, ecs::{schedule::{InternedScheduleLabel, ScheduleLabel}, system::Commands, world::DeferredWorld}, prelude::World};
+
+use super::{FgrCtx, FgrExtensionMethods, FgrLabelExtensionMethods, HasFgrCtx, Memo, MemoImpl, Signal, SignalImpl};
+
+/// Returned by `HasFgrCtx::try_fgr_ctx` when the context has no reactive graph.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FgrCtxError {
+    Missing {
+        context: &'static str,
+        hint: &'static str,
+    },
+}
+
+impl fmt::Display for FgrCtxError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FgrCtxError::Missing { context, hint } => write!(f, "no FgrCtx found for {}. {}", context, hint),
+        }
+    }
+}
+
+impl std::error::Error for FgrCtxError {}
+
+/// Inserts `FgrCtx<World>` and runs `fgr_update` once per run of `schedule` (`Update` by
+/// default). Can be added to a sub-app to give its world a graph of its own, e.g.
+/// `render_app.add_plugins(FgrPlugin::in_schedule(ExtractSchedule))`, or once per label for
+/// independent graphs in the same world, see `labelled`.
+pub struct FgrPlugin {
+    pub schedule: InternedScheduleLabel,
+    label: TypeId,
+    install: fn(&mut App, InternedScheduleLabel),
+}
+
+impl Default for FgrPlugin {
+    fn default() -> Self {
+        Self::in_schedule(Update)
+    }
+}
+
+impl FgrPlugin {
+    pub fn in_schedule(schedule: impl ScheduleLabel) -> Self {
+        Self::labelled::<()>(schedule)
+    }
+
+    /// Inserts `FgrCtx<World, L>` instead, updated by activating it with `fgr_in_ctx`.
+    pub fn labelled<L: Send + Sync + 'static>(schedule: impl ScheduleLabel) -> Self {
+        Self {
+            schedule: schedule.intern(),
+            label: TypeId::of::<L>(),
+            install: |app, schedule| {
+                if !app.world().contains_resource::<FgrCtx<World, L>>() {
+                    app.insert_resource(FgrCtx::<World, L>::labelled());
+                }
+                app.add_systems(schedule, |world: &mut World| world.fgr_in_ctx::<L, _, _>(|world| world.fgr_update()));
+            },
+        }
+    }
+
+    /// Whether `app` has an unlabelled `FgrPlugin`, for plugins that add one when it is missing.
+    /// Unlike `App::is_plugin_added` labelled ones do not count, and unlike checking for the
+    /// `FgrCtx<World>` resource a graph inserted by hand does not either, as it is not updated.
+    pub fn is_added(app: &App) -> bool {
+        app.get_added_plugins::<FgrPlugin>().iter().any(|plugin| plugin.label == TypeId::of::<()>())
+    }
+
+    /// Adds an unlabelled `FgrPlugin` to `app` unless it has one, for plugins that need the
+    /// `World` graph to be updated.
+    pub fn ensure(app: &mut App) {
+        if !Self::is_added(app) {
+            app.add_plugins(FgrPlugin::default());
+        }
+    }
+}
+
+impl Plugin for FgrPlugin {
+    fn build(&self, app: &mut App) {
+        (self.install)(app, self.schedule);
+    }
+
+    fn is_unique(&self) -> bool {
+        false
+    }
+}
+
+/// A reactive context over plain data, for graphs that have nothing to do with a `World`.
+pub struct FgrDataCtx<T> {
+    data: T,
+    fgr_ctx: FgrCtx<FgrDataCtx<T>>,
+}
+
+impl<T: 'static> FgrDataCtx<T> {
+    pub fn new(data: T) -> Self {
+        Self {
+            data,
+            fgr_ctx: FgrCtx::new(),
+        }
+    }
+
+    /// Gives back the data. Any nodes still alive can not be used anymore.
+    pub fn into_inner(self) -> T {
+        self.data
+    }
+}
+
+impl<T> Deref for FgrDataCtx<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.data
+    }
+}
+
+impl<T> DerefMut for FgrDataCtx<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.data
+    }
+}
+
+impl<T> HasFgrCtx for FgrDataCtx<T> {
+    fn fgr_ctx<'a>(&'a mut self) -> impl DerefMut<Target=FgrCtx<Self>> + 'a {
+        &mut self.fgr_ctx
+    }
+}
+
+/// Reaching the `World`'s graph from places that only get deferred access, such as component
+/// hooks and observers.
+///
+/// `DeferredWorld` does not implement `HasFgrCtx`: nodes hold on to their context type, so it
+/// could not share nodes with the `World`'s graph, and effects run with the `&mut World` it does
+/// not give. Instead signals and memos of the `World`'s graph are read with `value_deferred` and
+/// signals written with `update_value_deferred`, which take effect right away while their
+/// dependents update when commands are applied, for hooks and observers right after they return.
+/// Anything else, such as creating nodes, is queued with `fgr_queue` and runs against the `World`
+/// in a batch at the same point. If the world has no graph by then the callback is skipped with a
+/// warning rather than a panic.
+pub trait FgrDeferredExtensionMethods {
+    fn fgr_queue<CALLBACK: FnOnce(&mut World) + Send + 'static>(&mut self, callback: CALLBACK);
+}
+
+impl FgrDeferredExtensionMethods for Commands<'_, '_> {
+    fn fgr_queue<CALLBACK: FnOnce(&mut World) + Send + 'static>(&mut self, callback: CALLBACK) {
+        self.add(move |world: &mut World| {
+            if let Err(err) = world.try_fgr_ctx().map(drop) {
+                bevy::log::warn!("skipping queued reactive callback: {}", err);
+                return;
+            }
+            world.fgr_batch(callback);
+        });
+    }
+}
+
+impl FgrDeferredExtensionMethods for DeferredWorld<'_> {
+    fn fgr_queue<CALLBACK: FnOnce(&mut World) + Send + 'static>(&mut self, callback: CALLBACK) {
+        self.commands().fgr_queue(callback);
+    }
+}
+
+/// Panics like `FgrCtx::assert_own_node` if `graph` is not the world's active graph. A world
+/// without a graph has no nodes of its own to tell apart.
+fn assert_active_graph(world: &DeferredWorld, graph: u64, access: &str) {
+    if let Some(fgr_ctx) = world.get_resource::<FgrCtx<World>>() {
+        fgr_ctx.assert_own_node(graph, access);
+    }
+}
+
+/// A value behind a read guard of a node.
+struct NodeValue<'a, T, A> {
+    guard: RwLockReadGuard<'a, T>,
+    value: fn(&T) -> &A,
+}
+
+impl<T, A> Deref for NodeValue<'_, T, A> {
+    type Target = A;
+
+    fn deref(&self) -> &A {
+        (self.value)(&self.guard)
+    }
+}
+
+impl<A: Send + Sync + 'static> Signal<World, A> {
+    /// `value` for component hooks and observers. Reads there are not tracked.
+    pub fn value_deferred<'a>(&'a self, world: &DeferredWorld) -> impl Deref<Target=A> + 'a {
+        let guard = self.impl_.read().unwrap();
+        assert_active_graph(world, guard.node_data.graph, "read");
+        NodeValue { guard, value: |impl_: &SignalImpl<World, A>| &impl_.value }
+    }
+
+    /// `update_value` for component hooks and observers. The new value can be read right away,
+    /// the signal's dependents update when the world's commands are applied.
+    pub fn update_value_deferred<CALLBACK: FnOnce(&mut A)>(&mut self, world: &mut DeferredWorld, callback: CALLBACK) {
+        let recording = world.get_resource::<FgrCtx<World>>().is_some_and(|fgr_ctx| fgr_ctx.recording.is_some());
+        let recorded = {
+            let mut impl_ = self.impl_.write().unwrap();
+            assert_active_graph(world, impl_.node_data.graph, "written");
+            let snapshot = impl_.snapshot.filter(|_| recording);
+            let old_value = snapshot.map(|snapshot| snapshot(&impl_.value));
+            callback(&mut impl_.value);
+            old_value.zip(snapshot).map(|(old_value, snapshot)| (old_value, snapshot(&impl_.value), snapshot))
+        };
+        let mut signal = self.clone();
+        world.commands().add(move |world: &mut World| signal.notify(world, recorded));
+    }
+}
+
+impl<A: Send + Sync + 'static> Memo<World, A> {
+    /// `value` for component hooks and observers. Reads there are not tracked, and do not see
+    /// signals written with `update_value_deferred` until the world's commands are applied.
+    pub fn value_deferred<'a>(&'a self, world: &DeferredWorld) -> impl Deref<Target=A> + 'a {
+        let guard = self.impl_.read().unwrap();
+        assert_active_graph(world, guard.node_data.graph, "read");
+        NodeValue { guard, value: |impl_: &MemoImpl<World, A>| impl_.value.as_ref().unwrap() }
+    }
+}
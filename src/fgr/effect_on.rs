@@ -0,0 +1,84 @@
+use super::{Accessor, BoxedAccessor, ConstAccessor, FgrCtx, HasFgrCtx, MaybeReactive, Memo, Signal};
+
+/// The explicit dependency list of an effect created with `fgr_create_effect_on`.
+///
+/// Implemented for single accessors, `Vec`s of them and tuples of up to four. Reading the deps
+/// is the only tracked part of such an effect.
+pub trait EffectDeps<CTX> {
+    type Value;
+
+    fn read(&self, ctx: &mut CTX) -> Self::Value;
+}
+
+macro_rules! impl_effect_deps_for_accessor {
+    ($($accessor:ident),*) => {
+        $(
+            impl<CTX: HasFgrCtx + 'static, A: Clone + Send + Sync + 'static> EffectDeps<CTX> for $accessor<CTX, A> {
+                type Value = A;
+
+                fn read(&self, ctx: &mut CTX) -> A {
+                    Accessor::<CTX, A>::value(self, ctx).clone()
+                }
+            }
+        )*
+    };
+}
+
+impl_effect_deps_for_accessor!(Signal, Memo, BoxedAccessor, MaybeReactive);
+
+impl<CTX, A: Clone> EffectDeps<CTX> for ConstAccessor<A> {
+    type Value = A;
+
+    fn read(&self, ctx: &mut CTX) -> A {
+        Accessor::<CTX, A>::value(self, ctx).clone()
+    }
+}
+
+impl<CTX, D: EffectDeps<CTX>> EffectDeps<CTX> for Vec<D> {
+    type Value = Vec<D::Value>;
+
+    fn read(&self, ctx: &mut CTX) -> Self::Value {
+        self.iter().map(|dep| dep.read(ctx)).collect()
+    }
+}
+
+macro_rules! impl_effect_deps_for_tuple {
+    ($(($($dep:ident),*)),*) => {
+        $(
+            impl<CTX, $($dep: EffectDeps<CTX>),*> EffectDeps<CTX> for ($($dep,)*) {
+                type Value = ($($dep::Value,)*);
+
+                #[allow(non_snake_case)]
+                fn read(&self, ctx: &mut CTX) -> Self::Value {
+                    let ($($dep,)*) = self;
+                    ($($dep.read(ctx),)*)
+                }
+            }
+        )*
+    };
+}
+
+impl_effect_deps_for_tuple!((D1), (D1, D2), (D1, D2, D3), (D1, D2, D3, D4));
+
+impl<CTX: HasFgrCtx + 'static> FgrCtx<CTX> {
+    /// Creates an effect that only subscribes to `deps`. The callback runs untracked and receives
+    /// the new value of the deps and the value from its previous run. With `defer` the callback
+    /// is skipped on the initial run and first called when a dependency changes.
+    pub fn create_effect_on<DEPS, CALLBACK>(ctx: &mut CTX, deps: DEPS, defer: bool, mut callback: CALLBACK)
+    where
+        DEPS: EffectDeps<CTX> + Send + Sync + 'static,
+        DEPS::Value: Send + Sync + 'static,
+        CALLBACK: FnMut(&mut CTX, &DEPS::Value, Option<&DEPS::Value>) + Send + Sync + 'static,
+    {
+        let mut prev_value: Option<DEPS::Value> = None;
+        let mut skip = defer;
+        FgrCtx::create_effect(ctx, move |ctx: &mut CTX| {
+            let value = deps.read(ctx);
+            if !skip {
+                FgrCtx::untrack(ctx, |ctx| callback(ctx, &value, prev_value.as_ref()));
+            }
+            skip = false;
+            prev_value = Some(value);
+        });
+    }
+}
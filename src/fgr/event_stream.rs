@@ -0,0 +1,143 @@
+use std::sync::{Arc, Mutex, RwLock};
+
+use bevy::{ecs::event::{Event, Events}, prelude::World};
+
+use super::{BoxedAccessor, FgrExtensionMethods, HasFgrCtx, Signal};
+
+type EventHandler<CTX, E> = Arc<Mutex<Box<dyn FnMut(&mut CTX, &E) + Send + Sync>>>;
+
+/// A push based stream of events.
+///
+/// Unlike a signal an event stream has no current value, every emitted event is handed to the
+/// handlers registered with `on_event` exactly once. Handlers, and streams derived with `filter`
+/// and `map`, are removed again when the scope they were registered in is disposed.
+pub struct EventStream<CTX, E> {
+    handlers: Arc<RwLock<EventHandlers<CTX, E>>>,
+}
+
+impl<CTX, E> Clone for EventStream<CTX, E> {
+    fn clone(&self) -> Self {
+        Self {
+            handlers: Arc::clone(&self.handlers),
+        }
+    }
+}
+
+struct EventHandlers<CTX, E> {
+    next_id: u64,
+    handlers: Vec<(u64, EventHandler<CTX, E>)>,
+}
+
+impl<CTX: HasFgrCtx + 'static, E: Send + Sync + 'static> Default for EventStream<CTX, E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<CTX: HasFgrCtx + 'static, E: Send + Sync + 'static> EventStream<CTX, E> {
+    pub fn new() -> Self {
+        Self {
+            handlers: Arc::new(RwLock::new(EventHandlers {
+                next_id: 0,
+                handlers: Vec::new(),
+            })),
+        }
+    }
+
+    /// Hands `event` to every handler. The handlers run untracked and in a single batch.
+    pub fn emit(&self, ctx: &mut CTX, event: &E) {
+        let handlers: Vec<EventHandler<CTX, E>> = self.handlers.read().unwrap()
+            .handlers
+            .iter()
+            .map(|(_, handler)| Arc::clone(handler))
+            .collect();
+        if handlers.is_empty() {
+            return;
+        }
+        ctx.fgr_batch(|ctx| {
+            ctx.fgr_untrack(|ctx| {
+                for handler in handlers {
+                    (handler.lock().unwrap())(ctx, event);
+                }
+            });
+        });
+    }
+
+    /// Calls `handler` for every event until the current scope is disposed.
+    pub fn on_event(&self, ctx: &mut CTX, handler: impl FnMut(&mut CTX, &E) + Send + Sync + 'static) {
+        let id = {
+            let mut handlers = self.handlers.write().unwrap();
+            let id = handlers.next_id;
+            handlers.next_id += 1;
+            handlers.handlers.push((id, Arc::new(Mutex::new(Box::new(handler)))));
+            id
+        };
+        let handlers = Arc::clone(&self.handlers);
+        ctx.fgr_on_cleanup(move |_ctx| {
+            handlers.write().unwrap().handlers.retain(|(id2, _)| *id2 != id);
+        });
+    }
+
+    pub fn filter(&self, ctx: &mut CTX, predicate: impl Fn(&E) -> bool + Send + Sync + 'static) -> EventStream<CTX, E>
+    where E: Clone
+    {
+        self.filter_map(ctx, move |event| predicate(event).then(|| event.clone()))
+    }
+
+    pub fn map<B: Send + Sync + 'static>(&self, ctx: &mut CTX, f: impl Fn(&E) -> B + Send + Sync + 'static) -> EventStream<CTX, B> {
+        self.filter_map(ctx, move |event| Some(f(event)))
+    }
+
+    pub fn filter_map<B: Send + Sync + 'static>(&self, ctx: &mut CTX, f: impl Fn(&E) -> Option<B> + Send + Sync + 'static) -> EventStream<CTX, B> {
+        let output = EventStream::new();
+        self.on_event(ctx, {
+            let output = output.clone();
+            move |ctx, event| {
+                if let Some(event) = f(event) {
+                    output.emit(ctx, &event);
+                }
+            }
+        });
+        output
+    }
+
+    /// The latest event as state, `init` until the first one arrives.
+    pub fn hold(&self, ctx: &mut CTX, init: E) -> BoxedAccessor<CTX, E>
+    where E: Clone
+    {
+        let latest = Signal::new(ctx, init);
+        self.on_event(ctx, {
+            let mut latest = latest.clone();
+            move |ctx, event| latest.update_value(ctx, |x| *x = event.clone())
+        });
+        latest.into()
+    }
+}
+
+impl<E: Event + Clone> EventStream<World, E> {
+    /// Sends every event of this stream as a Bevy event.
+    pub fn send_as_events(&self, world: &mut World) {
+        self.on_event(world, |world, event| {
+            world.send_event(event.clone());
+        });
+    }
+}
+
+/// A stream of the Bevy events `E` sent from now on, read on every `fgr_update`. The reader is
+/// dropped with the current scope.
+pub fn use_event<E: Event + Clone>(world: &mut World) -> EventStream<World, E> {
+    let stream = EventStream::new();
+    let mut reader = world.get_resource::<Events<E>>().map(|events| events.get_reader_current());
+    world.fgr_on_update({
+        let stream = stream.clone();
+        move |world| {
+            let Some(events) = world.get_resource::<Events<E>>() else { return; };
+            let reader = reader.get_or_insert_with(|| events.get_reader());
+            let events: Vec<E> = reader.read(events).cloned().collect();
+            for event in &events {
+                stream.emit(world, event);
+            }
+        }
+    });
+    stream
+}
@@ -0,0 +1,337 @@
+use std::{any::Any, sync::{Arc, RwLock}, time::{Duration, Instant}};
+
+use bevy::prelude::Resource;
+
+use super::{AccessorExt, BoxedAccessor, FgrExtensionMethods, HasFgrCtx, Signal};
+
+/// Undo/redo history for signal writes.
+///
+/// Every write made inside `transaction` is recorded on the `FgrCtx`, whether it goes through
+/// `Signal::update_value` directly or through a component, and a transaction is undone or redone
+/// as a whole. Only signals created with `Signal::new_recorded` are recorded, such as the
+/// contents of a `TextBox` and the state of a `CheckBox`. `ReactiveVec` and `ReactiveMap` edits
+/// are recorded as their patches together with the inverse patches, which undo applies. Writes made by effects while the transaction's batch settles are not
+/// recorded, undo re-derives them. Transactions started with `transaction_merging` are folded
+/// into the previous one if it has the same merge key and happened within the merge window, so
+/// e.g. typing a word becomes a single undo step.
+#[derive(Resource)]
+pub struct History<CTX> {
+    state: Arc<RwLock<HistoryState<CTX>>>,
+    undo_count: Signal<CTX, usize>,
+    redo_count: Signal<CTX, usize>,
+}
+
+impl<CTX> Clone for History<CTX> {
+    fn clone(&self) -> Self {
+        Self {
+            state: Arc::clone(&self.state),
+            undo_count: self.undo_count.clone(),
+            redo_count: self.redo_count.clone(),
+        }
+    }
+}
+
+struct HistoryState<CTX> {
+    undo_stack: Vec<HistoryEntry<CTX>>,
+    redo_stack: Vec<HistoryEntry<CTX>>,
+    merge_window: Duration,
+}
+
+struct HistoryEntry<CTX> {
+    label: String,
+    merge_key: Option<String>,
+    time: Instant,
+    writes: RecordedWrites<CTX>,
+}
+
+impl<CTX: HasFgrCtx + 'static> HistoryEntry<CTX> {
+    fn merge(&mut self, newer: HistoryEntry<CTX>) {
+        for write in newer.writes {
+            let existing = self.writes.iter_mut().find(|existing| existing.node_id() == write.node_id());
+            match existing {
+                Some(existing) => existing.merge(write),
+                None => self.writes.push(write),
+            }
+        }
+        self.time = newer.time;
+    }
+}
+
+pub(super) trait RecordedWrite<CTX> {
+    fn node_id(&self) -> u64;
+    fn undo(&self, ctx: &mut CTX);
+    fn redo(&self, ctx: &mut CTX);
+    /// Takes over the new value of a later write to the same node.
+    fn merge(&mut self, newer: Box<dyn RecordedWrite<CTX> + Send + Sync>);
+    fn into_any(self: Box<Self>) -> Box<dyn Any>;
+}
+
+pub(super) type RecordedWrites<CTX> = Vec<Box<dyn RecordedWrite<CTX> + Send + Sync>>;
+
+/// A write replacing a node's value, undone and redone by writing the old or new value back.
+struct ValueWrite<CTX, A> {
+    node_id: u64,
+    old_value: A,
+    new_value: A,
+    snapshot: fn(&A) -> A,
+    write: WriteFn<CTX, A>,
+}
+
+type WriteFn<CTX, A> = Arc<dyn Fn(&mut CTX, A) + Send + Sync>;
+
+impl<CTX: 'static, A: Send + Sync + 'static> RecordedWrite<CTX> for ValueWrite<CTX, A> {
+    fn node_id(&self) -> u64 {
+        self.node_id
+    }
+
+    fn undo(&self, ctx: &mut CTX) {
+        (self.write)(ctx, (self.snapshot)(&self.old_value));
+    }
+
+    fn redo(&self, ctx: &mut CTX) {
+        (self.write)(ctx, (self.snapshot)(&self.new_value));
+    }
+
+    fn merge(&mut self, newer: Box<dyn RecordedWrite<CTX> + Send + Sync>) {
+        if let Ok(newer) = newer.into_any().downcast::<ValueWrite<CTX, A>>() {
+            self.new_value = newer.new_value;
+        }
+    }
+
+    fn into_any(self: Box<Self>) -> Box<dyn Any> {
+        self
+    }
+}
+
+/// Edits of a collection as patches, undone by applying the inverse patches and redone by applying
+/// the patches again.
+struct PatchWrite<CTX, P> {
+    node_id: u64,
+    patches: Vec<P>,
+    /// The inverse of `patches`, in the order undo applies them.
+    inverse: Vec<P>,
+    apply: ApplyFn<CTX, P>,
+}
+
+type ApplyFn<CTX, P> = Arc<dyn Fn(&mut CTX, Vec<P>) + Send + Sync>;
+
+impl<CTX: 'static, P: Clone + Send + Sync + 'static> RecordedWrite<CTX> for PatchWrite<CTX, P> {
+    fn node_id(&self) -> u64 {
+        self.node_id
+    }
+
+    fn undo(&self, ctx: &mut CTX) {
+        (self.apply)(ctx, self.inverse.clone());
+    }
+
+    fn redo(&self, ctx: &mut CTX) {
+        (self.apply)(ctx, self.patches.clone());
+    }
+
+    fn merge(&mut self, newer: Box<dyn RecordedWrite<CTX> + Send + Sync>) {
+        if let Ok(newer) = newer.into_any().downcast::<PatchWrite<CTX, P>>() {
+            let PatchWrite { patches, mut inverse, .. } = *newer;
+            self.patches.extend(patches);
+            // the newer edits are undone first
+            inverse.append(&mut self.inverse);
+            self.inverse = inverse;
+        }
+    }
+
+    fn into_any(self: Box<Self>) -> Box<dyn Any> {
+        self
+    }
+}
+
+/// Whether a history transaction is recording the writes made on `ctx`.
+pub(super) fn is_recording<CTX: HasFgrCtx>(ctx: &mut CTX) -> bool {
+    ctx.fgr_ctx().recording.is_some()
+}
+
+/// Records a write of the node `node_id` from `old_value` to `new_value` in the active history
+/// transaction, if there is one. `write` puts a value back on undo and redo.
+pub(super) fn record_write<CTX: HasFgrCtx + 'static, A: Send + Sync + 'static>(
+    ctx: &mut CTX,
+    node_id: u64,
+    old_value: A,
+    new_value: A,
+    snapshot: fn(&A) -> A,
+    write: impl Fn(&mut CTX, A) + Send + Sync + 'static,
+) {
+    let Some(recording) = &mut ctx.fgr_ctx().recording else { return; };
+    let write = Box::new(ValueWrite {
+        node_id,
+        old_value,
+        new_value,
+        snapshot,
+        write: Arc::new(write),
+    });
+    // a node written more than once is restored once, from before the first write
+    match recording.iter_mut().find(|existing| existing.node_id() == node_id) {
+        Some(existing) => existing.merge(write),
+        None => recording.push(write),
+    }
+}
+
+/// Records edits of the collection `node_id` in the active history transaction, if there is one,
+/// as `patches` and the `inverse` patches that revert them. `apply` applies patches on undo and
+/// redo.
+pub(super) fn record_patches<CTX: HasFgrCtx + 'static, P: Clone + Send + Sync + 'static>(
+    ctx: &mut CTX,
+    node_id: u64,
+    patches: Vec<P>,
+    inverse: Vec<P>,
+    apply: impl Fn(&mut CTX, Vec<P>) + Send + Sync + 'static,
+) {
+    let Some(recording) = &mut ctx.fgr_ctx().recording else { return; };
+    let write = Box::new(PatchWrite {
+        node_id,
+        patches,
+        inverse,
+        apply: Arc::new(apply),
+    });
+    match recording.iter_mut().find(|existing| existing.node_id() == node_id) {
+        Some(existing) => existing.merge(write),
+        None => recording.push(write),
+    }
+}
+
+impl<CTX: HasFgrCtx + 'static> History<CTX> {
+    pub fn new(ctx: &mut CTX) -> Self {
+        Self {
+            state: Arc::new(RwLock::new(HistoryState {
+                undo_stack: Vec::new(),
+                redo_stack: Vec::new(),
+                merge_window: Duration::from_millis(1000),
+            })),
+            undo_count: Signal::new(ctx, 0),
+            redo_count: Signal::new(ctx, 0),
+        }
+    }
+
+    pub fn set_merge_window(&self, merge_window: Duration) {
+        self.state.write().unwrap().merge_window = merge_window;
+    }
+
+    pub fn can_undo(&self) -> BoxedAccessor<CTX, bool> {
+        self.undo_count.derive(|count| *count > 0)
+    }
+
+    pub fn can_redo(&self) -> BoxedAccessor<CTX, bool> {
+        self.redo_count.derive(|count| *count > 0)
+    }
+
+    /// The label of the transaction `undo` would revert. Not tracked, pair with `can_undo`.
+    pub fn undo_label(&self) -> Option<String> {
+        self.state.read().unwrap().undo_stack.last().map(|entry| entry.label.clone())
+    }
+
+    pub fn redo_label(&self) -> Option<String> {
+        self.state.read().unwrap().redo_stack.last().map(|entry| entry.label.clone())
+    }
+
+    /// Records the writes `callback` makes as one undo step. A transaction started inside another
+    /// one adds its writes to the outer one.
+    pub fn transaction<R, CALLBACK: FnOnce(&mut CTX) -> R>(&mut self, ctx: &mut CTX, label: impl Into<String>, callback: CALLBACK) -> R {
+        self.record(ctx, label.into(), None, callback)
+    }
+
+    pub fn transaction_merging<R, CALLBACK: FnOnce(&mut CTX) -> R>(&mut self, ctx: &mut CTX, label: impl Into<String>, merge_key: impl Into<String>, callback: CALLBACK) -> R {
+        self.record(ctx, label.into(), Some(merge_key.into()), callback)
+    }
+
+    pub fn undo(&mut self, ctx: &mut CTX) -> bool {
+        let entry = self.state.write().unwrap().undo_stack.pop();
+        let Some(entry) = entry else { return false; };
+        unrecorded(ctx, |ctx| {
+            for write in entry.writes.iter().rev() {
+                write.undo(ctx);
+            }
+        });
+        self.state.write().unwrap().redo_stack.push(entry);
+        self.update_counts(ctx);
+        true
+    }
+
+    pub fn redo(&mut self, ctx: &mut CTX) -> bool {
+        let entry = self.state.write().unwrap().redo_stack.pop();
+        let Some(entry) = entry else { return false; };
+        unrecorded(ctx, |ctx| {
+            for write in &entry.writes {
+                write.redo(ctx);
+            }
+        });
+        self.state.write().unwrap().undo_stack.push(entry);
+        self.update_counts(ctx);
+        true
+    }
+
+    pub fn clear(&mut self, ctx: &mut CTX) {
+        {
+            let mut state = self.state.write().unwrap();
+            state.undo_stack.clear();
+            state.redo_stack.clear();
+        }
+        self.update_counts(ctx);
+    }
+
+    fn record<R, CALLBACK: FnOnce(&mut CTX) -> R>(&mut self, ctx: &mut CTX, label: String, merge_key: Option<String>, callback: CALLBACK) -> R {
+        if is_recording(ctx) {
+            return callback(ctx);
+        }
+        // recording stops before the batch settles, so writes made by effects are left out
+        let (result, writes) = ctx.fgr_batch(|ctx| {
+            ctx.fgr_ctx().recording = Some(Vec::new());
+            let result = callback(ctx);
+            (result, ctx.fgr_ctx().recording.take().unwrap())
+        });
+        if writes.is_empty() {
+            return result;
+        }
+        let entry = HistoryEntry {
+            label,
+            merge_key,
+            time: Instant::now(),
+            writes,
+        };
+        {
+            let mut state = self.state.write().unwrap();
+            state.redo_stack.clear();
+            let merge_window = state.merge_window;
+            let merge_into = state.undo_stack.last_mut().filter(|last| {
+                entry.merge_key.is_some()
+                    && last.merge_key == entry.merge_key
+                    && entry.time.duration_since(last.time) <= merge_window
+            });
+            match merge_into {
+                Some(last) => last.merge(entry),
+                None => state.undo_stack.push(entry),
+            }
+        }
+        self.update_counts(ctx);
+        result
+    }
+
+    fn update_counts(&mut self, ctx: &mut CTX) {
+        let (undo_count, redo_count) = {
+            let state = self.state.read().unwrap();
+            (state.undo_stack.len(), state.redo_stack.len())
+        };
+        ctx.fgr_batch(|ctx| {
+            if ctx.fgr_untrack(|ctx| *self.undo_count.value(ctx)) != undo_count {
+                self.undo_count.update_value(ctx, |x| *x = undo_count);
+            }
+            if ctx.fgr_untrack(|ctx| *self.redo_count.value(ctx)) != redo_count {
+                self.redo_count.update_value(ctx, |x| *x = redo_count);
+            }
+        });
+    }
+}
+
+/// Runs `callback` in a batch without recording its writes, for undo and redo inside a
+/// transaction.
+fn unrecorded<CTX: HasFgrCtx + 'static>(ctx: &mut CTX, callback: impl FnOnce(&mut CTX)) {
+    let recording = ctx.fgr_ctx().recording.take();
+    ctx.fgr_batch(callback);
+    ctx.fgr_ctx().recording = recording;
+}
@@ -0,0 +1,248 @@
+use std::{any::{type_name, TypeId}, marker::PhantomData};
+
+use bevy::prelude::{DetectChangesMut, Mut, Resource, World};
+
+use super::{BoxedAccessor, EffectDeps, FgrCtx, FgrExtensionMethods, HasFgrCtx, Signal};
+
+// Labelled graphs live in `FgrCtx<World, L>` resources. Nodes only know their context type, so
+// a labelled graph is used by swapping it into the `FgrCtx<World>` slot for the duration of a
+// callback. Nodes must only be used while their own graph is active (reading or writing one of
+// another graph panics), to share state across graphs use `fgr_bridge`. The swap happens in
+// place without touching change ticks, so system effects watching the whole world do not see
+// every switch as a change.
+
+impl<CTX, L> FgrCtx<CTX, L> {
+    fn relabel<L2>(self) -> FgrCtx<CTX, L2> {
+        FgrCtx {
+            graph: self.graph,
+            witness_created: self.witness_created,
+            created_nodes: self.created_nodes,
+            witness_observe: self.witness_observe,
+            observed_nodes: self.observed_nodes,
+            update_flag_signal: self.update_flag_signal,
+            stack: self.stack,
+            tmp_buffer_1: self.tmp_buffer_1,
+            tmp_buffer_2: self.tmp_buffer_2,
+            transaction_level: self.transaction_level,
+            update_count: self.update_count,
+            parallel: self.parallel,
+            defered_effects: self.defered_effects,
+            recording: self.recording,
+            label: PhantomData,
+        }
+    }
+
+    fn swap<L2>(&mut self, other: &mut FgrCtx<CTX, L2>) {
+        std::mem::swap(&mut self.graph, &mut other.graph);
+        std::mem::swap(&mut self.witness_created, &mut other.witness_created);
+        std::mem::swap(&mut self.created_nodes, &mut other.created_nodes);
+        std::mem::swap(&mut self.witness_observe, &mut other.witness_observe);
+        std::mem::swap(&mut self.observed_nodes, &mut other.observed_nodes);
+        std::mem::swap(&mut self.update_flag_signal, &mut other.update_flag_signal);
+        std::mem::swap(&mut self.stack, &mut other.stack);
+        std::mem::swap(&mut self.tmp_buffer_1, &mut other.tmp_buffer_1);
+        std::mem::swap(&mut self.tmp_buffer_2, &mut other.tmp_buffer_2);
+        std::mem::swap(&mut self.transaction_level, &mut other.transaction_level);
+        std::mem::swap(&mut self.update_count, &mut other.update_count);
+        std::mem::swap(&mut self.parallel, &mut other.parallel);
+        std::mem::swap(&mut self.defered_effects, &mut other.defered_effects);
+        std::mem::swap(&mut self.recording, &mut other.recording);
+    }
+}
+
+/// The label of a graph, known at runtime rather than as a type. See `fgr_active_label`.
+#[derive(Clone, Copy)]
+pub struct FgrLabel(ActiveLabel);
+
+impl PartialEq for FgrLabel {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.id == other.0.id
+    }
+}
+
+impl Eq for FgrLabel {}
+
+impl std::fmt::Debug for FgrLabel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("FgrLabel").field(&self.0.id).finish()
+    }
+}
+
+/// The `R` resources of labelled graphs, see `fgr_resource`.
+#[derive(Resource)]
+struct LabelledResources<R> {
+    instances: Vec<(FgrLabel, R)>,
+}
+
+/// Where the unlabelled graph is kept while a labelled one is active.
+struct Unlabelled;
+
+/// The label of the graph in the `FgrCtx<World>` slot.
+#[derive(Resource, Clone, Copy)]
+struct ActiveLabel {
+    id: TypeId,
+    /// Swaps the graph in the slot with the one in the label's own resource. Switching from one
+    /// graph to another swaps twice, so the resource of the active graph holds an empty graph.
+    swap: fn(&mut World),
+}
+
+impl ActiveLabel {
+    fn of<L: Send + Sync + 'static>() -> Self {
+        if TypeId::of::<L>() == TypeId::of::<()>() {
+            return Self { id: TypeId::of::<()>(), swap: swap::<Unlabelled> };
+        }
+        Self { id: TypeId::of::<L>(), swap: swap::<L> }
+    }
+
+    fn is_unlabelled(&self) -> bool {
+        self.id == TypeId::of::<()>()
+    }
+
+    fn switch_to(&self, world: &mut World, next: ActiveLabel) {
+        (self.swap)(world);
+        (next.swap)(world);
+        match world.get_resource_mut::<ActiveLabel>() {
+            Some(mut active) => *active.bypass_change_detection() = next,
+            None => world.insert_resource(next),
+        }
+    }
+}
+
+fn swap<L: Send + Sync + 'static>(world: &mut World) {
+    if !world.contains_resource::<FgrCtx<World, L>>() {
+        world.insert_resource(FgrCtx::<World, L>::labelled());
+    }
+    world.resource_scope(|world, mut stored: Mut<FgrCtx<World, L>>| {
+        if let Some(mut active) = world.get_resource_mut::<FgrCtx<World>>() {
+            active.bypass_change_detection().swap(stored.bypass_change_detection());
+        }
+    });
+}
+
+struct RestoreLabel<'w> {
+    world: &'w mut World,
+    active: ActiveLabel,
+    previous: ActiveLabel,
+}
+
+impl Drop for RestoreLabel<'_> {
+    fn drop(&mut self) {
+        self.active.switch_to(self.world, self.previous);
+    }
+}
+
+impl<CTX: HasFgrCtx + 'static, L> FgrCtx<CTX, L> {
+    /// An empty graph labelled `L`, e.g. `FgrCtx::<World, EditorUi>::labelled()`.
+    pub fn labelled() -> Self {
+        FgrCtx::<CTX>::new().relabel()
+    }
+}
+
+/// Working with graphs labelled by a type, so e.g. an editor and the game UI it edits can keep
+/// separate graphs that update on their own schedules.
+pub trait FgrLabelExtensionMethods {
+    /// Runs `callback` with the `FgrCtx<World, L>` resource as the active graph, so everything
+    /// created, read or written in it belongs to that graph. Calls nest, and `L = ()` switches
+    /// back to the unlabelled graph.
+    fn fgr_in_ctx<L: Send + Sync + 'static, R, CALLBACK: FnOnce(&mut World) -> R>(&mut self, callback: CALLBACK) -> R;
+
+    /// Exposes `source`, a node of the active graph, as a read-only accessor in the graph
+    /// labelled `TO`. Must be called inside a scope of the active graph, which the bridge
+    /// belongs to.
+    fn fgr_bridge<TO, A, SOURCE>(&mut self, source: SOURCE) -> BoxedAccessor<World, A>
+    where
+        TO: Send + Sync + 'static,
+        A: Clone + Send + Sync + 'static,
+        SOURCE: EffectDeps<World, Value=A> + Send + Sync + 'static;
+
+    /// The label of the active graph, for running code in it later with `fgr_in_label`, e.g.
+    /// handlers called from a system while another graph is active.
+    fn fgr_active_label(&self) -> FgrLabel;
+
+    /// `fgr_in_ctx` for a label from `fgr_active_label`.
+    fn fgr_in_label<R, CALLBACK: FnOnce(&mut World) -> R>(&mut self, label: FgrLabel, callback: CALLBACK) -> R;
+
+    /// The active graph's instance of `R`, for resources holding nodes, which can only be used in
+    /// their own graph. The unlabelled graph's instance is the `R` resource itself.
+    fn fgr_resource<R: Resource + Clone>(&self) -> Option<R>;
+
+    /// Sets the active graph's instance of `R`, see `fgr_resource`.
+    fn fgr_insert_resource<R: Resource + Clone>(&mut self, resource: R);
+
+    /// Every graph's instance of `R` with the graph's label, unlabelled first.
+    fn fgr_resources<R: Resource + Clone>(&self) -> Vec<(FgrLabel, R)>;
+}
+
+impl FgrLabelExtensionMethods for World {
+    fn fgr_in_ctx<L: Send + Sync + 'static, R, CALLBACK: FnOnce(&mut World) -> R>(&mut self, callback: CALLBACK) -> R {
+        let target = ActiveLabel::of::<L>();
+        if !target.is_unlabelled() && target.id != self.fgr_active_label().0.id && !self.contains_resource::<FgrCtx<World, L>>() {
+            panic!(
+                "no FgrCtx<World, {}> found. Add FgrPlugin::labelled::<{}>(..) to the app, or insert FgrCtx::<World, {}>::labelled() as a resource.",
+                type_name::<L>(), type_name::<L>(), type_name::<L>(),
+            );
+        }
+        self.fgr_in_label(FgrLabel(target), callback)
+    }
+
+    fn fgr_bridge<TO, A, SOURCE>(&mut self, source: SOURCE) -> BoxedAccessor<World, A>
+    where
+        TO: Send + Sync + 'static,
+        A: Clone + Send + Sync + 'static,
+        SOURCE: EffectDeps<World, Value=A> + Send + Sync + 'static,
+    {
+        let initial = self.fgr_untrack(|world| source.read(world));
+        let target = self.fgr_in_ctx::<TO, _, _>(|world| Signal::new(world, initial));
+        self.fgr_create_effect_on_deferred(source, {
+            let target = target.clone();
+            move |world, value, _| {
+                let value = value.clone();
+                let mut target = target.clone();
+                world.fgr_in_ctx::<TO, _, _>(|world| target.update_value(world, |x| *x = value));
+            }
+        });
+        target.into()
+    }
+
+    fn fgr_active_label(&self) -> FgrLabel {
+        FgrLabel(self.get_resource::<ActiveLabel>().copied().unwrap_or(ActiveLabel::of::<()>()))
+    }
+
+    fn fgr_in_label<R, CALLBACK: FnOnce(&mut World) -> R>(&mut self, label: FgrLabel, callback: CALLBACK) -> R {
+        let FgrLabel(target) = label;
+        let FgrLabel(previous) = self.fgr_active_label();
+        if target.id == previous.id {
+            return callback(self);
+        }
+        previous.switch_to(self, target);
+        // switches back even if `callback` panics
+        let guard = RestoreLabel { world: self, active: target, previous };
+        callback(guard.world)
+    }
+
+    fn fgr_resource<R: Resource + Clone>(&self) -> Option<R> {
+        let label = self.fgr_active_label();
+        if label.0.is_unlabelled() {
+            return self.get_resource::<R>().cloned();
+        }
+        let instances = &self.get_resource::<LabelledResources<R>>()?.instances;
+        instances.iter().find(|(other, _)| *other == label).map(|(_, resource)| resource.clone())
+    }
+
+    fn fgr_insert_resource<R: Resource + Clone>(&mut self, resource: R) {
+        let label = self.fgr_active_label();
+        if label.0.is_unlabelled() {
+            self.insert_resource(resource);
+            return;
+        }
+        let mut resources = self.get_resource_or_insert_with(|| LabelledResources::<R> { instances: Vec::new() });
+        resources.instances.retain(|(other, _)| *other != label);
+        resources.instances.push((label, resource));
+    }
+
+    fn fgr_resources<R: Resource + Clone>(&self) -> Vec<(FgrLabel, R)> {
+        let unlabelled = self.get_resource::<R>().map(|resource| (FgrLabel(ActiveLabel::of::<()>()), resource.clone()));
+        let labelled = self.get_resource::<LabelledResources<R>>().into_iter().flat_map(|resources| resources.instances.iter().cloned());
+        unlabelled.into_iter().chain(labelled).collect()
+    }
+}
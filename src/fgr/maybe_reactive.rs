@@ -0,0 +1,143 @@
+use std::ops::Deref;
+
+use super::{Accessor, BoxedAccessor, ConstAccessor, HasFgrCtx, Memo, Signal};
+
+/// A prop value that is either a plain value or any reactive accessor.
+///
+/// Plain values, `Signal`s, `Memo`s, `ConstAccessor`s and `BoxedAccessor`s all convert with
+/// `.into()`. A closure can not get a `From` impl (it would overlap with the one for plain
+/// values), it converts through `FromDeriveFn` instead, which `view!` uses for closure values:
+/// `width={move |world| ui::px(*size.value(world))}`.
+pub enum MaybeReactive<CTX, A> {
+    Static(ConstAccessor<A>),
+    Reactive(BoxedAccessor<CTX, A>),
+}
+
+impl<CTX, A> Clone for MaybeReactive<CTX, A> {
+    fn clone(&self) -> Self {
+        match self {
+            MaybeReactive::Static(value) => MaybeReactive::Static(value.clone()),
+            MaybeReactive::Reactive(accessor) => MaybeReactive::Reactive(accessor.clone()),
+        }
+    }
+}
+
+impl<CTX: 'static, A: Send + Sync + 'static> MaybeReactive<CTX, A> {
+    pub fn derived(derive_fn: impl Fn(&mut CTX) -> A + Send + Sync + 'static) -> Self {
+        MaybeReactive::Reactive(BoxedAccessor::derived(derive_fn))
+    }
+
+    pub fn is_static(&self) -> bool {
+        matches!(self, MaybeReactive::Static(_))
+    }
+}
+
+impl<CTX: HasFgrCtx + 'static, A: Send + Sync + 'static> MaybeReactive<CTX, A> {
+    pub fn into_boxed(self) -> BoxedAccessor<CTX, A> {
+        match self {
+            MaybeReactive::Static(value) => value.into(),
+            MaybeReactive::Reactive(accessor) => accessor,
+        }
+    }
+}
+
+impl<CTX, A: Default> Default for MaybeReactive<CTX, A> {
+    fn default() -> Self {
+        MaybeReactive::Static(ConstAccessor::new(A::default()))
+    }
+}
+
+impl<CTX, A> From<A> for MaybeReactive<CTX, A> {
+    fn from(value: A) -> Self {
+        MaybeReactive::Static(ConstAccessor::new(value))
+    }
+}
+
+impl<CTX> From<&str> for MaybeReactive<CTX, String> {
+    fn from(value: &str) -> Self {
+        MaybeReactive::Static(ConstAccessor::new(value.into()))
+    }
+}
+
+impl<CTX, A> From<ConstAccessor<A>> for MaybeReactive<CTX, A> {
+    fn from(value: ConstAccessor<A>) -> Self {
+        MaybeReactive::Static(value)
+    }
+}
+
+impl<CTX, A> From<BoxedAccessor<CTX, A>> for MaybeReactive<CTX, A> {
+    fn from(accessor: BoxedAccessor<CTX, A>) -> Self {
+        MaybeReactive::Reactive(accessor)
+    }
+}
+
+impl<CTX: HasFgrCtx + 'static, A: Send + Sync + 'static> From<Signal<CTX, A>> for MaybeReactive<CTX, A> {
+    fn from(signal: Signal<CTX, A>) -> Self {
+        MaybeReactive::Reactive(signal.into())
+    }
+}
+
+impl<CTX: HasFgrCtx + 'static, A: Send + Sync + 'static> From<Memo<CTX, A>> for MaybeReactive<CTX, A> {
+    fn from(memo: Memo<CTX, A>) -> Self {
+        MaybeReactive::Reactive(memo.into())
+    }
+}
+
+/// Props that can be built from a closure deriving their value from the context, which `From`
+/// can not express for closures. `view!` sets props given a closure through this, so the
+/// closure's argument type is inferred from the prop.
+pub trait FromDeriveFn {
+    type Ctx;
+    type Value;
+
+    fn from_derive_fn(derive_fn: impl Fn(&mut Self::Ctx) -> Self::Value + Send + Sync + 'static) -> Self;
+}
+
+impl<CTX: 'static, A: Send + Sync + 'static> FromDeriveFn for MaybeReactive<CTX, A> {
+    type Ctx = CTX;
+    type Value = A;
+
+    fn from_derive_fn(derive_fn: impl Fn(&mut CTX) -> A + Send + Sync + 'static) -> Self {
+        MaybeReactive::derived(derive_fn)
+    }
+}
+
+impl<CTX: 'static, A: Send + Sync + 'static> FromDeriveFn for BoxedAccessor<CTX, A> {
+    type Ctx = CTX;
+    type Value = A;
+
+    fn from_derive_fn(derive_fn: impl Fn(&mut CTX) -> A + Send + Sync + 'static) -> Self {
+        BoxedAccessor::derived(derive_fn)
+    }
+}
+
+impl<P: FromDeriveFn> FromDeriveFn for Option<P> {
+    type Ctx = P::Ctx;
+    type Value = P::Value;
+
+    fn from_derive_fn(derive_fn: impl Fn(&mut P::Ctx) -> P::Value + Send + Sync + 'static) -> Self {
+        Some(P::from_derive_fn(derive_fn))
+    }
+}
+
+impl<CTX: HasFgrCtx + 'static, A: Send + Sync + 'static> Accessor<CTX, A> for MaybeReactive<CTX, A> {
+    fn value<'a>(&'a self, ctx: &mut CTX) -> impl Deref<Target=A> + 'a {
+        enum MyRef<'a, A, R> {
+            Static(&'a A),
+            Reactive(R),
+        }
+        impl<'a, A, R: Deref<Target=A>> Deref for MyRef<'a, A, R> {
+            type Target = A;
+            fn deref(&self) -> &Self::Target {
+                match self {
+                    MyRef::Static(value) => value,
+                    MyRef::Reactive(value) => value,
+                }
+            }
+        }
+        match self {
+            MaybeReactive::Static(value) => MyRef::Static(&*value.0),
+            MaybeReactive::Reactive(accessor) => MyRef::Reactive(accessor.value(ctx)),
+        }
+    }
+}
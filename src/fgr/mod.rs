@@ -1,13 +1,51 @@
-use std::{ops::DerefMut, sync::{Arc, RwLock, RwLockReadGuard}};
+use std::{any::Any, collections::HashSet, marker::PhantomData, ops::DerefMut, sync::{atomic::{AtomicU64, Ordering}, Arc, RwLock, RwLockReadGuard}};
 
 use bevy::prelude::{Resource, World};
 use crate::cloned;
 
+mod accessor_ext;
+mod animation;
+mod boxed_accessor;
+mod contexts;
+mod effect_on;
+mod event_stream;
+mod history;
+mod labelled;
+mod maybe_reactive;
+mod parallel;
+mod persisted;
+mod reactive_map;
+mod reactive_vec;
+mod system_effect;
+mod time;
+
+pub use accessor_ext::AccessorExt;
+pub use animation::{spring, tween, Animatable, Easing};
+pub use boxed_accessor::{BoxedAccessor, BoxedAccessorRef};
+pub use contexts::{FgrCtxError, FgrDataCtx, FgrDeferredExtensionMethods, FgrPlugin};
+pub use effect_on::EffectDeps;
+pub use event_stream::{use_event, EventStream};
+pub use history::History;
+pub use labelled::{FgrLabel, FgrLabelExtensionMethods};
+pub use maybe_reactive::{FromDeriveFn, MaybeReactive};
+pub use persisted::{persisted_signal, PersistedSettings, PersistedSettingsPlugin};
+pub use reactive_map::{MapPatch, ReactiveMap};
+pub use reactive_vec::{ReactiveVec, VecPatch};
+pub use system_effect::{FgrWorldExtensionMethods, SYSTEM_EFFECT_SCAN_LIMIT};
+pub use time::{debounce, delayed, interval, throttle};
+
 const DEBUG_LOG: bool = false;
 
+// Node and graph ids are unique across every `FgrCtx`, so a node read from another graph can be
+// told apart and never shares an id with a node of this one.
+static NEXT_NODE_ID: AtomicU64 = AtomicU64::new(0);
+static NEXT_GRAPH_ID: AtomicU64 = AtomicU64::new(0);
+
+/// The reactive graph of a context. `L` labels independent graphs over the same context type,
+/// see `FgrLabelExtensionMethods`. The graph in use is always the one labelled `()`.
 #[derive(Resource)]
-pub struct FgrCtx<CTX> {
-    next_id: u64,
+pub struct FgrCtx<CTX, L = ()> {
+    graph: u64,
     witness_created: bool,
     created_nodes: Vec<NodeRef<CTX>>,
     witness_observe: bool,
@@ -17,16 +55,36 @@ pub struct FgrCtx<CTX> {
     tmp_buffer_1: Vec<NodeRef<CTX>>,
     tmp_buffer_2: Vec<NodeRef<CTX>>,
     transaction_level: u32,
+    update_count: u64,
+    parallel: bool,
     defered_effects: Vec<Box<dyn FnOnce(&mut CTX) + Sync + Send>>,
+    /// The writes of the active `History` transaction.
+    recording: Option<history::RecordedWrites<CTX>>,
+    label: PhantomData<fn() -> L>,
 }
 
 pub trait HasFgrCtx where Self: Sized {
+    /// The context's graph, which every reactive method goes through. Contexts that may not have
+    /// one panic here with the error `try_fgr_ctx` returns, check with it first where a missing
+    /// graph is expected.
     fn fgr_ctx<'a>(&'a mut self) -> impl DerefMut<Target=FgrCtx<Self>> + 'a;
+
+    /// Like `fgr_ctx`, for contexts that may not have a graph (yet).
+    fn try_fgr_ctx<'a>(&'a mut self) -> Result<impl DerefMut<Target=FgrCtx<Self>> + 'a, FgrCtxError> {
+        Ok(self.fgr_ctx())
+    }
 }
 
 impl HasFgrCtx for World {
     fn fgr_ctx<'a>(&'a mut self) -> impl DerefMut<Target=FgrCtx<Self>> + 'a {
-        self.get_resource_mut::<FgrCtx<Self>>().unwrap()
+        self.try_fgr_ctx().unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    fn try_fgr_ctx<'a>(&'a mut self) -> Result<impl DerefMut<Target=FgrCtx<Self>> + 'a, FgrCtxError> {
+        self.get_resource_mut::<FgrCtx<Self>>().ok_or(FgrCtxError::Missing {
+            context: "World",
+            hint: "Add FgrPlugin to the app (or sub-app), or insert FgrCtx::<World>::new() as a resource.",
+        })
     }
 }
 
@@ -35,6 +93,18 @@ pub trait FgrExtensionMethods {
     fn fgr_batch<R, CALLBACK: FnOnce(&mut Self) -> R>(&mut self, callback: CALLBACK) -> R;
     fn fgr_create_root<R, CALLBACK: FnOnce(&mut Self, RootScope<Self>) -> R>(&mut self, callback: CALLBACK) -> R;
     fn fgr_create_effect<CALLBACK: FnMut(&mut Self) + Send + Sync + 'static>(&mut self, callback: CALLBACK);
+    fn fgr_create_effect_on<DEPS, CALLBACK>(&mut self, deps: DEPS, callback: CALLBACK)
+    where
+        Self: Sized,
+        DEPS: EffectDeps<Self> + Send + Sync + 'static,
+        DEPS::Value: Send + Sync + 'static,
+        CALLBACK: FnMut(&mut Self, &DEPS::Value, Option<&DEPS::Value>) + Send + Sync + 'static;
+    fn fgr_create_effect_on_deferred<DEPS, CALLBACK>(&mut self, deps: DEPS, callback: CALLBACK)
+    where
+        Self: Sized,
+        DEPS: EffectDeps<Self> + Send + Sync + 'static,
+        DEPS::Value: Send + Sync + 'static,
+        CALLBACK: FnMut(&mut Self, &DEPS::Value, Option<&DEPS::Value>) + Send + Sync + 'static;
     fn fgr_on_cleanup<CALLBACK: FnMut(&mut Self) + Send + Sync + 'static>(&mut self, callback: CALLBACK);
     fn fgr_on_update<CALLBACK: FnMut(&mut Self) + Send + Sync + 'static>(&mut self, callback: CALLBACK);
     fn fgr_update(&mut self);
@@ -77,6 +147,24 @@ impl<CTX: HasFgrCtx + 'static> FgrExtensionMethods for CTX {
         FgrCtx::create_effect(self, callback)
     }
 
+    fn fgr_create_effect_on<DEPS, CALLBACK>(&mut self, deps: DEPS, callback: CALLBACK)
+    where
+        DEPS: EffectDeps<Self> + Send + Sync + 'static,
+        DEPS::Value: Send + Sync + 'static,
+        CALLBACK: FnMut(&mut Self, &DEPS::Value, Option<&DEPS::Value>) + Send + Sync + 'static,
+    {
+        FgrCtx::create_effect_on(self, deps, false, callback)
+    }
+
+    fn fgr_create_effect_on_deferred<DEPS, CALLBACK>(&mut self, deps: DEPS, callback: CALLBACK)
+    where
+        DEPS: EffectDeps<Self> + Send + Sync + 'static,
+        DEPS::Value: Send + Sync + 'static,
+        CALLBACK: FnMut(&mut Self, &DEPS::Value, Option<&DEPS::Value>) + Send + Sync + 'static,
+    {
+        FgrCtx::create_effect_on(self, deps, true, callback)
+    }
+
     fn fgr_on_cleanup<CALLBACK: FnMut(&mut Self) + Send + Sync + 'static>(&mut self, callback: CALLBACK) {
         FgrCtx::on_cleanup(self, callback)
     }
@@ -92,9 +180,18 @@ impl<CTX: HasFgrCtx + 'static> FgrExtensionMethods for CTX {
 
 impl<CTX: HasFgrCtx + 'static> FgrCtx<CTX> {
     fn alloc_id(&mut self) -> u64 {
-        let id = self.next_id;
-        self.next_id += 1;
-        id
+        NEXT_NODE_ID.fetch_add(1, Ordering::Relaxed)
+    }
+
+    fn assert_own_node(&self, graph: u64, access: &str) {
+        if graph != self.graph {
+            panic!("a node of another graph was {} while this one is active. Use nodes of a labelled graph inside fgr_in_ctx for its label, and fgr_bridge to share values between graphs.", access);
+        }
+    }
+
+    fn observe(&mut self, node: NodeRef<CTX>, graph: u64) {
+        self.assert_own_node(graph, "read");
+        self.observed_nodes.push(node);
     }
 
     fn track_observed<R, CALLBACK: FnOnce(&mut CTX)->R>(ctx: &mut CTX, callback: CALLBACK) -> (Vec<NodeRef<CTX>>, R) {
@@ -140,7 +237,7 @@ impl<CTX: HasFgrCtx + 'static> FgrCtx<CTX> {
 
     pub fn new() -> Self {
         Self {
-            next_id: 0,
+            graph: NEXT_GRAPH_ID.fetch_add(1, Ordering::Relaxed),
             witness_created: false,
             created_nodes: Vec::new(),
             witness_observe: false,
@@ -150,7 +247,11 @@ impl<CTX: HasFgrCtx + 'static> FgrCtx<CTX> {
             tmp_buffer_1: Vec::new(),
             tmp_buffer_2: Vec::new(),
             transaction_level: 0,
+            update_count: 0,
+            parallel: false,
             defered_effects: Vec::new(),
+            recording: None,
+            label: PhantomData,
         }
     }
 
@@ -198,10 +299,12 @@ impl<CTX: HasFgrCtx + 'static> FgrCtx<CTX> {
             panic!("Effect created outside of scope. Did you forget to call create_root()?");
         }
         let id = ctx.fgr_ctx().alloc_id();
+        let graph = ctx.fgr_ctx().graph;
         let effect: Arc<RwLock<dyn FnMut(&mut CTX) + Send + Sync>> = Arc::new(RwLock::new(callback));
         let impl_: Arc<RwLock<dyn IsNode<CTX> + Send + Sync>> = Arc::new(RwLock::new(EffectImpl {
             node_data: NodeData {
                 id,
+                graph,
                 flag: NodeFlag::Stale,
                 changed: false,
                 dependencies: Vec::new(),
@@ -236,9 +339,11 @@ impl<CTX: HasFgrCtx + 'static> FgrCtx<CTX> {
             panic!("on_cleanup created outside of scope. Did you forget to call create_root()?");
         }
         let id = ctx.fgr_ctx().alloc_id();
+        let graph = ctx.fgr_ctx().graph;
         let impl_: Arc<RwLock<dyn IsNode<CTX> + Send + Sync>> = Arc::new(RwLock::new(CleanupImpl {
             node_data: NodeData {
                 id,
+                graph,
                 flag: NodeFlag::Stale,
                 changed: false,
                 dependencies: Vec::new(),
@@ -258,20 +363,24 @@ impl<CTX: HasFgrCtx + 'static> FgrCtx<CTX> {
         if !ctx.fgr_ctx().witness_created {
             panic!("on_update created outside of scope. Did you forget to call create_root()?");
         }
-        let update_flag_signal: Option<Signal<CTX, u32>> = ctx.fgr_ctx().update_flag_signal.clone();
-        let update_flag_signal_2: Signal<CTX, u32>;
-        if let Some(x) = &update_flag_signal {
-            update_flag_signal_2 = x.clone();
-        } else {
-            update_flag_signal_2 = Signal::new(ctx, 0);
-            ctx.fgr_ctx().update_flag_signal = Some(update_flag_signal_2.clone());
-        }
+        let update_flag_signal_2 = FgrCtx::update_flag_signal(ctx);
         FgrCtx::create_effect(ctx, move |ctx: &mut CTX| {
             let _ = *update_flag_signal_2.value(ctx);
             FgrCtx::untrack(ctx, |ctx| callback(ctx));
         });
     }
 
+    /// The signal flipped by every `update`. Reading it makes a node re-run each update.
+    fn update_flag_signal(ctx: &mut CTX) -> Signal<CTX, u32> {
+        let update_flag_signal: Option<Signal<CTX, u32>> = ctx.fgr_ctx().update_flag_signal.clone();
+        if let Some(x) = update_flag_signal {
+            return x;
+        }
+        let update_flag_signal = Signal::new(ctx, 0);
+        ctx.fgr_ctx().update_flag_signal = Some(update_flag_signal.clone());
+        update_flag_signal
+    }
+
     pub fn update(ctx: &mut CTX) {
         let Some(mut update_flag_signal) = ctx.fgr_ctx().update_flag_signal.clone() else { return; };
         update_flag_signal.update_value(ctx, |x| *x = 1 - *x);
@@ -382,8 +491,14 @@ impl<CTX: HasFgrCtx + 'static> IsNode<CTX> for EffectImpl<CTX> {
             });
             for dep in dependencies_to_remove {
                 self_node_ref.with_node_mut(|self_node| self_node.node_data_mut().dependencies.retain(|x| *x != dep));
+                dep.with_node_mut(|dep| dep.node_data_mut().dependents.retain(|x| *x != self_node_ref));
             }
             for dep in dependencies_to_add {
+                dep.with_node_mut(|dep| {
+                    if !dep.node_data().dependents.contains(&self_node_ref) {
+                        dep.node_data_mut().dependents.push(self_node_ref.clone());
+                    }
+                });
                 self_node_ref.with_node_mut(|self_node| self_node.node_data_mut().dependencies.push(dep));
             }
             self_node_ref.with_node_mut(|self_node| {
@@ -410,64 +525,6 @@ impl<CTX: HasFgrCtx + 'static> IsNode<CTX> for EffectImpl<CTX> {
     }
 }
 
-pub struct BoxedAccessor<CTX, A>(Arc<dyn BoxedAccessorImpl<CTX, A> + Send + Sync>);
-
-impl<CTX: HasFgrCtx + 'static, A: Send + Sync + 'static> Into<BoxedAccessor<CTX,A>> for Memo<CTX, A> {
-    fn into(self) -> BoxedAccessor<CTX,A> {
-        BoxedAccessor(Arc::new(self.clone()))
-    }
-}
-
-impl<CTX: HasFgrCtx + 'static, A: Send + Sync + 'static> Into<BoxedAccessor<CTX,A>> for Signal<CTX, A> {
-    fn into(self) -> BoxedAccessor<CTX,A> {
-        BoxedAccessor(Arc::new(self.clone()))
-    }
-}
-
-impl<CTX: HasFgrCtx + 'static, A: Send + Sync + 'static> Into<BoxedAccessor<CTX,A>> for ConstAccessor<A> {
-    fn into(self) -> BoxedAccessor<CTX,A> {
-        BoxedAccessor(Arc::new(self.clone()))
-    }
-}
-
-impl<CTX, A> Clone for BoxedAccessor<CTX, A> {
-    fn clone(&self) -> Self {
-        Self(Arc::clone(&self.0))
-    }
-}
-
-impl<CTX,A> BoxedAccessor<CTX,A> {
-    pub fn with_value<'a, R:'static, CALLBACK: FnOnce(&A)->R + 'a>(&'a self, ctx: &mut CTX, callback: CALLBACK) -> R {
-        let mut result: Option<R> = None;
-        self.0.with_value(ctx, Box::new(|a| {
-            result = Some(callback(a));
-        }));
-        return result.unwrap();
-    }
-}
-
-trait BoxedAccessorImpl<CTX,A> {
-    fn with_value<'a>(&'a self, ctx: &mut CTX, callback: Box<dyn FnOnce(&A) + 'a>);
-}
-
-impl <CTX: HasFgrCtx + 'static, A: Send + Sync + 'static> BoxedAccessorImpl<CTX, A> for Memo<CTX, A> {
-    fn with_value<'a>(&'a self, ctx: &mut CTX, callback: Box<dyn FnOnce(&A) + 'a>) {
-        callback(&self.value(ctx));
-    }
-}
-
-impl <CTX: HasFgrCtx + 'static, A: Send + Sync + 'static> BoxedAccessorImpl<CTX, A> for Signal<CTX, A> {
-    fn with_value<'a>(&'a self, ctx: &mut CTX, callback: Box<dyn FnOnce(&A) + 'a>) {
-        callback(&self.value(ctx));
-    }
-}
-
-impl <CTX,A> BoxedAccessorImpl<CTX,A> for ConstAccessor<A> {
-    fn with_value<'a>(&'a self, _ctx: &mut CTX, callback: Box<dyn FnOnce(&A) + 'a>) {
-        callback(&self.0);
-    }
-}
-
 pub trait Accessor<CTX, A> {
     fn value<'a>(&'a self, ctx: &mut CTX) -> impl std::ops::Deref<Target=A> + 'a;
 }
@@ -490,25 +547,6 @@ impl<CTX,A> Accessor<CTX,A> for ConstAccessor<A> {
     }
 }
 
-impl<CTX,A:'static> Accessor<CTX,A> for BoxedAccessor<CTX,A> {
-    fn value<'a>(&'a self, ctx: &mut CTX) -> impl std::ops::Deref<Target=A> + 'a {
-        struct MyRef<A> {
-            value: *const A,
-        }
-        impl<A> std::ops::Deref for MyRef<A> {
-            type Target = A;
-            fn deref(&self) -> &Self::Target {
-                unsafe { &*self.value }
-            }
-        }
-        let mut my_ref = MyRef { value: 0 as *const A };
-        self.with_value(ctx, |a| {
-            my_ref.value = a as *const A;
-        });
-        my_ref
-    }
-}
-
 pub struct ConstAccessor<A>(Arc<A>);
 
 impl<A> Clone for ConstAccessor<A> {
@@ -521,6 +559,11 @@ impl<A> ConstAccessor<A> {
     pub fn new(value: A) -> Self {
         Self(Arc::new(value))
     }
+
+    /// The value, without a context to read it in.
+    pub fn get(&self) -> &A {
+        &self.0
+    }
 }
 
 pub struct Memo<CTX, A> {
@@ -545,13 +588,48 @@ impl<CTX: HasFgrCtx + 'static, A: Send + Sync + 'static> Memo<CTX, A> {
     }
 
     pub fn new_with_diff(ctx: &mut CTX, mut update_fn: impl FnMut(&mut CTX) -> A + Send + Sync + 'static, compare_fn: impl FnMut(&A, &A) -> bool + Send + Sync + 'static) -> Self {
+        Self::new_with_prev_and_diff_impl(ctx, move |ctx, _prev| update_fn(ctx), compare_fn)
+    }
+
+    /// A memo whose update function also receives its previous value, for incremental updates.
+    /// `init` stands in for the previous value on the first run.
+    pub fn new_with_prev(ctx: &mut CTX, init: A, update_fn: impl FnMut(&mut CTX, &A) -> A + Send + Sync + 'static) -> Self
+    where A: PartialEq<A>
+    {
+        Self::new_with_prev_and_diff(ctx, init, update_fn, |a, b| a == b)
+    }
+
+    pub fn new_with_prev_and_diff(ctx: &mut CTX, init: A, mut update_fn: impl FnMut(&mut CTX, &A) -> A + Send + Sync + 'static, compare_fn: impl FnMut(&A, &A) -> bool + Send + Sync + 'static) -> Self {
+        let mut init = Some(init);
+        Self::new_with_prev_and_diff_impl(
+            ctx,
+            move |ctx, prev| {
+                match prev {
+                    Some(prev) => update_fn(ctx, prev),
+                    None => update_fn(ctx, &init.take().unwrap()),
+                }
+            },
+            compare_fn,
+        )
+    }
+
+    /// Folds every new value of `source` into an accumulator, e.g. a running total.
+    pub fn reduce<S: 'static>(ctx: &mut CTX, source: impl Accessor<CTX, S> + Send + Sync + 'static, init: A, mut reduce_fn: impl FnMut(&A, &S) -> A + Send + Sync + 'static) -> Self
+    where A: PartialEq<A>
+    {
+        Self::new_with_prev(ctx, init, move |ctx, acc| reduce_fn(acc, &*source.value(ctx)))
+    }
+
+    fn new_with_prev_and_diff_impl(ctx: &mut CTX, mut update_fn: impl FnMut(&mut CTX, Option<&A>) -> A + Send + Sync + 'static, compare_fn: impl FnMut(&A, &A) -> bool + Send + Sync + 'static) -> Self {
         if !ctx.fgr_ctx().witness_created {
             panic!("Memo created outside of scope. Did you forget to call create_root()?");
         }
         let id = ctx.fgr_ctx().alloc_id();
+        let graph = ctx.fgr_ctx().graph;
         let impl_ = Arc::new(RwLock::new(MemoImpl {
             node_data: NodeData {
                 id,
+                graph,
                 flag: NodeFlag::Ready,
                 changed: false,
                 dependencies: Vec::new(),
@@ -561,13 +639,14 @@ impl<CTX: HasFgrCtx + 'static, A: Send + Sync + 'static> Memo<CTX, A> {
             value: None,
             update_fn: None,
             compare_fn: Box::new(compare_fn),
+            pure: None,
         }));
         let result = Self {
             impl_,
         };
         let self_ref: NodeRef<CTX> = (&result).into();
         ctx.fgr_ctx().witness_observe = true;
-        let value = update_fn(ctx);
+        let value = update_fn(ctx, None);
         ctx.fgr_ctx().witness_observe = false;
         for node in ctx.fgr_ctx().observed_nodes.drain(..) {
             node.with_node_mut(|node| {
@@ -592,11 +671,20 @@ impl<CTX: HasFgrCtx + 'static, A: Send + Sync + 'static> Memo<CTX, A> {
 }
 
 impl<CTX: HasFgrCtx + 'static, A: Send + Sync + 'static> Memo<CTX, A> {
-    pub fn value<'a>(&'a self, ctx: &mut CTX) -> impl std::ops::Deref<Target=A> + 'a {
+    fn read<'a>(&'a self, ctx: &mut CTX) -> RwLockReadGuard<'a, MemoImpl<CTX, A>> {
+        self.track(ctx);
+        (*self.impl_).read().unwrap()
+    }
+
+    fn track(&self, ctx: &mut CTX) {
         if ctx.fgr_ctx().witness_observe {
-            ctx.fgr_ctx().observed_nodes.push(self.into());
+            let graph = self.impl_.read().unwrap().node_data.graph;
+            ctx.fgr_ctx().observe(self.into(), graph);
         }
-        let impl_ = (*self.impl_).read().unwrap();
+    }
+
+    pub fn value<'a>(&'a self, ctx: &mut CTX) -> impl std::ops::Deref<Target=A> + 'a {
+        let impl_ = self.read(ctx);
         struct MyRef<'a,CTX,A> {
             impl_: RwLockReadGuard<'a, MemoImpl<CTX, A>>,
         }
@@ -633,11 +721,12 @@ impl<CTX: HasFgrCtx + 'static, A: Send + Sync + 'static> Into<NodeRef<CTX>> for
 pub struct MemoImpl<CTX, A> {
     node_data: NodeData<CTX>,
     value: Option<A>, // <-- only temporarly None during initialization.
-    update_fn: Option<Box<dyn FnMut(&mut CTX) -> A + Send + Sync>>, // <-- only temporarly None during initialization.
+    update_fn: Option<Box<dyn FnMut(&mut CTX, Option<&A>) -> A + Send + Sync>>, // <-- only temporarly None during initialization.
     compare_fn: Box<dyn FnMut(&A, &A) -> bool + Send + Sync>,
+    pure: Option<Box<dyn parallel::PureUpdate<CTX>>>,
 }
 
-impl<CTX: HasFgrCtx, A> IsNode<CTX> for MemoImpl<CTX,A> {
+impl<CTX: HasFgrCtx, A: Send + 'static> IsNode<CTX> for MemoImpl<CTX,A> {
     fn is_source(&self) -> bool {
         false
     }
@@ -655,7 +744,22 @@ impl<CTX: HasFgrCtx, A> IsNode<CTX> for MemoImpl<CTX,A> {
     }
 
     fn update(&mut self, _self_node_ref: NodeRef<CTX>, ctx: &mut CTX) -> bool {
-        let next_value = (self.update_fn.as_mut().unwrap())(ctx);
+        let next_value = (self.update_fn.as_mut().unwrap())(ctx, self.value.as_ref());
+        let changed = !(self.compare_fn)(&next_value, self.value.as_ref().unwrap());
+        self.value = Some(next_value);
+        changed
+    }
+
+    fn is_pure(&self) -> bool {
+        self.pure.is_some()
+    }
+
+    fn prepare_pure(&mut self, ctx: &mut CTX) -> Option<parallel::PureJob> {
+        self.pure.as_mut().map(|pure| pure.prepare(ctx))
+    }
+
+    fn finish_pure(&mut self, next_value: Box<dyn Any + Send>) -> bool {
+        let next_value = *next_value.downcast::<A>().unwrap();
         let changed = !(self.compare_fn)(&next_value, self.value.as_ref().unwrap());
         self.value = Some(next_value);
         changed
@@ -696,11 +800,17 @@ pub struct Signal<CTX, A> {
 
 impl<CTX: HasFgrCtx + 'static, A> Signal<CTX, A> {
     pub fn new(ctx: &mut CTX, value: A) -> Self {
+        Self::new_with_snapshot(ctx, value, None)
+    }
+
+    fn new_with_snapshot(ctx: &mut CTX, value: A, snapshot: Option<fn(&A) -> A>) -> Self {
         let id = ctx.fgr_ctx().alloc_id();
+        let graph = ctx.fgr_ctx().graph;
         Self {
             impl_: Arc::new(RwLock::new(SignalImpl {
                 node_data: NodeData {
                     id,
+                    graph,
                     flag: NodeFlag::Ready,
                     changed: false,
                     dependencies: Vec::new(),
@@ -709,11 +819,20 @@ impl<CTX: HasFgrCtx + 'static, A> Signal<CTX, A> {
                 },
                 value,
                 value_changed: false,
+                snapshot,
             })),
         }
     }
 }
 
+impl<CTX: HasFgrCtx + 'static, A: Clone> Signal<CTX, A> {
+    /// A signal whose writes inside `History` transactions are recorded, so they are undone and
+    /// redone with the transaction. A recorded write clones the value before and after it.
+    pub fn new_recorded(ctx: &mut CTX, value: A) -> Self {
+        Self::new_with_snapshot(ctx, value, Some(A::clone))
+    }
+}
+
 impl<CTX: HasFgrCtx + 'static, A: Send + Sync + 'static> std::fmt::Debug for Signal<CTX, A> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "(Signal {})", Into::<NodeRef<CTX>>::into(self).id)
@@ -743,6 +862,8 @@ struct SignalImpl<CTX, A> {
     node_data: NodeData<CTX>,
     value: A,
     value_changed: bool,
+    /// Copies the value for `History`, `None` for signals it does not record.
+    snapshot: Option<fn(&A) -> A>,
 }
 
 impl<CTX: HasFgrCtx + 'static, A> IsNode<CTX> for SignalImpl<CTX, A> {
@@ -772,11 +893,20 @@ impl<CTX: HasFgrCtx + 'static, A> IsNode<CTX> for SignalImpl<CTX, A> {
 }
 
 impl<CTX: HasFgrCtx + 'static, A: Send + Sync + 'static> Signal<CTX, A> {
-    pub fn value<'a>(&'a self, ctx: &mut CTX) -> impl std::ops::Deref<Target=A> + 'a {
+    fn read<'a>(&'a self, ctx: &mut CTX) -> RwLockReadGuard<'a, SignalImpl<CTX, A>> {
+        self.track(ctx);
+        (*self.impl_).read().unwrap()
+    }
+
+    fn track(&self, ctx: &mut CTX) {
         if ctx.fgr_ctx().witness_observe {
-            ctx.fgr_ctx().observed_nodes.push(self.into());
+            let graph = self.impl_.read().unwrap().node_data.graph;
+            ctx.fgr_ctx().observe(self.into(), graph);
         }
-        let impl_ = (*self.impl_).read().unwrap();
+    }
+
+    pub fn value<'a>(&'a self, ctx: &mut CTX) -> impl std::ops::Deref<Target=A> + 'a {
+        let impl_ = self.read(ctx);
         struct MyRef<'a,CTX,A> {
             impl_: RwLockReadGuard<'a, SignalImpl<CTX,A>>,
         }
@@ -796,20 +926,51 @@ impl<CTX: HasFgrCtx + 'static, A: Send + Sync + 'static> Signal<CTX, A> {
             println!("Signal::update_value called on {:?}", (Into::<NodeRef<CTX>>::into(&*self)));
         }
         //
+        let graph = self.impl_.read().unwrap().node_data.graph;
+        ctx.fgr_ctx().assert_own_node(graph, "written");
+        let recording = history::is_recording(ctx);
+        let recorded = {
+            let mut impl_ = (*self.impl_).write().unwrap();
+            let snapshot = impl_.snapshot.filter(|_| recording);
+            let old_value = snapshot.map(|snapshot| snapshot(&impl_.value));
+            callback(&mut impl_.value);
+            old_value.zip(snapshot).map(|(old_value, snapshot)| (old_value, snapshot(&impl_.value), snapshot))
+        };
+        self.notify(ctx, recorded);
+    }
+
+    /// Marks the signal changed and its dependents stale once its value was written, and records
+    /// the write as `(old_value, new_value, snapshot)` in the active `History` transaction.
+    fn notify(&mut self, ctx: &mut CTX, recorded: Option<SignalWrite<A>>) {
         ctx.fgr_batch(|ctx| {
             {
                 let mut impl_ = (*self.impl_).write().unwrap();
-                callback(&mut impl_.value);
                 impl_.value_changed = true;
                 impl_.node_data.flag = NodeFlag::Stale;
             }
             // add self to stack for propergating dependent flags to stale.
             ctx.fgr_ctx().stack.push((&*self).into());
             propergate_dependents_flags_to_stale(ctx);
+            if let Some((old_value, new_value, snapshot)) = recorded {
+                let signal = self.clone();
+                let id = Into::<NodeRef<CTX>>::into(&*self).id;
+                history::record_write(ctx, id, old_value, new_value, snapshot, move |ctx, value| signal.clone().set_value(ctx, value));
+            }
         });
     }
 }
 
+/// A signal's old value, new value and snapshot function, for `History`.
+type SignalWrite<A> = (A, A, fn(&A) -> A);
+
+impl<CTX: HasFgrCtx + 'static, A: Send + Sync + 'static> Signal<CTX, A> {
+    /// Not generic over a callback, so `History` writing values back does not instantiate
+    /// `update_value` recursively.
+    fn set_value(&mut self, ctx: &mut CTX, value: A) {
+        self.update_value(ctx, |x| *x = value);
+    }
+}
+
 #[derive(PartialEq, Eq, Clone, Copy, Debug)]
 enum NodeFlag {
     Ready,
@@ -818,6 +979,8 @@ enum NodeFlag {
 
 struct NodeData<CTX> {
     id: u64,
+    /// The `FgrCtx` the node was created in.
+    graph: u64,
     flag: NodeFlag,
     changed: bool,
     dependencies: Vec<NodeRef<CTX>>,
@@ -832,6 +995,20 @@ trait IsNode<CTX: HasFgrCtx> {
     fn node_data_mut(&mut self) -> &mut NodeData<CTX>;
     fn update(&mut self, self_node_ref: NodeRef<CTX>, ctx: &mut CTX) -> bool;
     fn dispose(&mut self, ctx: &mut CTX);
+
+    fn is_pure(&self) -> bool {
+        false
+    }
+
+    /// Reads the inputs of a pure node and returns the job computing its next value.
+    fn prepare_pure(&mut self, _ctx: &mut CTX) -> Option<parallel::PureJob> {
+        None
+    }
+
+    /// Takes the value computed by the job from `prepare_pure`, returns whether it changed.
+    fn finish_pure(&mut self, _next_value: Box<dyn Any + Send>) -> bool {
+        false
+    }
 }
 
 pub struct NodeRef<CTX> {
@@ -875,10 +1052,13 @@ impl<CTX> NodeRef<CTX> {
     }
 }
 
-fn update_graph<CTX: HasFgrCtx>(ctx: &mut CTX) {
+fn update_graph<CTX: HasFgrCtx + 'static>(ctx: &mut CTX) {
     let mut tmp_buffer_1 = Vec::new();
     let mut tmp_buffer_2 = Vec::new();
     let mut stack = Vec::new();
+    let parallel = ctx.fgr_ctx().parallel;
+    let mut wave: Vec<NodeRef<CTX>> = Vec::new();
+    let mut wave_ids: HashSet<u64> = HashSet::new();
     std::mem::swap(&mut ctx.fgr_ctx().tmp_buffer_1, &mut tmp_buffer_1);
     std::mem::swap(&mut ctx.fgr_ctx().tmp_buffer_2, &mut tmp_buffer_2);
     std::mem::swap(&mut ctx.fgr_ctx().stack, &mut stack);
@@ -888,7 +1068,14 @@ fn update_graph<CTX: HasFgrCtx>(ctx: &mut CTX) {
     }
     //
     loop {
-        let Some(node) = stack.pop() else { break; };
+        let Some(node) = stack.pop() else {
+            if wave.is_empty() {
+                break;
+            }
+            wave_ids.clear();
+            parallel::evaluate_wave(ctx, &mut wave, &mut stack, &mut tmp_buffer_1);
+            continue;
+        };
         //
         if DEBUG_LOG {
             println!("at node {:?}", node);
@@ -901,6 +1088,9 @@ fn update_graph<CTX: HasFgrCtx>(ctx: &mut CTX) {
         match flag {
             NodeFlag::Ready => { /* do nothing */ },
             NodeFlag::Stale => {
+                if wave_ids.contains(&node.id) {
+                    continue;
+                }
                 let is_source = node.with_node(|n| n.is_source());
                 let is_sink = node.with_node(|n| n.is_sink());
                 node.with_node(|n| {
@@ -914,10 +1104,10 @@ fn update_graph<CTX: HasFgrCtx>(ctx: &mut CTX) {
                     let flag = dep.with_node(|n| n.node_data().flag);
                     match flag {
                         NodeFlag::Ready => {
+                            // no early exit here, a later dependency may still be stale.
                             let changed = dep.with_node(|n| n.node_data().changed);
                             if changed {
                                 any_dependencies_changed = true;
-                                break;
                             }
                         },
                         NodeFlag::Stale => {
@@ -928,7 +1118,10 @@ fn update_graph<CTX: HasFgrCtx>(ctx: &mut CTX) {
                     }
                 }
                 tmp_buffer_2.clear();
-                if !has_stale_dependencies && (any_dependencies_changed || is_source) {
+                if !has_stale_dependencies && any_dependencies_changed && parallel && node.with_node(|n| n.is_pure()) {
+                    wave_ids.insert(node.id);
+                    wave.push(node);
+                } else if !has_stale_dependencies && (any_dependencies_changed || is_source) {
                     if DEBUG_LOG {
                         println!("  update node {:?}", node);
                     }
@@ -937,11 +1130,12 @@ fn update_graph<CTX: HasFgrCtx>(ctx: &mut CTX) {
                         if !(is_source || is_sink) {
                             ctx.fgr_ctx().witness_created = true;
                             ctx.fgr_ctx().witness_observe = true;
+                            ctx.fgr_ctx().observed_nodes.clear();
                             for scoped in n.node_data_mut().scoped.drain(..) {
                                 (*scoped.node).write().unwrap().dispose(ctx);
                             }
                         }
-                        let changed = n.update(node2, ctx);
+                        let changed = n.update(node2.clone(), ctx);
                         if !(is_source || is_sink) {
                             ctx.fgr_ctx().witness_observe = false;
                             ctx.fgr_ctx().witness_created = false;
@@ -961,10 +1155,17 @@ fn update_graph<CTX: HasFgrCtx>(ctx: &mut CTX) {
                             }
                             for dep in dependencies_to_remove {
                                 n.node_data_mut().dependencies.retain(|x| *x != dep);
+                                dep.with_node_mut(|dep| dep.node_data_mut().dependents.retain(|x| *x != node2));
                             }
                             for dep in dependencies_to_add {
+                                dep.with_node_mut(|dep| {
+                                    if !dep.node_data().dependents.contains(&node2) {
+                                        dep.node_data_mut().dependents.push(node2.clone());
+                                    }
+                                });
                                 n.node_data_mut().dependencies.push(dep);
                             }
+                            ctx.fgr_ctx().observed_nodes.clear();
                             std::mem::swap(&mut n.node_data_mut().scoped, &mut ctx.fgr_ctx().created_nodes);
                         }
                         let n2 = n.node_data_mut();
@@ -991,6 +1192,7 @@ fn update_graph<CTX: HasFgrCtx>(ctx: &mut CTX) {
             n2.changed = false;
         });
     }
+    ctx.fgr_ctx().update_count += 1;
     //
     if DEBUG_LOG {
         println!("update_graph finished.");
@@ -1004,24 +1206,25 @@ fn update_graph<CTX: HasFgrCtx>(ctx: &mut CTX) {
 }
 
 fn propergate_dependents_flags_to_stale<CTX: HasFgrCtx + 'static>(ctx: &mut CTX) {
-    loop {
-        let Some(at) = ctx.fgr_ctx().stack.pop() else { break; };
+    // Only walks from the node just pushed. Nodes left on the stack by earlier writes in the
+    // same batch still need updating, so they must stay there.
+    let Some(start) = ctx.fgr_ctx().stack.pop() else { return; };
+    let mut walk = std::mem::take(&mut ctx.fgr_ctx().tmp_buffer_1);
+    walk.push(start);
+    while let Some(at) = walk.pop() {
         at.with_node(|n| {
             for dep in &n.node_data().dependents {
-                ctx.fgr_ctx().tmp_buffer_1.push(dep.clone());
+                if DEBUG_LOG {
+                    println!("  dep: {:?} marked stale", dep);
+                }
+                dep.with_node_mut(|n| n.node_data_mut().flag = NodeFlag::Stale);
+                walk.push(dep.clone());
                 ctx.fgr_ctx().tmp_buffer_2.push(dep.clone());
             }
         });
-        let fgr_ctx = &mut *ctx.fgr_ctx();
-        for dep in fgr_ctx.tmp_buffer_1.drain(..) {
-            if DEBUG_LOG {
-                println!("  dep: {:?} marked stale", dep);
-            }
-            dep.with_node_mut(|n| n.node_data_mut().flag = NodeFlag::Stale);
-            fgr_ctx.stack.push(dep);
-        }
     }
     let fgr_ctx = &mut *ctx.fgr_ctx();
+    fgr_ctx.tmp_buffer_1 = walk;
     for dep in fgr_ctx.tmp_buffer_2.drain(..) {
         fgr_ctx.stack.push(dep);
     }
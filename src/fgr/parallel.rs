@@ -0,0 +1,102 @@
+use std::{any::Any, sync::Arc};
+
+use bevy::tasks::{ComputeTaskPool, TaskPool};
+
+use super::{EffectDeps, FgrCtx, HasFgrCtx, Memo, NodeFlag, NodeRef};
+
+// Pure memos split their update into reading the inputs, which needs the context and happens on
+// the graph's thread, and computing the value from them, which may happen on any thread. With
+// parallel evaluation enabled `update_graph` collects the pure memos that are ready to update
+// into a wave instead of updating them, and once nothing else can make progress computes the
+// whole wave on the `ComputeTaskPool`, the graph's thread only waits for it rather than taking
+// jobs itself. The results are applied in the order the memos were collected in, so the values
+// are the same as in sequential mode.
+
+pub(super) type PureJob = Box<dyn FnOnce() -> Box<dyn Any + Send> + Send>;
+
+pub(super) trait PureUpdate<CTX>: Send + Sync {
+    fn prepare(&mut self, ctx: &mut CTX) -> PureJob;
+}
+
+struct PureMemo<DEPS, F> {
+    deps: Arc<DEPS>,
+    compute: Arc<F>,
+}
+
+impl<CTX, A, DEPS, F> PureUpdate<CTX> for PureMemo<DEPS, F>
+where
+    CTX: HasFgrCtx + 'static,
+    A: Send + 'static,
+    DEPS: EffectDeps<CTX> + Send + Sync,
+    DEPS::Value: Send + 'static,
+    F: Fn(&DEPS::Value) -> A + Send + Sync + 'static,
+{
+    fn prepare(&mut self, ctx: &mut CTX) -> PureJob {
+        let input = self.deps.read(ctx);
+        let compute = Arc::clone(&self.compute);
+        Box::new(move || Box::new(compute(&input)))
+    }
+}
+
+impl<CTX: HasFgrCtx + 'static, A: PartialEq + Send + Sync + 'static> Memo<CTX, A> {
+    /// A memo computed by `compute` from the values of `deps` alone. When the graph evaluates in
+    /// parallel (see `FgrCtx::set_parallel`), pure memos that become stale in the same update are
+    /// computed concurrently.
+    pub fn new_pure<DEPS>(ctx: &mut CTX, deps: DEPS, compute: impl Fn(&DEPS::Value) -> A + Send + Sync + 'static) -> Self
+    where
+        DEPS: EffectDeps<CTX> + Send + Sync + 'static,
+        DEPS::Value: Send + 'static,
+    {
+        let deps = Arc::new(deps);
+        let compute = Arc::new(compute);
+        let memo = Memo::new(ctx, {
+            let deps = Arc::clone(&deps);
+            let compute = Arc::clone(&compute);
+            move |ctx| compute(&deps.read(ctx))
+        });
+        memo.impl_.write().unwrap().pure = Some(Box::new(PureMemo { deps, compute }));
+        memo
+    }
+}
+
+impl<CTX: HasFgrCtx + 'static> FgrCtx<CTX> {
+    /// Opts into computing independent pure memos on the `ComputeTaskPool`.
+    pub fn set_parallel(&mut self, parallel: bool) {
+        self.parallel = parallel;
+    }
+}
+
+/// Computes the collected pure memos and applies their results like a sequential update would.
+pub(super) fn evaluate_wave<CTX: HasFgrCtx + 'static>(ctx: &mut CTX, wave: &mut Vec<NodeRef<CTX>>, stack: &mut Vec<NodeRef<CTX>>, changed_nodes: &mut Vec<NodeRef<CTX>>) {
+    let jobs: Vec<PureJob> = wave
+        .iter()
+        .map(|node| {
+            node.with_node_mut(|n| FgrCtx::untrack(ctx, |ctx| n.prepare_pure(ctx)))
+                .expect("only pure nodes are collected into a wave")
+        })
+        .collect();
+    let results: Vec<Box<dyn Any + Send>> = if jobs.len() == 1 {
+        jobs.into_iter().map(|job| job()).collect()
+    } else {
+        ComputeTaskPool::get_or_init(TaskPool::default).scope_with_executor(false, None, |scope| {
+            for job in jobs {
+                scope.spawn(async move { job() });
+            }
+        })
+    };
+    for (node, result) in wave.drain(..).zip(results) {
+        let changed = node.with_node_mut(|n| {
+            let changed = n.finish_pure(result);
+            let node_data = n.node_data_mut();
+            node_data.changed = changed;
+            node_data.flag = NodeFlag::Ready;
+            for dep in &node_data.dependents {
+                stack.push(dep.clone());
+            }
+            changed
+        });
+        if changed {
+            changed_nodes.push(node);
+        }
+    }
+}
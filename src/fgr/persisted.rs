@@ -0,0 +1,191 @@
+use std::{collections::BTreeMap, path::{Path, PathBuf}, sync::{Arc, RwLock}, time::{Duration, Instant}};
+
+use bevy::{app::{App, AppExit, Last, Plugin}, log::warn, prelude::{EventReader, Res, Resource, World}};
+use serde::{de::DeserializeOwned, Serialize};
+
+use super::{FgrExtensionMethods, HasFgrCtx, Signal};
+
+/// A settings file that signals can be persisted to.
+///
+/// The file is a RON map from keys to the RON text of their values. Each value is deserialized
+/// straight from its text into the signal's type, so enums keep their variants (`ron::Value`
+/// has no enums). Values that no longer deserialize into the signal's type (or a file that does
+/// not parse at all) fall back to the default, so changing a setting's type between versions
+/// only resets that setting. Writes are debounced and go through a temporary file, so a crash
+/// mid-write can not truncate the settings.
+#[derive(Resource)]
+pub struct PersistedSettings {
+    state: Arc<RwLock<PersistedSettingsState>>,
+}
+
+impl Clone for PersistedSettings {
+    fn clone(&self) -> Self {
+        Self {
+            state: Arc::clone(&self.state),
+        }
+    }
+}
+
+struct PersistedSettingsState {
+    path: PathBuf,
+    entries: BTreeMap<String, String>,
+    debounce: Duration,
+    /// Set by a change that `flush_if_due_at` has not seen yet.
+    changed: bool,
+    /// When `flush_if_due_at` saw the last change.
+    last_change: Option<Instant>,
+}
+
+impl PersistedSettings {
+    pub fn load(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let entries = read_entries(&path);
+        Self {
+            state: Arc::new(RwLock::new(PersistedSettingsState {
+                path,
+                entries,
+                debounce: Duration::from_millis(500),
+                changed: false,
+                last_change: None,
+            })),
+        }
+    }
+
+    pub fn set_debounce(&self, debounce: Duration) {
+        self.state.write().unwrap().debounce = debounce;
+    }
+
+    pub fn get<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
+        let state = self.state.read().unwrap();
+        let text = state.entries.get(key)?;
+        match ron::from_str::<T>(text) {
+            Ok(value) => Some(value),
+            Err(err) => {
+                warn!("ignoring persisted setting {:?}, it does not match the expected type: {}", key, err);
+                None
+            }
+        }
+    }
+
+    pub fn set<T: Serialize>(&self, key: &str, value: &T) {
+        let text = match ron::to_string(value) {
+            Ok(text) => text,
+            Err(err) => {
+                warn!("could not persist setting {:?}: {}", key, err);
+                return;
+            }
+        };
+        let mut state = self.state.write().unwrap();
+        state.entries.insert(key.into(), text);
+        state.changed = true;
+    }
+
+    /// A signal initialised from the setting `key` (or `default`) that is written back whenever
+    /// it changes. Must be called inside a scope.
+    pub fn signal<CTX, T>(&self, ctx: &mut CTX, key: impl Into<String>, default: T) -> Signal<CTX, T>
+    where
+        CTX: HasFgrCtx + 'static,
+        T: Serialize + DeserializeOwned + Clone + Send + Sync + 'static,
+    {
+        let key = key.into();
+        let signal = Signal::new(ctx, self.get(&key).unwrap_or(default));
+        let settings = self.clone();
+        ctx.fgr_create_effect_on_deferred(signal.clone(), move |_ctx, value, _| {
+            settings.set(&key, value);
+        });
+        signal
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        let state = self.state.read().unwrap();
+        state.changed || state.last_change.is_some()
+    }
+
+    /// `flush_if_due_at` the current time.
+    pub fn flush_if_due(&self) {
+        self.flush_if_due_at(Instant::now());
+    }
+
+    /// Writes the file if there are changes and none of them within the debounce window before
+    /// `now`, so a setting changed continuously, e.g. while dragging a splitter, is written once
+    /// it settles. A change counts from the first call that sees it, the plugin calls this every
+    /// frame.
+    pub fn flush_if_due_at(&self, now: Instant) {
+        let due = {
+            let mut state = self.state.write().unwrap();
+            if state.changed {
+                state.changed = false;
+                state.last_change = Some(now);
+            }
+            state.last_change.is_some_and(|last_change| now.saturating_duration_since(last_change) >= state.debounce)
+        };
+        if due {
+            self.flush();
+        }
+    }
+
+    pub fn flush(&self) {
+        let mut state = self.state.write().unwrap();
+        if !state.changed && state.last_change.is_none() {
+            return;
+        }
+        match write_entries(&state.path, &state.entries) {
+            Ok(()) => {
+                state.changed = false;
+                state.last_change = None;
+            }
+            Err(err) => warn!("could not write settings to {:?}: {}", state.path, err),
+        }
+    }
+}
+
+fn read_entries(path: &Path) -> BTreeMap<String, String> {
+    let Ok(text) = std::fs::read_to_string(path) else { return BTreeMap::new(); };
+    match ron::from_str(&text) {
+        Ok(entries) => entries,
+        Err(err) => {
+            warn!("could not parse settings file {:?}, starting with defaults: {}", path, err);
+            let _ = std::fs::rename(path, path.with_extension("ron.bak"));
+            BTreeMap::new()
+        }
+    }
+}
+
+fn write_entries(path: &Path, entries: &BTreeMap<String, String>) -> std::io::Result<()> {
+    let text = ron::ser::to_string_pretty(entries, ron::ser::PrettyConfig::default())
+        .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let tmp_path = path.with_extension("ron.tmp");
+    std::fs::write(&tmp_path, text)?;
+    std::fs::rename(&tmp_path, path)
+}
+
+/// Loads `PersistedSettings` from `path` as a resource and writes it back debounced and on exit.
+pub struct PersistedSettingsPlugin {
+    pub path: PathBuf,
+}
+
+impl Plugin for PersistedSettingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(PersistedSettings::load(self.path.clone()))
+            .add_systems(Last, (
+                |settings: Res<PersistedSettings>| settings.flush_if_due(),
+                |settings: Res<PersistedSettings>, mut exit_events: EventReader<AppExit>| {
+                    if exit_events.read().next().is_some() {
+                        settings.flush();
+                    }
+                },
+            ));
+    }
+}
+
+/// `PersistedSettings::signal` using the `PersistedSettings` resource.
+pub fn persisted_signal<T>(world: &mut World, key: impl Into<String>, default: T) -> Signal<World, T>
+where
+    T: Serialize + DeserializeOwned + Clone + Send + Sync + 'static,
+{
+    let settings = world.resource::<PersistedSettings>().clone();
+    settings.signal(world, key, default)
+}
@@ -0,0 +1,137 @@
+use std::collections::BTreeMap;
+
+use super::{history, reactive_vec::PatchLog, AccessorExt, BoxedAccessor, FgrExtensionMethods, HasFgrCtx, NodeRef, Signal};
+
+/// A structural change made to a `ReactiveMap`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum MapPatch<K, V> {
+    Insert { key: K, value: V },
+    Remove { key: K },
+    Update { key: K, value: V },
+    Clear,
+}
+
+/// A key ordered map that records its operations per batch, the map counterpart of `ReactiveVec`.
+pub struct ReactiveMap<CTX, K, V> {
+    entries: Signal<CTX, BTreeMap<K, V>>,
+    patch_log: Signal<CTX, PatchLog<MapPatch<K, V>>>,
+}
+
+impl<CTX, K, V> Clone for ReactiveMap<CTX, K, V> {
+    fn clone(&self) -> Self {
+        Self {
+            entries: self.entries.clone(),
+            patch_log: self.patch_log.clone(),
+        }
+    }
+}
+
+impl<CTX, K, V> ReactiveMap<CTX, K, V>
+where
+    CTX: HasFgrCtx + 'static,
+    K: Ord + Clone + Send + Sync + 'static,
+    V: Clone + Send + Sync + 'static,
+{
+    pub fn new(ctx: &mut CTX, entries: BTreeMap<K, V>) -> Self {
+        Self {
+            entries: Signal::new(ctx, entries),
+            patch_log: Signal::new(ctx, PatchLog::new()),
+        }
+    }
+
+    pub fn entries(&self) -> BoxedAccessor<CTX, BTreeMap<K, V>> {
+        self.entries.clone().into()
+    }
+
+    pub fn patches(&self) -> BoxedAccessor<CTX, Vec<MapPatch<K, V>>> {
+        self.patch_log.select(|patch_log| &patch_log.patches)
+    }
+
+    pub fn get(&self, ctx: &mut CTX, key: &K) -> Option<V> {
+        self.entries.value(ctx).get(key).cloned()
+    }
+
+    pub fn len(&self, ctx: &mut CTX) -> usize {
+        self.entries.value(ctx).len()
+    }
+
+    pub fn is_empty(&self, ctx: &mut CTX) -> bool {
+        self.entries.value(ctx).is_empty()
+    }
+
+    pub fn insert(&mut self, ctx: &mut CTX, key: K, value: V) {
+        let has_key = ctx.fgr_untrack(|ctx| self.entries.value(ctx).contains_key(&key));
+        match has_key {
+            true => self.record(ctx, MapPatch::Update { key, value }),
+            false => self.record(ctx, MapPatch::Insert { key, value }),
+        }
+    }
+
+    /// Calls `callback` with the value of `key`, if there is one, otherwise does nothing.
+    pub fn update<CALLBACK: FnOnce(&mut V)>(&mut self, ctx: &mut CTX, key: K, callback: CALLBACK) {
+        let Some(mut value) = ctx.fgr_untrack(|ctx| self.entries.value(ctx).get(&key).cloned()) else { return; };
+        callback(&mut value);
+        self.record(ctx, MapPatch::Update { key, value });
+    }
+
+    pub fn remove(&mut self, ctx: &mut CTX, key: K) {
+        let has_key = ctx.fgr_untrack(|ctx| self.entries.value(ctx).contains_key(&key));
+        if !has_key {
+            return;
+        }
+        self.record(ctx, MapPatch::Remove { key });
+    }
+
+    pub fn clear(&mut self, ctx: &mut CTX) {
+        self.record(ctx, MapPatch::Clear);
+    }
+
+    /// Applies `patch`, and records it with its inverse in the active `History` transaction.
+    fn record(&mut self, ctx: &mut CTX, patch: MapPatch<K, V>) {
+        if history::is_recording(ctx) {
+            let inverse = ctx.fgr_untrack(|ctx| inverse(&self.entries.value(ctx), &patch));
+            let id = Into::<NodeRef<CTX>>::into(&self.entries).id;
+            let this = self.clone();
+            history::record_patches(ctx, id, vec![patch.clone()], inverse, move |ctx, patches| this.clone().apply(ctx, patches));
+        }
+        self.apply(ctx, vec![patch]);
+    }
+
+    fn apply(&mut self, ctx: &mut CTX, patches: Vec<MapPatch<K, V>>) {
+        ctx.fgr_batch(|ctx| {
+            let update_count = ctx.fgr_ctx().update_count;
+            self.entries.update_value(ctx, |entries| {
+                for patch in &patches {
+                    apply(entries, patch);
+                }
+            });
+            self.patch_log.update_value(ctx, |patch_log| {
+                for patch in patches {
+                    patch_log.push(update_count, patch);
+                }
+            });
+        });
+    }
+}
+
+fn apply<K: Ord + Clone, V: Clone>(entries: &mut BTreeMap<K, V>, patch: &MapPatch<K, V>) {
+    match patch {
+        MapPatch::Insert { key, value } | MapPatch::Update { key, value } => {
+            entries.insert(key.clone(), value.clone());
+        }
+        MapPatch::Remove { key } => {
+            entries.remove(key);
+        }
+        MapPatch::Clear => entries.clear(),
+    }
+}
+
+/// The patches reverting `patch` on `entries`, the map before `patch`.
+fn inverse<K: Ord + Clone, V: Clone>(entries: &BTreeMap<K, V>, patch: &MapPatch<K, V>) -> Vec<MapPatch<K, V>> {
+    match patch {
+        MapPatch::Insert { key, .. } => vec![MapPatch::Remove { key: key.clone() }],
+        MapPatch::Remove { key } => entries.get(key).map(|value| MapPatch::Insert { key: key.clone(), value: value.clone() }).into_iter().collect(),
+        MapPatch::Update { key, .. } => entries.get(key).map(|value| MapPatch::Update { key: key.clone(), value: value.clone() }).into_iter().collect(),
+        MapPatch::Clear => entries.iter().map(|(key, value)| MapPatch::Insert { key: key.clone(), value: value.clone() }).collect(),
+    }
+}
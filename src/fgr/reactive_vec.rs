@@ -0,0 +1,178 @@
+use super::{history, AccessorExt, BoxedAccessor, FgrExtensionMethods, HasFgrCtx, NodeRef, Signal};
+
+/// A structural change made to a `ReactiveVec`. Indices refer to the list as it was when the
+/// patch was applied, so patches must be replayed in order.
+#[derive(Clone, Debug, PartialEq)]
+pub enum VecPatch<T> {
+    Insert { index: usize, value: T },
+    Remove { index: usize },
+    Move { from: usize, to: usize },
+    Update { index: usize, value: T },
+    Clear,
+}
+
+/// The patches recorded since the graph last updated. `update_count` is the value of
+/// `FgrCtx::update_count` they belong to, so the first write of a new batch starts a fresh list.
+pub(super) struct PatchLog<P> {
+    pub(super) update_count: u64,
+    pub(super) patches: Vec<P>,
+}
+
+impl<P> PatchLog<P> {
+    pub(super) fn new() -> Self {
+        Self {
+            update_count: 0,
+            patches: Vec::new(),
+        }
+    }
+
+    pub(super) fn push(&mut self, update_count: u64, patch: P) {
+        if self.update_count != update_count {
+            self.patches.clear();
+            self.update_count = update_count;
+        }
+        self.patches.push(patch);
+    }
+}
+
+/// A list that records insert/remove/move/update operations per batch.
+///
+/// `items` tracks the whole list, `patches` only the operations of the latest batch, so list
+/// renderers can patch their entities instead of diffing. Operations on an index out of range do
+/// nothing, as `ReactiveMap` does for missing keys.
+pub struct ReactiveVec<CTX, T> {
+    items: Signal<CTX, Vec<T>>,
+    patch_log: Signal<CTX, PatchLog<VecPatch<T>>>,
+}
+
+impl<CTX, T> Clone for ReactiveVec<CTX, T> {
+    fn clone(&self) -> Self {
+        Self {
+            items: self.items.clone(),
+            patch_log: self.patch_log.clone(),
+        }
+    }
+}
+
+impl<CTX: HasFgrCtx + 'static, T: Clone + Send + Sync + 'static> ReactiveVec<CTX, T> {
+    pub fn new(ctx: &mut CTX, items: Vec<T>) -> Self {
+        Self {
+            items: Signal::new(ctx, items),
+            patch_log: Signal::new(ctx, PatchLog::new()),
+        }
+    }
+
+    pub fn items(&self) -> BoxedAccessor<CTX, Vec<T>> {
+        self.items.clone().into()
+    }
+
+    pub fn patches(&self) -> BoxedAccessor<CTX, Vec<VecPatch<T>>> {
+        self.patch_log.select(|patch_log| &patch_log.patches)
+    }
+
+    pub fn len(&self, ctx: &mut CTX) -> usize {
+        self.items.value(ctx).len()
+    }
+
+    pub fn is_empty(&self, ctx: &mut CTX) -> bool {
+        self.items.value(ctx).is_empty()
+    }
+
+    pub fn push(&mut self, ctx: &mut CTX, value: T) {
+        let index = self.len(ctx);
+        self.record(ctx, VecPatch::Insert { index, value });
+    }
+
+    pub fn insert(&mut self, ctx: &mut CTX, index: usize, value: T) {
+        self.record(ctx, VecPatch::Insert { index, value });
+    }
+
+    pub fn remove(&mut self, ctx: &mut CTX, index: usize) {
+        self.record(ctx, VecPatch::Remove { index });
+    }
+
+    pub fn move_item(&mut self, ctx: &mut CTX, from: usize, to: usize) {
+        self.record(ctx, VecPatch::Move { from, to });
+    }
+
+    pub fn set(&mut self, ctx: &mut CTX, index: usize, value: T) {
+        self.record(ctx, VecPatch::Update { index, value });
+    }
+
+    pub fn update<CALLBACK: FnOnce(&mut T)>(&mut self, ctx: &mut CTX, index: usize, callback: CALLBACK) {
+        let Some(mut value) = ctx.fgr_untrack(|ctx| self.items.value(ctx).get(index).cloned()) else { return; };
+        callback(&mut value);
+        self.record(ctx, VecPatch::Update { index, value });
+    }
+
+    pub fn clear(&mut self, ctx: &mut CTX) {
+        self.record(ctx, VecPatch::Clear);
+    }
+
+    /// Applies `patch`, and records it with its inverse in the active `History` transaction.
+    fn record(&mut self, ctx: &mut CTX, patch: VecPatch<T>) {
+        let len = ctx.fgr_untrack(|ctx| self.len(ctx));
+        if !in_range(&patch, len) {
+            return;
+        }
+        if history::is_recording(ctx) {
+            let inverse = ctx.fgr_untrack(|ctx| inverse(&self.items.value(ctx), &patch));
+            let id = Into::<NodeRef<CTX>>::into(&self.items).id;
+            let this = self.clone();
+            history::record_patches(ctx, id, vec![patch.clone()], inverse, move |ctx, patches| this.clone().apply(ctx, patches));
+        }
+        self.apply(ctx, vec![patch]);
+    }
+
+    fn apply(&mut self, ctx: &mut CTX, patches: Vec<VecPatch<T>>) {
+        ctx.fgr_batch(|ctx| {
+            let update_count = ctx.fgr_ctx().update_count;
+            self.items.update_value(ctx, |items| {
+                for patch in &patches {
+                    apply(items, patch);
+                }
+            });
+            self.patch_log.update_value(ctx, |patch_log| {
+                for patch in patches {
+                    patch_log.push(update_count, patch);
+                }
+            });
+        });
+    }
+}
+
+/// Whether the indices of `patch` are in range of a list of `len` items.
+fn in_range<T>(patch: &VecPatch<T>, len: usize) -> bool {
+    match patch {
+        VecPatch::Insert { index, .. } => *index <= len,
+        VecPatch::Remove { index } | VecPatch::Update { index, .. } => *index < len,
+        VecPatch::Move { from, to } => *from < len && *to < len,
+        VecPatch::Clear => true,
+    }
+}
+
+fn apply<T: Clone>(items: &mut Vec<T>, patch: &VecPatch<T>) {
+    match patch {
+        VecPatch::Insert { index, value } => items.insert(*index, value.clone()),
+        VecPatch::Remove { index } => {
+            items.remove(*index);
+        }
+        VecPatch::Move { from, to } => {
+            let value = items.remove(*from);
+            items.insert(*to, value);
+        }
+        VecPatch::Update { index, value } => items[*index] = value.clone(),
+        VecPatch::Clear => items.clear(),
+    }
+}
+
+/// The patches reverting `patch` on `items`, the list before `patch`.
+fn inverse<T: Clone>(items: &[T], patch: &VecPatch<T>) -> Vec<VecPatch<T>> {
+    match patch {
+        VecPatch::Insert { index, .. } => vec![VecPatch::Remove { index: *index }],
+        VecPatch::Remove { index } => vec![VecPatch::Insert { index: *index, value: items[*index].clone() }],
+        VecPatch::Move { from, to } => vec![VecPatch::Move { from: *to, to: *from }],
+        VecPatch::Update { index, .. } => vec![VecPatch::Update { index: *index, value: items[*index].clone() }],
+        VecPatch::Clear => items.iter().cloned().enumerate().map(|(index, value)| VecPatch::Insert { index, value }).collect(),
+    }
+}
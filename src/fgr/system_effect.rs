@@ -0,0 +1,283 @@
+use std::{collections::HashMap, sync::{Arc, Mutex}};
+
+use bevy::ecs::{
+    component::{ComponentId, ComponentTicks, StorageType, Tick},
+    event::ManualEventReader,
+    removal_detection::RemovedComponentEntity,
+    storage::TableRow,
+    system::{IntoSystem, Resource, System},
+    world::World,
+};
+
+use super::{FgrCtx, FgrExtensionMethods, HasFgrCtx};
+
+/// Reactive helpers that only make sense for a Bevy `World`.
+pub trait FgrWorldExtensionMethods {
+    /// Runs `system` now and again on every `fgr_update` in which one of the components or
+    /// resources it accesses changed, including added and removed components and inserted and
+    /// removed resources. Components only used in `With`/`Without` filters are not tracked, and
+    /// a system that reads the whole world tracks every component and resource. The system's
+    /// param state is cached between runs and its `Commands` are applied right after each run.
+    /// Must be called inside a scope, the system stops with it.
+    ///
+    /// Bevy keeps change ticks per entity, so finding changes costs a visit of every entity with
+    /// a component the system reads, or of every component of every entity for a system reading
+    /// the whole world, once per update for all system effects together. Past
+    /// `SYSTEM_EFFECT_SCAN_LIMIT` rows the visit is skipped and the system runs on every update.
+    fn fgr_create_system_effect<M, S: IntoSystem<(), (), M>>(&mut self, system: S);
+}
+
+impl FgrWorldExtensionMethods for World {
+    fn fgr_create_system_effect<M, S: IntoSystem<(), (), M>>(&mut self, system: S) {
+        let index = self.get_resource_or_insert_with(ChangeIndex::default).clone();
+        let mut system_effect = SystemEffect::new(self, Box::new(IntoSystem::into_system(system)));
+        index.track(&system_effect, 1);
+        system_effect.run(self);
+        let system_effect = Arc::new(Mutex::new(system_effect));
+        self.fgr_on_update({
+            let index = index.clone();
+            let system_effect = Arc::clone(&system_effect);
+            move |world| {
+                let mut system_effect = system_effect.lock().unwrap();
+                if system_effect.is_stale(world, &index) {
+                    system_effect.run(world);
+                }
+            }
+        });
+        self.fgr_on_cleanup(move |_world| index.track(&system_effect.lock().unwrap(), -1));
+    }
+}
+
+/// The most rows of component storage visited to find the newest change of one component, or of
+/// the whole world. Anything bigger counts as changed on every update, running a system effect
+/// is then expected to cost about as much as the visit would.
+pub const SYSTEM_EFFECT_SCAN_LIMIT: usize = 1 << 16;
+
+/// The newest change of every component a system effect reads, shared by the system effects
+/// of a world so the storages are scanned once per update rather than once per system effect.
+#[derive(Resource, Clone, Default)]
+struct ChangeIndex {
+    state: Arc<Mutex<ChangeIndexState>>,
+}
+
+#[derive(Default)]
+struct ChangeIndexState {
+    /// The `FgrCtx::update_count` and change tick the newest changes were found at. They are
+    /// found again once either moves on, as running a system effect moves the change tick.
+    found_at: Option<(u64, Tick)>,
+    /// The number of system effects reading each component and the component's newest change.
+    components: HashMap<ComponentId, (usize, Option<Tick>)>,
+    /// The number of system effects reading the whole world and its newest change.
+    world: (usize, Option<Tick>),
+}
+
+impl ChangeIndex {
+    fn track(&self, system_effect: &SystemEffect, users: isize) {
+        let mut state = self.state.lock().unwrap();
+        if system_effect.reads_all {
+            state.world.0 = state.world.0.saturating_add_signed(users);
+        }
+        for component in &system_effect.components {
+            let entry = state.components.entry(component.id).or_default();
+            entry.0 = entry.0.saturating_add_signed(users);
+            if entry.0 == 0 {
+                state.components.remove(&component.id);
+            }
+        }
+        state.found_at = None;
+    }
+
+    /// Whether `id`, or with `None` anything in the world, changed between the two ticks.
+    fn changed_since(&self, world: &mut World, id: Option<ComponentId>, last_run: Tick, this_run: Tick) -> bool {
+        let mut state = self.state.lock().unwrap();
+        let found_at = (world.fgr_ctx().update_count, this_run);
+        if state.found_at != Some(found_at) {
+            state.found_at = Some(found_at);
+            let ChangeIndexState { components, world: (users, world_newest), .. } = &mut *state;
+            for (id, (_, newest)) in components.iter_mut() {
+                *newest = newest_component_change(world, *id, this_run);
+            }
+            if *users > 0 {
+                *world_newest = newest_world_change(world, this_run);
+            }
+        }
+        let newest = match id {
+            Some(id) => state.components.get(&id).and_then(|(_, newest)| *newest),
+            None => state.world.1,
+        };
+        newest.is_some_and(|newest| newest.is_newer_than(last_run, this_run))
+    }
+}
+
+/// The newer of `newest` and the changes in `ticks`, as seen from `this_run`.
+fn newer(newest: Option<Tick>, ticks: impl Iterator<Item = ComponentTicks>, this_run: Tick) -> Option<Tick> {
+    ticks.fold(newest, |newest, ticks| match newest {
+        Some(newest) if !ticks.last_changed_tick().is_newer_than(newest, this_run) => Some(newest),
+        _ => Some(ticks.last_changed_tick()),
+    })
+}
+
+/// The newest change of component `id`, or `this_run` past `SYSTEM_EFFECT_SCAN_LIMIT`.
+fn newest_component_change(world: &World, id: ComponentId, this_run: Tick) -> Option<Tick> {
+    let info = world.components().get_info(id)?;
+    let rows = match info.storage_type() {
+        StorageType::Table => {
+            world.storages().tables.iter().filter(|table| table.has_column(id)).map(|table| table.entity_count()).sum()
+        }
+        StorageType::SparseSet => world.storages().sparse_sets.get(id).map_or(0, |sparse_set| sparse_set.len()),
+    };
+    if rows > SYSTEM_EFFECT_SCAN_LIMIT {
+        return Some(this_run);
+    }
+    match info.storage_type() {
+        StorageType::Table => world.storages().tables.iter().fold(None, |newest, table| {
+            let Some(column) = table.get_column(id) else { return newest; };
+            newer(newest, (0..column.len()).filter_map(|row| column.get_ticks(TableRow::from_usize(row))), this_run)
+        }),
+        StorageType::SparseSet => {
+            let sparse_set = world.storages().sparse_sets.get(id)?;
+            let entities = world.archetypes().iter().filter(|archetype| archetype.contains(id)).flat_map(|archetype| archetype.entities());
+            newer(None, entities.filter_map(|entity| sparse_set.get_ticks(entity.id())), this_run)
+        }
+    }
+}
+
+/// The newest change of any component or resource, or `this_run` past
+/// `SYSTEM_EFFECT_SCAN_LIMIT`.
+fn newest_world_change(world: &World, this_run: Tick) -> Option<Tick> {
+    // the graph itself changes on every update
+    let fgr_ctx = world.components().resource_id::<FgrCtx<World>>();
+    let storages = world.storages();
+    let table_rows: usize = storages.tables.iter().map(|table| table.entity_count() * table.component_count()).sum();
+    let sparse_set_rows: usize = storages.sparse_sets.iter().map(|(_, sparse_set)| sparse_set.len()).sum();
+    if table_rows + sparse_set_rows > SYSTEM_EFFECT_SCAN_LIMIT {
+        return Some(this_run);
+    }
+    let mut newest = None;
+    for table in storages.tables.iter() {
+        for column in table.iter() {
+            newest = newer(newest, (0..column.len()).filter_map(|row| column.get_ticks(TableRow::from_usize(row))), this_run);
+        }
+    }
+    for archetype in world.archetypes().iter() {
+        for id in archetype.sparse_set_components() {
+            let Some(sparse_set) = storages.sparse_sets.get(id) else { continue; };
+            newest = newer(newest, archetype.entities().iter().filter_map(|entity| sparse_set.get_ticks(entity.id())), this_run);
+        }
+    }
+    let resources = storages.resources.iter().filter(|(id, _)| Some(*id) != fgr_ctx).filter_map(|(_, data)| data.get_ticks());
+    let non_send_resources = storages.non_send_resources.iter().filter_map(|(_, data)| data.get_ticks());
+    newer(newest, resources.chain(non_send_resources), this_run)
+}
+
+struct SystemEffect {
+    system: Box<dyn System<In = (), Out = ()>>,
+    reads_all: bool,
+    resources: Vec<AccessedResource>,
+    components: Vec<AccessedComponent>,
+    /// With `reads_all`, a removal reader per component anything was removed from.
+    removals: HashMap<ComponentId, ManualEventReader<RemovedComponentEntity>>,
+    /// With `reads_all`, the resources present at the last run, so inserting or removing one is
+    /// a change.
+    present_resources: Vec<ComponentId>,
+}
+
+struct AccessedResource {
+    id: ComponentId,
+    /// Whether the resource existed at the last run, inserting or removing it is a change.
+    present: bool,
+}
+
+struct AccessedComponent {
+    id: ComponentId,
+    removals: ManualEventReader<RemovedComponentEntity>,
+}
+
+impl SystemEffect {
+    fn new(world: &mut World, mut system: Box<dyn System<In = (), Out = ()>>) -> Self {
+        system.initialize(world);
+        let access = system.component_access();
+        let reads_all = access.has_read_all();
+        let ids: Vec<ComponentId> = access.reads_and_writes().collect();
+        let mut resources = Vec::new();
+        let mut components = Vec::new();
+        for id in ids {
+            let components_info = world.components();
+            let is_resource = components_info.get_info(id).and_then(|info| info.type_id()).is_some_and(|type_id| {
+                components_info.get_resource_id(type_id) == Some(id)
+            });
+            match is_resource {
+                true => resources.push(AccessedResource { id, present: resource_ticks(world, id).is_some() }),
+                false => components.push(AccessedComponent { id, removals: ManualEventReader::default() }),
+            }
+        }
+        Self {
+            system,
+            reads_all,
+            resources,
+            components,
+            removals: HashMap::new(),
+            present_resources: Vec::new(),
+        }
+    }
+
+    fn run(&mut self, world: &mut World) {
+        self.system.run((), world);
+        // Treat the changes made by the system (and its commands) as seen, so it does not
+        // invalidate itself.
+        let tick = world.increment_change_tick();
+        self.system.set_last_run(tick);
+        for resource in &mut self.resources {
+            resource.present = resource_ticks(world, resource.id).is_some();
+        }
+        for component in &mut self.components {
+            if let Some(events) = world.removed_components().get(component.id) {
+                component.removals.clear(events);
+            }
+        }
+        if self.reads_all {
+            for (id, events) in world.removed_components().iter() {
+                self.removals.entry(*id).or_default().clear(events);
+            }
+            self.present_resources = present_resources(world);
+        }
+    }
+
+    fn is_stale(&mut self, world: &mut World, index: &ChangeIndex) -> bool {
+        let last_run = self.system.get_last_run();
+        let this_run = world.read_change_tick();
+        // every removal reader is drained, so removals are not reported twice.
+        let mut stale = false;
+        for component in &mut self.components {
+            if let Some(events) = world.removed_components().get(component.id) {
+                stale |= !component.removals.is_empty(events);
+                component.removals.clear(events);
+            }
+        }
+        if self.reads_all {
+            for (id, events) in world.removed_components().iter() {
+                let removals = self.removals.entry(*id).or_default();
+                stale |= !removals.is_empty(events);
+                removals.clear(events);
+            }
+            stale = stale || present_resources(world) != self.present_resources || index.changed_since(world, None, last_run, this_run);
+        }
+        stale = stale || self.resources.iter().any(|resource| {
+            let ticks = resource_ticks(world, resource.id);
+            ticks.is_some() != resource.present || ticks.is_some_and(|ticks| ticks.is_changed(last_run, this_run))
+        });
+        stale || self.components.iter().any(|component| index.changed_since(world, Some(component.id), last_run, this_run))
+    }
+}
+
+fn resource_ticks(world: &World, id: ComponentId) -> Option<ComponentTicks> {
+    world.get_resource_change_ticks_by_id(id)
+        .or_else(|| world.storages().non_send_resources.get(id).and_then(|resource| resource.get_ticks()))
+}
+
+fn present_resources(world: &World) -> Vec<ComponentId> {
+    let storages = world.storages();
+    let resources = storages.resources.iter().filter(|(_, data)| data.is_present()).map(|(id, _)| id);
+    let non_send_resources = storages.non_send_resources.iter().filter(|(_, data)| data.is_present()).map(|(id, _)| id);
+    resources.chain(non_send_resources).collect()
+}
@@ -0,0 +1,147 @@
+use std::{collections::VecDeque, sync::{Arc, RwLock}, time::Duration};
+
+use bevy::{prelude::World, time::Time};
+
+use super::{BoxedAccessor, EffectDeps, FgrExtensionMethods, Signal};
+
+// Time based primitives. They are checked on every `fgr_update` against Bevy's `Time` resource,
+// so tests can drive them by advancing a manually inserted `Time`. Like any effect they belong
+// to the scope they are created in and stop when it is disposed.
+
+pub(super) fn now(world: &World) -> Duration {
+    world.get_resource::<Time>().map(|time| time.elapsed()).unwrap_or_default()
+}
+
+fn initial_value<SOURCE: EffectDeps<World>>(world: &mut World, source: &SOURCE) -> SOURCE::Value {
+    world.fgr_untrack(|world| source.read(world))
+}
+
+/// Follows `source`, but only once it has stopped changing for `duration`.
+pub fn debounce<A, SOURCE>(world: &mut World, source: SOURCE, duration: Duration) -> BoxedAccessor<World, A>
+where
+    A: Clone + Send + Sync + 'static,
+    SOURCE: EffectDeps<World, Value=A> + Send + Sync + 'static,
+{
+    let initial = initial_value(world, &source);
+    let output = Signal::new(world, initial);
+    let pending: Arc<RwLock<Option<(Duration, A)>>> = Arc::new(RwLock::new(None));
+    let pending2 = Arc::clone(&pending);
+    world.fgr_create_effect_on_deferred(source, move |world, value, _| {
+        *pending2.write().unwrap() = Some((now(world) + duration, value.clone()));
+    });
+    world.fgr_on_update({
+        let mut output = output.clone();
+        move |world| {
+            let due = pending.read().unwrap().as_ref().is_some_and(|(deadline, _)| now(world) >= *deadline);
+            if !due {
+                return;
+            }
+            let (_, value) = pending.write().unwrap().take().unwrap();
+            output.update_value(world, |x| *x = value);
+        }
+    });
+    output.into()
+}
+
+/// Follows `source` at most once per `duration`. The first change passes straight through,
+/// later ones within the window are delivered when it ends (only the latest one).
+pub fn throttle<A, SOURCE>(world: &mut World, source: SOURCE, duration: Duration) -> BoxedAccessor<World, A>
+where
+    A: Clone + Send + Sync + 'static,
+    SOURCE: EffectDeps<World, Value=A> + Send + Sync + 'static,
+{
+    struct ThrottleState<A> {
+        last_emit: Option<Duration>,
+        pending: Option<A>,
+    }
+    let initial = initial_value(world, &source);
+    let output = Signal::new(world, initial);
+    let state = Arc::new(RwLock::new(ThrottleState { last_emit: None, pending: None }));
+    world.fgr_create_effect_on_deferred(source, {
+        let state = Arc::clone(&state);
+        let mut output = output.clone();
+        move |world, value, _| {
+            let now = now(world);
+            let mut state = state.write().unwrap();
+            let window_open = state.last_emit.is_none_or(|last_emit| now >= last_emit + duration);
+            if window_open {
+                state.last_emit = Some(now);
+                state.pending = None;
+                drop(state);
+                let value = value.clone();
+                output.update_value(world, |x| *x = value);
+            } else {
+                state.pending = Some(value.clone());
+            }
+        }
+    });
+    world.fgr_on_update({
+        let mut output = output.clone();
+        move |world| {
+            let now = now(world);
+            let value = {
+                let mut state = state.write().unwrap();
+                let due = state.pending.is_some() && state.last_emit.is_none_or(|last_emit| now >= last_emit + duration);
+                if !due {
+                    return;
+                }
+                state.last_emit = Some(now);
+                state.pending.take().unwrap()
+            };
+            output.update_value(world, |x| *x = value);
+        }
+    });
+    output.into()
+}
+
+/// Replays every change of `source` after `duration`. Changes that fall due in the same update
+/// are set one after the other, so effects on the result see each of them.
+pub fn delayed<A, SOURCE>(world: &mut World, source: SOURCE, duration: Duration) -> BoxedAccessor<World, A>
+where
+    A: Clone + Send + Sync + 'static,
+    SOURCE: EffectDeps<World, Value=A> + Send + Sync + 'static,
+{
+    let initial = initial_value(world, &source);
+    let output = Signal::new(world, initial);
+    let queue: Arc<RwLock<VecDeque<(Duration, A)>>> = Arc::new(RwLock::new(VecDeque::new()));
+    let queue2 = Arc::clone(&queue);
+    world.fgr_create_effect_on_deferred(source, move |world, value, _| {
+        queue2.write().unwrap().push_back((now(world) + duration, value.clone()));
+    });
+    world.fgr_on_update({
+        let mut output = output.clone();
+        move |world| {
+            let now = now(world);
+            let mut due = Vec::new();
+            {
+                let mut queue = queue.write().unwrap();
+                while queue.front().is_some_and(|(deadline, _)| now >= *deadline) {
+                    due.push(queue.pop_front().unwrap().1);
+                }
+            }
+            for value in due {
+                output.update_value(world, |x| *x = value);
+            }
+        }
+    });
+    output.into()
+}
+
+/// Counts the number of whole `period`s elapsed since it was created.
+pub fn interval(world: &mut World, period: Duration) -> BoxedAccessor<World, u64> {
+    let output = Signal::new(world, 0u64);
+    let start = now(world);
+    world.fgr_on_update({
+        let mut output = output.clone();
+        move |world| {
+            if period.is_zero() {
+                return;
+            }
+            let ticks = (now(world).saturating_sub(start).as_nanos() / period.as_nanos()) as u64;
+            if world.fgr_untrack(|world| *output.value(world)) != ticks {
+                output.update_value(world, |x| *x = ticks);
+            }
+        }
+    });
+    output.into()
+}
@@ -1,2 +1,5 @@
+// lets `view!` expansions name this crate from inside it as well
+extern crate self as bevy_editor_experiment_lib;
+
 pub mod fgr;
 pub mod ui;
@@ -5,45 +5,44 @@ mod tests;
 
 use bevy_editor_experiment_lib::{
     cloned,
-    fgr::{print_graph, ConstAccessor, FgrExtensionMethods, RootScope, Signal},
-    ui::{self, UiComponent},
+    fgr::{print_graph, AccessorExt, FgrExtensionMethods, History, RootScope, Signal},
+    ui::{self, view, Theme, ThemePlugin, ThemeTokens, UiDocumentPlugin, UiSignals},
 };
 
 fn main() {
     let mut app = App::new();
-    app.add_plugins(DefaultPlugins)
+    app.add_plugins(DefaultPlugins.set(AssetPlugin {
+            // themes and UI documents are reloaded as their files are edited
+            watch_for_changes_override: Some(cfg!(feature = "dev")),
+            ..default()
+        }))
+        .add_plugins((ThemePlugin::from_asset("themes/editor.theme.ron"), UiDocumentPlugin))
         .insert_resource(WinitSettings::desktop_app())
-        .add_systems(Startup, setup);
+        .add_systems(Startup, setup)
+        .add_systems(Update, (undo_redo, toggle_theme));
     let scope = ui::render(
         &mut app,
         |world| {
+            let history = History::new(world);
+            world.insert_resource(history);
             let checked = Signal::new(world, false);
-            world.fgr_create_effect(cloned!((checked) => move |world| {
-                println!("checked = {}", *checked.value(world));
-            }));
+            world.fgr_create_effect_on(checked.clone(), |_world, checked, _prev_checked| {
+                println!("checked = {}", checked);
+            });
             //print_graph((&checked).into());
-            let children = [
-                ui::CheckBox::run(
-                    world,
-                    ui::CheckBoxProps {
-                        on_changed: Some(Box::new(cloned!((checked) => move |world, value| {
-                            checked.update_value(world, |old_value| *old_value = value);
-                            //print_graph((&checked).into());
-                        }))),
-                    },
-                ),
-                ui::TextBox::run(
-                    world,
-                    ui::TextBoxProps {
-                        width: Some(ConstAccessor::new(Val::Px(200.0)).into()),
-                        contents: Some(ConstAccessor::new("testing textbox".into()).into()),
-                        ..Default::default()
-                    }
-                ),
-            ];
-            let mut entity = world.spawn(NodeBundle { ..default() });
-            entity.push_children(&children);
-            return entity.id();
+            let check_box_state = checked.map(world, |checked| if *checked { "checked" } else { "unchecked" }.to_string());
+            world.resource_mut::<UiSignals>().insert("check_box_state", check_box_state);
+            let status = world.resource::<AssetServer>().load("ui/status.ui.ron");
+            view! { world,
+                <ui::Row gap={ui::px(8.0)} align={AlignItems::Center}>
+                    <ui::CheckBox on:changed={cloned!((checked) => move |world, value| {
+                        checked.update_value(world, |old_value| *old_value = value);
+                        //print_graph((&checked).into());
+                    })} />
+                    <ui::TextBox width={ui::px(200.0)} contents="testing textbox" />
+                    <ui::Document source={status} />
+                </ui::Row>
+            }
         }
     );
     app.insert_resource(scope)
@@ -64,3 +63,29 @@ fn setup(mut commands: Commands) {
     // ui camera
     commands.spawn(Camera2dBundle::default());
 }
+
+fn undo_redo(world: &mut World) {
+    let Some(keys) = world.get_resource::<ButtonInput<KeyCode>>() else { return; };
+    let ctrl = keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+    let shift = keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    let undo = ctrl && !shift && keys.just_pressed(KeyCode::KeyZ);
+    let redo = ctrl && (keys.just_pressed(KeyCode::KeyY) || (shift && keys.just_pressed(KeyCode::KeyZ)));
+    let Some(mut history) = world.get_resource::<History<World>>().cloned() else { return; };
+    if undo {
+        history.undo(world);
+    } else if redo {
+        history.redo(world);
+    }
+}
+
+/// F2 switches between the dark and light presets, until the theme file next changes.
+fn toggle_theme(world: &mut World) {
+    let Some(keys) = world.get_resource::<ButtonInput<KeyCode>>() else { return; };
+    if !keys.just_pressed(KeyCode::F2) {
+        return;
+    }
+    let Some(theme) = world.get_resource::<Theme>().cloned() else { return; };
+    let tokens = theme.tokens();
+    let light = world.fgr_untrack(|world| *tokens.value(world) == ThemeTokens::light());
+    theme.set(world, if light { ThemeTokens::dark() } else { ThemeTokens::light() });
+}
@@ -0,0 +1,58 @@
+use std::sync::Arc;
+
+use bevy_editor_experiment_lib::fgr::*;
+
+#[test]
+fn test_accessor_ext() {
+    struct Ctx {
+        fgr_ctx: FgrCtx<Ctx>,
+    }
+    impl HasFgrCtx for Ctx {
+        fn fgr_ctx<'a>(&'a mut self) -> impl std::ops::DerefMut<Target=FgrCtx<Ctx>> + 'a {
+            &mut self.fgr_ctx
+        }
+    }
+    let mut ctx = Ctx { fgr_ctx: FgrCtx::new() };
+    let ctx = &mut ctx;
+    let mut sa = Signal::new(ctx, (1, String::from("a")));
+    let mut sb = Signal::new(ctx, 10);
+    let (doubled, label, label_len, zipped, mut scope) = ctx.fgr_create_root(|ctx, scope| {
+        let doubled = sa.map(ctx, |(a, _)| *a * 2);
+        let label = sa.select(|(_, label)| label);
+        let label_len = label.derive(|label| label.len());
+        let zipped = doubled.zip(ctx, &sb);
+        (doubled, label, label_len, zipped, scope)
+    });
+    // selects of selects and of derived accessors
+    let label_again = label.select(|label| label);
+    let label_len_again = label_len.select(|len| len);
+    assert_eq!(*doubled.value(ctx), 2);
+    assert_eq!(*label.value(ctx), "a");
+    assert_eq!(*label_len.value(ctx), 1);
+    assert_eq!(*zipped.value(ctx), (2, 10));
+    sa.update_value(ctx, |(a, label)| {
+        *a = 2;
+        label.push('b');
+    });
+    sb.update_value(ctx, |b| *b = 20);
+    assert_eq!(*doubled.value(ctx), 4);
+    assert_eq!(*label.value(ctx), "ab");
+    assert_eq!(*label_len.value(ctx), 2);
+    assert_eq!(*zipped.value(ctx), (4, 20));
+    assert_eq!(*label_again.value(ctx), "ab");
+    let held = label_len_again.value(ctx);
+    assert_eq!(*label_len.value(ctx), *held);
+    drop(held);
+    // a select over a derived accessor lets go of the derived value with its read
+    let shared = Arc::new(());
+    let derived_shared = BoxedAccessor::derived({
+        let shared = Arc::clone(&shared);
+        move |_| Arc::clone(&shared)
+    });
+    let selected_shared = derived_shared.select(|shared| shared);
+    let read = selected_shared.value(ctx);
+    assert_eq!(Arc::strong_count(&read), 3);
+    drop(read);
+    assert_eq!(Arc::strong_count(&shared), 2);
+    scope.dispose(ctx);
+}
@@ -0,0 +1,85 @@
+use std::{sync::{atomic::{AtomicUsize, Ordering}, Arc}, time::Duration};
+
+use bevy::prelude::*;
+use bevy_editor_experiment_lib::fgr::{Animatable, *};
+
+fn advance(world: &mut World, millis: u64) {
+    world.resource_mut::<Time>().advance_by(Duration::from_millis(millis));
+    world.fgr_update();
+}
+
+fn counting_target(source: &Signal<World, f32>, evaluations: &Arc<AtomicUsize>) -> BoxedAccessor<World, f32> {
+    let evaluations = Arc::clone(evaluations);
+    source.derive(move |x| {
+        evaluations.fetch_add(1, Ordering::SeqCst);
+        *x
+    })
+}
+
+#[test]
+fn test_tween() {
+    let mut world = World::new();
+    world.insert_resource(FgrCtx::<World>::new());
+    world.insert_resource(Time::<()>::default());
+    let world = &mut world;
+    let mut source = Signal::new(world, 0.0f32);
+    let evaluations = Arc::new(AtomicUsize::new(0));
+    let (animated, mut scope) = world.fgr_create_root(|world, scope| {
+        (tween(world, counting_target(&source, &evaluations), Duration::from_millis(100), Easing::Linear), scope)
+    });
+    assert_eq!(*animated.value(world), 0.0);
+    source.update_value(world, |x| *x = 10.0);
+    assert_eq!(*animated.value(world), 0.0);
+    advance(world, 50);
+    assert!((*animated.value(world) - 5.0).abs() < 0.001);
+    // retargeting mid-flight continues from the current value.
+    source.update_value(world, |x| *x = 0.0);
+    advance(world, 50);
+    assert!((*animated.value(world) - 2.5).abs() < 0.001);
+    advance(world, 60);
+    assert_eq!(*animated.value(world), 0.0);
+    // settled, so further updates do not re-evaluate it.
+    let settled_evaluations = evaluations.load(Ordering::SeqCst);
+    advance(world, 100);
+    advance(world, 100);
+    assert_eq!(evaluations.load(Ordering::SeqCst), settled_evaluations);
+    scope.dispose(world);
+}
+
+#[test]
+fn test_spring() {
+    let mut world = World::new();
+    world.insert_resource(FgrCtx::<World>::new());
+    world.insert_resource(Time::<()>::default());
+    let world = &mut world;
+    let mut source = Signal::new(world, 0.0f32);
+    let evaluations = Arc::new(AtomicUsize::new(0));
+    let (animated, mut scope) = world.fgr_create_root(|world, scope| {
+        (spring(world, counting_target(&source, &evaluations), 200.0, 20.0), scope)
+    });
+    source.update_value(world, |x| *x = 1.0);
+    advance(world, 16);
+    let first_step = *animated.value(world);
+    assert!(first_step > 0.0 && first_step < 1.0);
+    let mut frames = 0;
+    while *animated.value(world) != 1.0 {
+        advance(world, 16);
+        frames += 1;
+        assert!(frames < 500, "spring did not settle");
+    }
+    let settled_evaluations = evaluations.load(Ordering::SeqCst);
+    advance(world, 16);
+    advance(world, 16);
+    assert_eq!(evaluations.load(Ordering::SeqCst), settled_evaluations);
+    scope.dispose(world);
+}
+
+#[test]
+fn test_animatable_values() {
+    assert_eq!(Val::Px(0.0).lerp(&Val::Px(10.0), 0.5), Val::Px(5.0));
+    assert_eq!(Val::Px(0.0).lerp(&Val::Percent(10.0), 0.5), Val::Px(0.0));
+    assert_eq!(Val::Px(0.0).lerp(&Val::Percent(10.0), 1.0), Val::Percent(10.0));
+    assert_eq!(Animatable::lerp(&Vec2::ZERO, &Vec2::new(2.0, 4.0), 0.5), Vec2::new(1.0, 2.0));
+    let grey = Color::BLACK.lerp(&Color::WHITE, 0.5).to_linear();
+    assert!((grey.red - 0.5).abs() < 0.001 && (grey.alpha - 1.0).abs() < 0.001);
+}
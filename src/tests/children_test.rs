@@ -0,0 +1,53 @@
+use bevy::prelude::*;
+use bevy_editor_experiment_lib::{fgr::*, ui::{component, view, Panel, PanelProps, Slot, UiComponent}};
+
+/// Mounts its children one level down, inside an inner node.
+#[component]
+fn frame(world: &mut World, #[prop(optional)] mut children: Slot) -> Entity {
+    let inner_id = world.spawn((Name::new("inner"), NodeBundle::default())).id();
+    Frame::mount_children(world, inner_id, &mut children);
+    world.spawn(NodeBundle::default()).add_child(inner_id).id()
+}
+
+fn child_names(world: &World, entity: Entity) -> Vec<String> {
+    world.get::<Children>(entity)
+        .map(|children| children.iter().map(|child| world.get::<Name>(*child).map_or("?".to_string(), |name| name.to_string())).collect())
+        .unwrap_or_default()
+}
+
+#[test]
+fn test_children_and_slots() {
+    let mut world = World::new();
+    world.insert_resource(FgrCtx::<World>::new());
+    let world = &mut world;
+    let (panel_id, frame_id, built_panel_id, mut scope) = world.fgr_create_root(|world, scope| {
+        let panel_id = view! { world,
+            <Panel>
+                <slot:header>
+                    {world.spawn(Name::new("title")).id()}
+                </slot:header>
+                {world.spawn(Name::new("content")).id()}
+            </Panel>
+        };
+        let frame_id = view! { world,
+            <Frame>
+                {world.spawn(Name::new("a")).id()}
+                {world.spawn(Name::new("b")).id()}
+            </Frame>
+        };
+        let built_panel_id = Panel::run(world, PanelProps::new()
+            .footer(|world: &mut World| vec![world.spawn(Name::new("status")).id()]));
+        (panel_id, frame_id, built_panel_id, scope)
+    });
+    assert_eq!(child_names(world, panel_id), vec!["header", "body"]);
+    let sections = world.get::<Children>(panel_id).unwrap().to_vec();
+    assert_eq!(child_names(world, sections[0]), vec!["title"]);
+    assert_eq!(child_names(world, sections[1]), vec!["content"]);
+    assert_eq!(child_names(world, frame_id), vec!["inner"]);
+    let inner_id = world.get::<Children>(frame_id).unwrap()[0];
+    assert_eq!(child_names(world, inner_id), vec!["a", "b"]);
+    assert_eq!(child_names(world, built_panel_id), vec!["footer"]);
+    scope.dispose(world);
+    assert!(world.get_entity(panel_id).is_none());
+    assert!(world.get_entity(sections[0]).is_none());
+}
@@ -0,0 +1,6 @@
+/// The errors `view!` and `#[component]` give for wrong input, the expected output is next to
+/// each case in `tests/ui`. Run with `TRYBUILD=overwrite` to update it.
+#[test]
+fn test_compile_errors() {
+    trybuild::TestCases::new().compile_fail("tests/ui/*.rs");
+}
@@ -0,0 +1,64 @@
+use bevy::prelude::*;
+use bevy_editor_experiment_lib::{fgr::*, ui::{self, component, view, MaybeReactive, UiComponent, ValExt}};
+
+#[component]
+fn label(
+    world: &mut World,
+    text: String,
+    #[prop(default = 20.0)] font_size: f32,
+    #[prop(optional)] width: MaybeReactive<Val>,
+) -> Entity {
+    let entity = world.spawn((Name::new(text), TextBundle::from_section("", TextStyle { font_size, ..default() }))).id();
+    world.fgr_create_effect(move |world| {
+        let width = *width.value(world);
+        world.get_mut::<Style>(entity).unwrap().width = width;
+    });
+    entity
+}
+
+#[component]
+fn bar(world: &mut World, #[prop(optional)] width: MaybeReactive<Val>) -> Entity {
+    let entity = world.spawn(NodeBundle::default()).id();
+    world.fgr_create_effect(move |world| {
+        let width = *width.value(world);
+        world.get_mut::<Style>(entity).unwrap().width = width;
+    });
+    entity
+}
+
+#[component]
+fn spacer(world: &mut World, #[prop(default = ui::px(8.0).into())] size: MaybeReactive<Val>) -> Entity {
+    let size = world.fgr_untrack(|world| *size.value(world));
+    world.spawn(NodeBundle { style: Style { width: size, height: size, ..default() }, ..default() }).id()
+}
+
+#[test]
+fn test_component() {
+    let mut world = World::new();
+    world.insert_resource(FgrCtx::<World>::new());
+    let world = &mut world;
+    let mut width = Signal::new(world, ui::px(100.0));
+    let (label_id, half_label_id, spacer_id, viewed_spacer_id) = world.fgr_create_root(|world, _scope| {
+        let label_id = Label::run(world, LabelProps::new("hello").width(width.clone()));
+        let half_label_id = view! { world,
+            <Bar width={
+                let width = width.clone();
+                move |world| match *width.value(world) {
+                    Val::Px(width) => (width / 2.0).px(),
+                    width => width,
+                }
+            } />
+        };
+        let spacer_id = Spacer::run(world, SpacerProps::default());
+        let viewed_spacer_id = view!(world, <Spacer size={4.0.px()} />);
+        (label_id, half_label_id, spacer_id, viewed_spacer_id)
+    });
+    assert_eq!(world.get::<Name>(label_id).unwrap().as_str(), "hello");
+    assert_eq!(world.get::<Text>(label_id).unwrap().sections[0].style.font_size, 20.0);
+    assert_eq!(world.get::<Style>(label_id).unwrap().width, ui::px(100.0));
+    width.update_value(world, |x| *x = ui::px(50.0));
+    assert_eq!(world.get::<Style>(label_id).unwrap().width, ui::px(50.0));
+    assert_eq!(world.get::<Style>(half_label_id).unwrap().width, ui::px(25.0));
+    assert_eq!(world.get::<Style>(spacer_id).unwrap().width, ui::px(8.0));
+    assert_eq!(world.get::<Style>(viewed_spacer_id).unwrap().height, ui::px(4.0));
+}
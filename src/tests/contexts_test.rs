@@ -0,0 +1,139 @@
+use bevy::{app::{App, AppLabel, SubApp, Update}, ecs::{schedule::ScheduleLabel, world::DeferredWorld}, prelude::*};
+use bevy_editor_experiment_lib::{cloned, fgr::*};
+
+#[test]
+fn test_data_ctx() {
+    let mut ctx = FgrDataCtx::new(vec![1, 2]);
+    let ctx = &mut ctx;
+    let mut extra = Signal::new(ctx, 0);
+    let (sum, _scope) = ctx.fgr_create_root(|ctx, scope| {
+        let extra = extra.clone();
+        let sum = Memo::new(ctx, move |ctx| ctx.iter().sum::<i32>() + *extra.value(ctx));
+        (sum, scope)
+    });
+    assert_eq!(*sum.value(ctx), 3);
+    ctx.push(3);
+    extra.update_value(ctx, |x| *x = 10);
+    assert_eq!(*sum.value(ctx), 16);
+}
+
+#[test]
+fn test_missing_world_ctx() {
+    let mut world = World::new();
+    let err = world.try_fgr_ctx().err().unwrap();
+    assert!(err.to_string().contains("FgrPlugin"));
+    let panic = std::panic::catch_unwind(move || {
+        Signal::new(&mut world, 0);
+    });
+    // the panic carries the error's context and hint
+    let message = panic.err().unwrap().downcast::<String>().unwrap();
+    assert_eq!(*message, err.to_string());
+    assert!(message.contains("no FgrCtx found for World"));
+}
+
+#[derive(Component)]
+struct Counted;
+
+#[derive(Resource, Clone)]
+struct AddedCount(Signal<World, u32>);
+
+#[test]
+fn test_deferred_world() {
+    let mut world = World::new();
+    world.insert_resource(FgrCtx::<World>::new());
+    let count = Signal::new(&mut world, 0u32);
+    world.insert_resource(AddedCount(count.clone()));
+    world.register_component_hooks::<Counted>().on_add(|mut world: DeferredWorld, _entity, _id| {
+        let AddedCount(mut count) = world.resource::<AddedCount>().clone();
+        world.fgr_queue(move |world| count.update_value(world, |x| *x += 1));
+    });
+    world.spawn(Counted);
+    world.spawn(Counted);
+    world.flush();
+    assert_eq!(*count.value(&mut world), 2);
+}
+
+#[derive(Component)]
+struct Selected;
+
+#[derive(Resource, Clone)]
+struct Selection {
+    count: Signal<World, u32>,
+    label: Memo<World, String>,
+    seen: Signal<World, Vec<(u32, String)>>,
+}
+
+#[test]
+fn test_deferred_world_graph() {
+    let mut world = World::new();
+    world.insert_resource(FgrCtx::<World>::new());
+    let effect_runs = Signal::new(&mut world, 0);
+    let (selection, _scope) = world.fgr_create_root(|world, scope| {
+        let count = Signal::new(world, 0u32);
+        let label = Memo::new(world, cloned!((count) => move |world| format!("{} selected", *count.value(world))));
+        let seen = Signal::new(world, Vec::new());
+        world.fgr_create_effect(cloned!((count, effect_runs) => move |world| {
+            count.value(world);
+            effect_runs.clone().update_value(world, |x| *x += 1);
+        }));
+        (Selection { count, label, seen }, scope)
+    });
+    world.insert_resource(selection.clone());
+    world.register_component_hooks::<Selected>().on_add(|mut world: DeferredWorld, _entity, _id| {
+        let mut selection = world.resource::<Selection>().clone();
+        selection.count.update_value_deferred(&mut world, |x| *x += 1);
+        // the write is seen right away, its dependents update once the hook returns
+        let count = *selection.count.value_deferred(&world);
+        let label = selection.label.value_deferred(&world).clone();
+        selection.seen.update_value_deferred(&mut world, |seen| seen.push((count, label)));
+    });
+    world.spawn(Selected);
+    world.spawn(Selected);
+    world.flush();
+    assert_eq!(*selection.seen.value(&mut world), vec![(1, "0 selected".to_string()), (2, "1 selected".to_string())]);
+    assert_eq!(*selection.label.value(&mut world), "2 selected");
+    assert_eq!(*effect_runs.value(&mut world), 3);
+}
+
+#[derive(AppLabel, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+struct MirrorApp;
+
+#[test]
+fn test_sub_app_ctx() {
+    let mut app = App::new();
+    let mut sub_app = SubApp::new();
+    sub_app.update_schedule = Some(Update.intern());
+    sub_app.add_plugins(FgrPlugin::default());
+    app.insert_sub_app(MirrorApp, sub_app);
+    let ticks = {
+        let world = app.sub_app_mut(MirrorApp).world_mut();
+        let (ticks, scope) = world.fgr_create_root(|world, scope| {
+            let ticks = Signal::new(world, 0);
+            world.fgr_on_update(cloned!((ticks) => move |world| ticks.update_value(world, |x| *x += 1)));
+            (ticks, scope)
+        });
+        world.insert_resource(scope);
+        ticks
+    };
+    // the sub-app has a graph of its own, the main world has none.
+    assert!(app.world_mut().try_fgr_ctx().is_err());
+    let initial = *ticks.value(app.sub_app_mut(MirrorApp).world_mut());
+    app.update();
+    app.update();
+    assert_eq!(*ticks.value(app.sub_app_mut(MirrorApp).world_mut()), initial + 2);
+}
+
+#[test]
+fn test_render_with_inserted_ctx() {
+    let mut app = App::new();
+    app.insert_resource(FgrCtx::<World>::new());
+    let ticks = Signal::new(app.world_mut(), 0);
+    let _scope = bevy_editor_experiment_lib::ui::render(&mut app, cloned!((ticks) => move |world| {
+        world.fgr_on_update(cloned!((ticks) => move |world| ticks.update_value(world, |x| *x += 1)));
+        world.spawn(NodeBundle::default()).id()
+    }));
+    // the inserted graph is kept and still gets updated
+    let initial = *ticks.value(app.world_mut());
+    app.update();
+    assert_eq!(*ticks.value(app.world_mut()), initial + 1);
+}
@@ -0,0 +1,145 @@
+use bevy::{asset::AssetPlugin, prelude::*};
+use bevy_editor_experiment_lib::{fgr::*, ui::{self, Document, DocumentProps, Theme, ThemeTokens, UiComponent, UiComponentRegistry, UiDocument, UiDocumentPlugin, UiDocuments, UiSignals}};
+
+const DOCUMENT: &str = r#"(
+    root: (
+        component: "Column",
+        props: (gap: Some(Px(4.0)), align: Some(Center), style: (width: Some(Percent(50.0)))),
+        children: [
+            (component: "Text", bind: { "text": "title" }),
+            (component: "Missing", props: (size: 3)),
+            (component: "Spacer", props: (grow: Some(2.0))),
+        ],
+    ),
+)"#;
+
+const BOUND_DOCUMENT: &str = r#"(
+    root: (
+        component: "Row",
+        children: [
+            (component: "Text", bind: { "text": "title" }),
+            (component: "CheckBox", bind: { "style.width": "check_width" }),
+        ],
+    ),
+)"#;
+
+fn text(world: &World, entity: Entity) -> String {
+    world.get::<Text>(entity).unwrap().sections[0].value.clone()
+}
+
+#[test]
+fn test_ui_document() {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, AssetPlugin::default(), UiDocumentPlugin));
+    let world = app.world_mut();
+    let mut title = Signal::new(world, "first".to_string());
+    world.resource_mut::<UiSignals>().insert("title", title.clone());
+    let document = UiDocument::from_ron(world.resource::<UiComponentRegistry>(), DOCUMENT.as_bytes()).unwrap();
+    let handle = world.resource_mut::<Assets<UiDocument>>().add(document);
+    let document_id = world.fgr_create_root(|world, _scope| Document::run(world, DocumentProps::new().source(handle.clone())));
+
+    let root = |world: &World| world.get::<Children>(document_id).unwrap()[0];
+    let world = app.world_mut();
+    let column_id = root(world);
+    let style = world.get::<Style>(column_id).unwrap();
+    assert_eq!(style.row_gap, ui::px(4.0));
+    assert_eq!(style.width, ui::percent(50.0));
+    assert_eq!(style.align_items, AlignItems::Center);
+    // the unknown component is skipped
+    let children = world.get::<Children>(column_id).unwrap().to_vec();
+    assert_eq!(children.len(), 2);
+    assert_eq!(text(world, children[0]), "first");
+    assert_eq!(world.get::<Style>(children[1]).unwrap().flex_grow, 2.0);
+
+    title.update_value(world, |x| *x = "second".to_string());
+    assert_eq!(text(world, children[0]), "second");
+
+    // editing the asset rebuilds the document, the signal keeps its value
+    let mut documents = world.resource_mut::<Assets<UiDocument>>();
+    documents.get_mut(&handle).unwrap().root.children.truncate(1);
+    // asset events are sent at the end of the frame, and applied the next one
+    app.update();
+    app.update();
+    let world = app.world_mut();
+    assert!(world.get_entity(column_id).is_none());
+    let column_id = root(world);
+    let children = world.get::<Children>(column_id).unwrap().to_vec();
+    assert_eq!(children.len(), 1);
+    assert_eq!(text(world, children[0]), "second");
+}
+
+#[test]
+fn test_bound_props_keep_defaults() {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, AssetPlugin::default(), UiDocumentPlugin));
+    let world = app.world_mut();
+    let theme = Theme::new(world, ThemeTokens::default());
+    world.insert_resource(theme.clone());
+    let title = Signal::new(world, "title".to_string());
+    let mut check_width = Signal::new(world, None::<Val>);
+    world.resource_mut::<UiSignals>().insert("title", title);
+    world.resource_mut::<UiSignals>().insert("check_width", check_width.clone());
+    let document = UiDocument::from_ron(world.resource::<UiComponentRegistry>(), BOUND_DOCUMENT.as_bytes()).unwrap();
+    let handle = world.resource_mut::<Assets<UiDocument>>().add(document);
+    let document_id = world.fgr_create_root(|world, _scope| Document::run(world, DocumentProps::new().source(handle.clone())));
+    let row_id = world.get::<Children>(document_id).unwrap()[0];
+    let children = world.get::<Children>(row_id).unwrap().to_vec();
+    let font_size = |world: &World| world.get::<Text>(children[0]).unwrap().sections[0].style.font_size;
+    let check_box_width = |world: &World| world.get::<Style>(children[1]).unwrap().width;
+
+    // props the bindings leave unset keep the components' own values
+    assert_eq!(font_size(world), ThemeTokens::default().font_sizes.body);
+    assert_eq!(check_box_width(world), ui::px(10.0));
+    let mut tokens = ThemeTokens::default();
+    tokens.font_sizes.body = 20.0;
+    theme.set(world, tokens);
+    assert_eq!(font_size(world), 20.0);
+
+    check_width.update_value(world, |x| *x = Some(ui::px(30.0)));
+    assert_eq!(check_box_width(world), ui::px(30.0));
+    check_width.update_value(world, |x| *x = None);
+    assert_eq!(check_box_width(world), ui::px(10.0));
+}
+
+#[test]
+fn test_ui_document_reload() {
+    let dir = std::env::temp_dir().join(format!("ui_document_reload_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let write = |text: &str| {
+        let source = format!(r#"(root: (component: "Column", children: [(component: "Text", props: (text: "{}"))]))"#, text);
+        std::fs::write(dir.join("status.ui.ron"), source).unwrap();
+    };
+    write("first");
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, AssetPlugin { file_path: dir.to_string_lossy().into(), ..default() }, UiDocumentPlugin));
+    let handle = app.world().resource::<AssetServer>().load::<UiDocument>("status.ui.ron");
+    let (document_id, mut scope) = app.world_mut().fgr_create_root(|world, scope| (Document::run(world, DocumentProps::new().source(handle.clone())), scope));
+    let update_until = |app: &mut App, expected: &str| {
+        for _ in 0..500 {
+            app.update();
+            let world = app.world();
+            let label = world
+                .get::<Children>(document_id)
+                .and_then(|children| world.get::<Children>(children[0]))
+                .map(|children| text(world, children[0]));
+            if label.as_deref() == Some(expected) {
+                return;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+        panic!("the document never showed {:?}", expected);
+    };
+    update_until(&mut app, "first");
+
+    // what the file watcher does when the file is saved
+    write("second");
+    app.world().resource::<AssetServer>().reload("status.ui.ron");
+    update_until(&mut app, "second");
+
+    // the document's state is dropped with the last `Document` rendering it
+    assert!(app.world().resource::<UiDocuments>().is_rendered(handle.id()));
+    scope.dispose(app.world_mut());
+    assert!(!app.world().resource::<UiDocuments>().is_rendered(handle.id()));
+    assert!(app.world().get_entity(document_id).is_none());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,45 @@
+use std::sync::{Arc, RwLock};
+
+use bevy_editor_experiment_lib::{cloned, fgr::*};
+
+#[test]
+fn test_effect_on() {
+    struct Ctx {
+        fgr_ctx: FgrCtx<Ctx>,
+    }
+    impl HasFgrCtx for Ctx {
+        fn fgr_ctx<'a>(&'a mut self) -> impl std::ops::DerefMut<Target=FgrCtx<Ctx>> + 'a {
+            &mut self.fgr_ctx
+        }
+    }
+    let mut ctx = Ctx { fgr_ctx: FgrCtx::new() };
+    let ctx = &mut ctx;
+    let mut sa = Signal::new(ctx, 1);
+    let mut sb = Signal::new(ctx, 10);
+    let mut untracked = Signal::new(ctx, 100);
+    let log: Arc<RwLock<Vec<String>>> = Arc::new(RwLock::new(Vec::new()));
+    let deferred_log: Arc<RwLock<Vec<String>>> = Arc::new(RwLock::new(Vec::new()));
+    let mut scope = ctx.fgr_create_root(|ctx, scope| {
+        ctx.fgr_create_effect_on((sa.clone(), sb.clone()), cloned!((log, untracked) => move |ctx, (a, b), prev| {
+            let untracked = *untracked.value(ctx);
+            log.write().unwrap().push(format!("{} {} {} {:?}", a, b, untracked, prev));
+        }));
+        ctx.fgr_create_effect_on_deferred(sa.clone(), cloned!((deferred_log) => move |_ctx, a, prev| {
+            deferred_log.write().unwrap().push(format!("{} {:?}", a, prev));
+        }));
+        scope
+    });
+    assert_eq!(*log.read().unwrap(), vec!["1 10 100 None"]);
+    assert!(deferred_log.read().unwrap().is_empty());
+    untracked.update_value(ctx, |x| *x = 200);
+    assert_eq!(log.read().unwrap().len(), 1);
+    sa.update_value(ctx, |x| *x = 2);
+    sb.update_value(ctx, |x| *x = 20);
+    assert_eq!(*log.read().unwrap(), vec![
+        "1 10 100 None",
+        "2 10 200 Some((1, 10))",
+        "2 20 200 Some((2, 10))",
+    ]);
+    assert_eq!(*deferred_log.read().unwrap(), vec!["2 Some(1)"]);
+    scope.dispose(ctx);
+}
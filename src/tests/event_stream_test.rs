@@ -0,0 +1,52 @@
+use std::sync::{Arc, RwLock};
+
+use bevy::prelude::*;
+use bevy_editor_experiment_lib::fgr::*;
+
+#[derive(Event, Clone, Debug, PartialEq)]
+struct Pressed(u32);
+
+#[derive(Event, Clone, Debug, PartialEq)]
+struct Doubled(u32);
+
+#[test]
+fn test_event_streams() {
+    let mut world = World::new();
+    world.insert_resource(FgrCtx::<World>::new());
+    world.init_resource::<Events<Pressed>>();
+    world.init_resource::<Events<Doubled>>();
+    let world = &mut world;
+    // sent before the stream exists, so not part of it.
+    world.send_event(Pressed(0));
+    let received = Arc::new(RwLock::new(Vec::new()));
+    let (latest_even, mut scope) = world.fgr_create_root(|world, scope| {
+        let pressed = use_event::<Pressed>(world);
+        pressed.on_event(world, {
+            let received = Arc::clone(&received);
+            move |_world, event| received.write().unwrap().push(event.0)
+        });
+        let even = pressed
+            .filter(world, |event| event.0 % 2 == 0)
+            .map(world, |event| event.0);
+        pressed.map(world, |event| Doubled(event.0 * 2)).send_as_events(world);
+        (even.hold(world, 0), scope)
+    });
+    world.send_event(Pressed(1));
+    world.send_event(Pressed(2));
+    world.send_event(Pressed(3));
+    world.fgr_update();
+    assert_eq!(*received.read().unwrap(), vec![1, 2, 3]);
+    assert_eq!(*latest_even.value(world), 2);
+    let doubled: Vec<Doubled> = world.resource_mut::<Events<Doubled>>().drain().collect();
+    assert_eq!(doubled, vec![Doubled(2), Doubled(4), Doubled(6)]);
+    world.send_event(Pressed(4));
+    world.fgr_update();
+    assert_eq!(*latest_even.value(world), 4);
+    world.resource_mut::<Events<Doubled>>().clear();
+    // the handlers and the reader go away with the scope.
+    scope.dispose(world);
+    world.send_event(Pressed(5));
+    world.fgr_update();
+    assert_eq!(*received.read().unwrap(), vec![1, 2, 3, 4]);
+    assert!(world.resource::<Events<Doubled>>().is_empty());
+}
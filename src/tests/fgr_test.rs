@@ -1,3 +1,5 @@
+use std::sync::{atomic::{AtomicUsize, Ordering}, Arc};
+
 use bevy_editor_experiment_lib::{cloned, fgr::*};
 
 #[test]
@@ -47,3 +49,40 @@ fn test_fgr() {
     sa.update_value(ctx, |v| *v += 1);
     scope.dispose(ctx);
 }
+
+#[test]
+fn test_memo_starts_reading_a_signal_on_a_later_run() {
+    struct Ctx {
+        fgr_ctx: FgrCtx<Ctx>,
+    }
+    impl HasFgrCtx for Ctx {
+        fn fgr_ctx<'a>(&'a mut self) -> impl std::ops::DerefMut<Target=FgrCtx<Ctx>> + 'a {
+            &mut self.fgr_ctx
+        }
+    }
+    let mut ctx = Ctx { fgr_ctx: FgrCtx::new() };
+    let ctx = &mut ctx;
+    let mut use_b = Signal::new(ctx, false);
+    let mut b = Signal::new(ctx, 1);
+    let runs = Arc::new(AtomicUsize::new(0));
+    let (memo, mut scope) = ctx.fgr_create_root(|ctx, scope| {
+        let memo = Memo::new(ctx, cloned!((use_b, b, runs) => move |ctx| {
+            runs.fetch_add(1, Ordering::Relaxed);
+            let use_b = *use_b.value(ctx);
+            if use_b { *b.value(ctx) } else { 0 }
+        }));
+        (memo, scope)
+    });
+    assert_eq!(*memo.value(ctx), 0);
+    use_b.update_value(ctx, |x| *x = true);
+    assert_eq!(*memo.value(ctx), 1);
+    // b was not read on the first run
+    b.update_value(ctx, |x| *x = 2);
+    assert_eq!(*memo.value(ctx), 2);
+    use_b.update_value(ctx, |x| *x = false);
+    assert_eq!(*memo.value(ctx), 0);
+    let runs_before = runs.load(Ordering::Relaxed);
+    b.update_value(ctx, |x| *x = 3);
+    assert_eq!(runs.load(Ordering::Relaxed), runs_before);
+    scope.dispose(ctx);
+}
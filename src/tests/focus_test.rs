@@ -0,0 +1,188 @@
+use std::sync::{Arc, RwLock};
+
+use bevy::{input::{keyboard::{Key, KeyboardInput}, ButtonState}, prelude::*};
+use bevy_editor_experiment_lib::{fgr::*, ui::{self, use_focus, view, FocusManager, FocusPlugin, PointerManager, Row, TextBox, Theme, ThemeTokens}};
+
+fn press(app: &mut App, key_code: KeyCode, logical_key: Key) {
+    app.world_mut().send_event(KeyboardInput { key_code, logical_key, state: ButtonState::Pressed, window: Entity::PLACEHOLDER });
+    app.update();
+}
+
+fn contents(world: &World, text_box: Entity) -> String {
+    let children = world.get::<Children>(text_box).unwrap();
+    [children[0], children[2]].iter().map(|child| world.get::<Text>(*child).unwrap().sections[0].value.clone()).collect()
+}
+
+#[test]
+fn test_keyboard_routing() {
+    let mut app = App::new();
+    app.add_plugins(FocusPlugin).add_event::<KeyboardInput>();
+    let world = app.world_mut();
+    let row = world.fgr_create_root(|world, _scope| {
+        view! { world,
+            <Row>
+                <TextBox />
+                <TextBox />
+            </Row>
+        }
+    });
+    let received = Arc::new(RwLock::new(Vec::new()));
+    let manager = world.resource::<FocusManager>().clone();
+    world.fgr_create_root(|world, _scope| {
+        manager.key_events(row).on_event(world, {
+            let received = Arc::clone(&received);
+            move |_world, event| received.write().unwrap().push(event.input.key_code)
+        });
+    });
+    let text_boxes = world.get::<Children>(row).unwrap().to_vec();
+
+    // nothing is focused, so nothing is typed
+    press(&mut app, KeyCode::KeyA, Key::Character("a".into()));
+    assert_eq!(contents(app.world(), text_boxes[0]), "");
+    assert_eq!(contents(app.world(), text_boxes[1]), "");
+
+    press(&mut app, KeyCode::Tab, Key::Tab);
+    press(&mut app, KeyCode::KeyB, Key::Character("b".into()));
+    assert_eq!(contents(app.world(), text_boxes[0]), "b");
+    assert_eq!(contents(app.world(), text_boxes[1]), "");
+
+    press(&mut app, KeyCode::Tab, Key::Tab);
+    press(&mut app, KeyCode::KeyC, Key::Character("c".into()));
+    assert_eq!(contents(app.world(), text_boxes[0]), "b");
+    assert_eq!(contents(app.world(), text_boxes[1]), "c");
+
+    // keys the text box does not handle bubble up to the row
+    press(&mut app, KeyCode::Escape, Key::Escape);
+    assert_eq!(*received.read().unwrap(), vec![KeyCode::Escape]);
+}
+
+#[test]
+fn test_focus_order() {
+    let mut world = World::new();
+    world.insert_resource(FgrCtx::<World>::new());
+    let world = &mut world;
+    let manager = FocusManager::get(world);
+    let parent = world.spawn(NodeBundle::default()).id();
+    let children: Vec<Entity> = (0..3).map(|_| world.spawn(NodeBundle::default()).set_parent(parent).id()).collect();
+    let (is_focused, mut scope) = world.fgr_create_root(|world, scope| {
+        let first = use_focus(world, children[0], 0);
+        use_focus(world, children[1], 1);
+        use_focus(world, children[2], 0);
+        (first.is_focused(world), scope)
+    });
+    let focused = manager.focused();
+    let mut order = Vec::new();
+    for _ in 0..4 {
+        manager.focus_next(world);
+        order.push(world.fgr_untrack(|world| focused.value(world).unwrap()));
+    }
+    // by tab index, then in hierarchy order, wrapping around
+    assert_eq!(order, vec![children[0], children[2], children[1], children[0]]);
+    assert!(*is_focused.value(world));
+    assert!(*manager.focus_visible().value(world));
+    assert_eq!(world.get::<Outline>(children[0]).unwrap().color, ui::ThemeTokens::default().accent);
+
+    manager.focus_previous(world);
+    assert_eq!(*focused.value(world), Some(children[1]));
+    assert!(!*is_focused.value(world));
+    assert_eq!(world.get::<Outline>(children[0]).unwrap().color, Color::NONE);
+
+    // focus set by a click is not shown
+    manager.focus(world, children[0]);
+    assert!(!*manager.focus_visible().value(world));
+    assert_eq!(world.get::<Outline>(children[0]).unwrap().color, Color::NONE);
+
+    // disposing the scope blurs and removes the focusables
+    scope.dispose(world);
+    assert_eq!(*focused.value(world), None);
+    manager.focus_next(world);
+    assert_eq!(*focused.value(world), None);
+}
+
+#[test]
+fn test_click_focus() {
+    let mut app = App::new();
+    app.add_plugins(FocusPlugin).add_event::<KeyboardInput>().init_resource::<ButtonInput<MouseButton>>();
+    let world = app.world_mut();
+    let row = world.fgr_create_root(|world, _scope| {
+        view! { world,
+            <Row>
+                <TextBox />
+            </Row>
+        }
+    });
+    let text_box = world.get::<Children>(row).unwrap()[0];
+    let contents = world.get::<Children>(text_box).unwrap()[0];
+    let focus = world.resource::<FocusManager>().clone();
+    let pointer = PointerManager::get(world);
+
+    // pressing the text box's contents focuses the text box
+    pointer.move_to(world, Vec2::ZERO, Some(contents));
+    pointer.press(world, MouseButton::Right);
+    assert_eq!(*focus.focused().value(world), None);
+    pointer.press(world, MouseButton::Left);
+    pointer.release(world, MouseButton::Left);
+    assert_eq!(*focus.focused().value(world), Some(text_box));
+
+    // pressing something that is not focusable blurs
+    pointer.move_to(world, Vec2::ZERO, Some(row));
+    world.resource_mut::<ButtonInput<MouseButton>>().press(MouseButton::Left);
+    app.update();
+    assert_eq!(*focus.focused().value(app.world_mut()), None);
+}
+
+struct EditorUi;
+
+#[test]
+fn test_labelled_text_box() {
+    let mut app = App::new();
+    app.add_plugins((FocusPlugin, FgrPlugin::labelled::<EditorUi>(Update)))
+        .add_event::<KeyboardInput>()
+        .init_resource::<ButtonInput<MouseButton>>();
+    let world = app.world_mut();
+    let theme = Theme::new(world, ThemeTokens::light());
+    world.insert_resource(theme);
+    let game_box = world.fgr_create_root(|world, _scope| view!(world, <TextBox />));
+    // the editor graph gets a focus manager, pointer manager and theme of its own
+    let (row, editor_pointer) = world.fgr_in_ctx::<EditorUi, _, _>(|world| {
+        let row = world.fgr_create_root(|world, _scope| {
+            view! { world,
+                <Row>
+                    <TextBox />
+                    <TextBox />
+                </Row>
+            }
+        });
+        (row, PointerManager::get(world))
+    });
+    let editor_boxes = world.get::<Children>(row).unwrap().to_vec();
+    world.fgr_in_ctx::<EditorUi, _, _>(|world| {
+        editor_pointer.move_to(world, Vec2::ZERO, Some(editor_boxes[0]));
+        editor_pointer.press(world, MouseButton::Left);
+        editor_pointer.release(world, MouseButton::Left);
+    });
+    press(&mut app, KeyCode::KeyA, Key::Character("a".into()));
+    assert_eq!(contents(app.world(), editor_boxes[0]), "a");
+    assert_eq!(contents(app.world(), game_box), "");
+
+    // Tab stays within the editor's text boxes and shows the focus in the theme's accent
+    press(&mut app, KeyCode::Tab, Key::Tab);
+    press(&mut app, KeyCode::KeyB, Key::Character("b".into()));
+    assert_eq!(contents(app.world(), editor_boxes[1]), "b");
+    assert_eq!(app.world().get::<Outline>(editor_boxes[1]).unwrap().color, ThemeTokens::light().accent);
+
+    // pressing the game's text box takes the keyboard from the editor
+    let world = app.world_mut();
+    let game_pointer = PointerManager::get(world);
+    game_pointer.move_to(world, Vec2::ZERO, Some(game_box));
+    game_pointer.press(world, MouseButton::Left);
+    world.resource_mut::<ButtonInput<MouseButton>>().press(MouseButton::Left);
+    press(&mut app, KeyCode::KeyC, Key::Character("c".into()));
+    assert_eq!(contents(app.world(), game_box), "c");
+    assert_eq!(contents(app.world(), editor_boxes[1]), "b");
+    let editor_focus = app.world_mut().fgr_in_ctx::<EditorUi, _, _>(|world| {
+        let focus = FocusManager::get(world);
+        world.fgr_untrack(|world| *focus.focused().value(world))
+    });
+    assert_eq!(editor_focus, None);
+}
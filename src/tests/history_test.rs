@@ -0,0 +1,121 @@
+use bevy::prelude::*;
+use bevy_editor_experiment_lib::{cloned, fgr::*, ui::{view, CheckBox, PointerManager}};
+
+struct Ctx {
+    fgr_ctx: FgrCtx<Ctx>,
+}
+
+impl HasFgrCtx for Ctx {
+    fn fgr_ctx<'a>(&'a mut self) -> impl std::ops::DerefMut<Target=FgrCtx<Ctx>> + 'a {
+        &mut self.fgr_ctx
+    }
+}
+
+#[test]
+fn test_history() {
+    let mut ctx = Ctx { fgr_ctx: FgrCtx::new() };
+    let ctx = &mut ctx;
+    let mut checked = Signal::new_recorded(ctx, false);
+    let mut text = Signal::new_recorded(ctx, String::new());
+    let mut history = History::new(ctx);
+    let can_undo = history.can_undo();
+    let can_redo = history.can_redo();
+    assert!(!*can_undo.value(ctx));
+    history.transaction(ctx, "Toggle", |ctx| checked.update_value(ctx, |x| *x = true));
+    for c in ['a', 'b', 'c'] {
+        history.transaction_merging(ctx, "Type", "text", |ctx| text.update_value(ctx, |x| x.push(c)));
+    }
+    assert!(*can_undo.value(ctx));
+    assert_eq!(history.undo_label().as_deref(), Some("Type"));
+    // the three keystrokes were merged into one step.
+    assert!(history.undo(ctx));
+    assert_eq!(*text.value(ctx), "");
+    assert!(*checked.value(ctx));
+    assert!(*can_redo.value(ctx));
+    assert!(history.undo(ctx));
+    assert!(!*checked.value(ctx));
+    assert!(!history.undo(ctx));
+    assert!(!*can_undo.value(ctx));
+    assert!(history.redo(ctx));
+    assert!(*checked.value(ctx));
+    // a new transaction drops the redo stack.
+    history.transaction(ctx, "Toggle", |ctx| checked.update_value(ctx, |x| *x = false));
+    assert!(!*can_redo.value(ctx));
+    assert!(!history.redo(ctx));
+}
+
+/// Signals need not hold `Clone` values unless they are recorded.
+struct NotClone(u32);
+
+#[test]
+fn test_history_records_what_the_transaction_writes() {
+    let mut ctx = Ctx { fgr_ctx: FgrCtx::new() };
+    let ctx = &mut ctx;
+    let mut count = Signal::new_recorded(ctx, 1);
+    let doubled = Signal::new_recorded(ctx, 2);
+    let mut unrecorded = Signal::new(ctx, NotClone(0));
+    let mut items = ReactiveVec::new(ctx, vec!["a"]);
+    let mut names = ReactiveMap::new(ctx, [(1, "one")].into_iter().collect());
+    let mut history = History::new(ctx);
+    let mut scope = ctx.fgr_create_root(|ctx, scope| {
+        ctx.fgr_create_effect(cloned!((count, doubled) => move |ctx| {
+            let count = *count.value(ctx);
+            doubled.clone().update_value(ctx, |x| *x = count * 2);
+        }));
+        scope
+    });
+    let mut nested = history.clone();
+    history.transaction(ctx, "Edit", |ctx| {
+        count.update_value(ctx, |x| *x = 5);
+        unrecorded.update_value(ctx, |x| x.0 = 1);
+        items.push(ctx, "b");
+        names.update(ctx, 1, |name| *name = "uno");
+        names.insert(ctx, 2, "dos");
+        // a nested transaction is part of the outer one
+        nested.transaction(ctx, "Nested", |ctx| items.remove(ctx, 0));
+    });
+    assert_eq!(*doubled.value(ctx), 10);
+    assert_eq!(*items.items().value(ctx), vec!["b"]);
+    assert!(history.undo(ctx));
+    // the effect's write is derived again rather than undone
+    assert_eq!(*count.value(ctx), 1);
+    assert_eq!(*doubled.value(ctx), 2);
+    assert_eq!(unrecorded.value(ctx).0, 1);
+    assert_eq!(*items.items().value(ctx), vec!["a"]);
+    // undo applies the inverse patches, newest first
+    assert_eq!(*items.patches().value(ctx), vec![VecPatch::Insert { index: 0, value: "a" }, VecPatch::Remove { index: 1 }]);
+    assert_eq!(*names.patches().value(ctx), vec![MapPatch::Remove { key: 2 }, MapPatch::Update { key: 1, value: "one" }]);
+    assert!(!history.undo(ctx));
+    assert!(history.redo(ctx));
+    assert_eq!(*doubled.value(ctx), 10);
+    assert_eq!(*items.items().value(ctx), vec!["b"]);
+    assert_eq!(*names.patches().value(ctx), vec![MapPatch::Update { key: 1, value: "uno" }, MapPatch::Insert { key: 2, value: "dos" }]);
+    scope.dispose(ctx);
+}
+
+#[test]
+fn test_check_box_toggles_are_recorded() {
+    let mut world = World::new();
+    world.insert_resource(FgrCtx::<World>::new());
+    let world = &mut world;
+    let mut history = History::new(world);
+    world.insert_resource(history.clone());
+    let manager = PointerManager::get(world);
+    let checked = Signal::new_recorded(world, false);
+    let check_box = world.fgr_create_root(|world, _scope| {
+        view! { world,
+            <CheckBox on:changed={cloned!((checked) => move |world, value| checked.clone().update_value(world, |x| *x = value))} />
+        }
+    });
+    manager.move_to(world, Vec2::ZERO, Some(check_box));
+    manager.press(world, MouseButton::Left);
+    manager.release(world, MouseButton::Left);
+    assert!(*checked.value(world));
+    assert_eq!(history.undo_label().as_deref(), Some("Toggle"));
+    assert!(history.undo(world));
+    assert!(!*checked.value(world));
+    // the check box's own state was restored too, so the next click checks it again
+    manager.press(world, MouseButton::Left);
+    manager.release(world, MouseButton::Left);
+    assert!(*checked.value(world));
+}
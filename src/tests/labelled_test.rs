@@ -0,0 +1,136 @@
+use std::sync::{Arc, RwLock};
+
+use bevy::{app::{App, Update}, ecs::schedule::ScheduleLabel, prelude::World};
+use bevy_editor_experiment_lib::{cloned, fgr::*};
+
+struct EditorUi;
+
+#[derive(ScheduleLabel, Clone, Debug, PartialEq, Eq, Hash)]
+struct EditorUpdate;
+
+#[test]
+fn test_labelled_ctxs() {
+    let mut app = App::new();
+    app.add_plugins((FgrPlugin::default(), FgrPlugin::labelled::<EditorUi>(EditorUpdate)));
+    let world = app.world_mut();
+    let (mut game_health, game_ticks, game_scope) = world.fgr_create_root(|world, scope| {
+        let health = Signal::new(world, 100);
+        let ticks = Signal::new(world, 0);
+        world.fgr_on_update(cloned!((ticks) => move |world| ticks.update_value(world, |x| *x += 1)));
+        (health, ticks, scope)
+    });
+    world.insert_resource(game_scope);
+    let editor_ticks = world.fgr_in_ctx::<EditorUi, _, _>(|world| {
+        world.fgr_create_root(|world, _scope| {
+            let ticks = Signal::new(world, 0);
+            world.fgr_on_update(cloned!((ticks) => move |world| ticks.update_value(world, |x| *x += 1)));
+            ticks
+        })
+    });
+    // the bridge belongs to the game graph and feeds the editor graph.
+    let (bridged, _bridge_scope) = world.fgr_create_root(|world, scope| {
+        (world.fgr_bridge::<EditorUi, _, _>(game_health.clone()), scope)
+    });
+    let label = world.fgr_in_ctx::<EditorUi, _, _>(|world| {
+        world.fgr_create_root(|world, _scope| {
+            let bridged = bridged.clone();
+            Memo::new(world, move |world| format!("health: {}", *bridged.value(world)))
+        })
+    });
+    let game_ticks_before = *game_ticks.value(world);
+    let editor_ticks_before = world.fgr_in_ctx::<EditorUi, _, _>(|world| *editor_ticks.value(world));
+    // only the game graph is updated by `Update`.
+    app.world_mut().run_schedule(Update);
+    app.world_mut().run_schedule(Update);
+    let world = app.world_mut();
+    assert_eq!(*game_ticks.value(world), game_ticks_before + 2);
+    assert_eq!(world.fgr_in_ctx::<EditorUi, _, _>(|world| *editor_ticks.value(world)), editor_ticks_before);
+    world.run_schedule(EditorUpdate);
+    assert_eq!(*game_ticks.value(world), game_ticks_before + 2);
+    assert_eq!(world.fgr_in_ctx::<EditorUi, _, _>(|world| *editor_ticks.value(world)), editor_ticks_before + 1);
+    // writes in the game graph reach the editor graph through the bridge.
+    assert_eq!(world.fgr_in_ctx::<EditorUi, _, _>(|world| label.value(world).clone()), "health: 100");
+    game_health.update_value(world, |x| *x = 42);
+    assert_eq!(world.fgr_in_ctx::<EditorUi, _, _>(|world| label.value(world).clone()), "health: 42");
+}
+
+#[test]
+fn test_nested_labelled_ctxs() {
+    let mut world = World::new();
+    world.insert_resource(FgrCtx::<World>::new());
+    world.insert_resource(FgrCtx::<World, EditorUi>::labelled());
+    let world = &mut world;
+    let (mut game_health, label) = world.fgr_create_root(|world, _scope| {
+        let health = Signal::new(world, 100);
+        let label = Memo::new(world, cloned!((health) => move |world| format!("health: {}", *health.value(world))));
+        (health, label)
+    });
+    let editor_count = world.fgr_in_ctx::<EditorUi, _, _>(|world| {
+        // the same label again keeps the active graph
+        let count = world.fgr_in_ctx::<EditorUi, _, _>(|world| Signal::new(world, 0));
+        // the unlabelled graph can be written to from inside the labelled one
+        world.fgr_in_ctx::<(), _, _>(|world| game_health.update_value(world, |x| *x = 42));
+        count
+    });
+    assert_eq!(*label.value(world), "health: 42");
+    assert_eq!(world.fgr_in_ctx::<EditorUi, _, _>(|world| *editor_count.value(world)), 0);
+
+    // a panicking callback still switches back
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        world.fgr_in_ctx::<EditorUi, _, _>(|_world| panic!("callback failed"));
+    }));
+    assert!(result.is_err());
+    assert!(world.contains_resource::<FgrCtx<World, EditorUi>>());
+    game_health.update_value(world, |x| *x = 7);
+    assert_eq!(*label.value(world), "health: 7");
+    assert_eq!(world.fgr_in_ctx::<EditorUi, _, _>(|world| *editor_count.value(world)), 0);
+}
+
+#[test]
+fn test_switching_graphs_is_not_a_world_change() {
+    let mut world = World::new();
+    world.insert_resource(FgrCtx::<World>::new());
+    world.insert_resource(FgrCtx::<World, EditorUi>::labelled());
+    let world = &mut world;
+    // the first switch sets up where the unlabelled graph is kept
+    world.fgr_in_ctx::<EditorUi, _, _>(|_world| ());
+    let runs = Arc::new(RwLock::new(0));
+    world.fgr_create_root({
+        let runs = Arc::clone(&runs);
+        move |world, _scope| {
+            world.fgr_create_system_effect(move |_world: &World| *runs.write().unwrap() += 1);
+        }
+    });
+    for _ in 0..3 {
+        world.fgr_in_ctx::<EditorUi, _, _>(|world| world.fgr_update());
+        world.fgr_update();
+    }
+    assert_eq!(*runs.read().unwrap(), 1);
+}
+
+#[test]
+fn test_cross_graph_read_panics() {
+    let mut world = World::new();
+    world.insert_resource(FgrCtx::<World>::new());
+    world.insert_resource(FgrCtx::<World, EditorUi>::labelled());
+    let world = &mut world;
+    let (mut game_health, _game_scope) = world.fgr_create_root(|world, scope| (Signal::new(world, 100), scope));
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        world.fgr_in_ctx::<EditorUi, _, _>(|world| game_health.update_value(world, |x| *x = 42));
+    }));
+    assert!(result.is_err());
+    // untracked reads are fine
+    assert_eq!(world.fgr_in_ctx::<EditorUi, _, _>(|world| *game_health.value(world)), 100);
+    game_health.update_value(world, |x| *x = 42);
+    assert_eq!(*game_health.value(world), 42);
+    // a memo of the editor graph can not depend on a signal of the game graph, its edge would
+    // not be seen by either graph.
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        world.fgr_in_ctx::<EditorUi, _, _>(|world| {
+            world.fgr_create_root(|world, _scope| {
+                Memo::new(world, cloned!((game_health) => move |world| *game_health.value(world)))
+            })
+        })
+    }));
+    assert!(result.is_err());
+}
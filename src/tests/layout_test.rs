@@ -0,0 +1,115 @@
+use bevy::prelude::*;
+use bevy_editor_experiment_lib::{fgr::*, ui::{self, view, Column, Grid, GridItem, LayoutPlugin, Row, Show, Spacer, Stack}};
+
+#[test]
+fn test_flex_layout() {
+    let mut world = World::new();
+    world.insert_resource(FgrCtx::<World>::new());
+    let world = &mut world;
+    let mut gap = Signal::new(world, ui::px(4.0));
+    let (row_id, mut scope) = world.fgr_create_root(|world, scope| {
+        let row_id = view! { world,
+            <Row gap={gap.clone()} align={AlignItems::Center} wrap={FlexWrap::Wrap}>
+                <NodeBundle />
+                <Spacer />
+                <Column justify={JustifyContent::End}>
+                    <NodeBundle />
+                </Column>
+            </Row>
+        };
+        (row_id, scope)
+    });
+    let style = world.get::<Style>(row_id).unwrap();
+    assert_eq!(style.flex_direction, FlexDirection::Row);
+    assert_eq!(style.column_gap, ui::px(4.0));
+    assert_eq!(style.align_items, AlignItems::Center);
+    assert_eq!(style.flex_wrap, FlexWrap::Wrap);
+    let children = world.get::<Children>(row_id).unwrap().to_vec();
+    assert_eq!(children.len(), 3);
+    assert_eq!(world.get::<Style>(children[1]).unwrap().flex_grow, 1.0);
+    let column = world.get::<Style>(children[2]).unwrap();
+    assert_eq!(column.flex_direction, FlexDirection::Column);
+    assert_eq!(column.justify_content, JustifyContent::End);
+    assert_eq!(world.get::<Children>(children[2]).unwrap().len(), 1);
+
+    gap.update_value(world, |x| *x = ui::px(8.0));
+    assert_eq!(world.get::<Style>(row_id).unwrap().row_gap, ui::px(8.0));
+
+    scope.dispose(world);
+    assert!(world.get_entity(row_id).is_none());
+    assert!(world.get_entity(children[2]).is_none());
+}
+
+#[test]
+fn test_grid_layout() {
+    let mut world = World::new();
+    world.insert_resource(FgrCtx::<World>::new());
+    let world = &mut world;
+    let mut areas = Signal::new(world, vec!["header header".to_string(), "sidebar main".to_string()]);
+    let (grid_id, stack_id) = world.fgr_create_root(|world, _scope| {
+        let grid_id = view! { world,
+            <Grid areas={areas.clone()}>
+                <GridItem area="header" />
+                <GridItem area="main" />
+                <NodeBundle />
+            </Grid>
+        };
+        let stack_id = view!(world, <Stack> <NodeBundle /> <NodeBundle /> </Stack>);
+        (grid_id, stack_id)
+    });
+    let style = world.get::<Style>(grid_id).unwrap();
+    assert_eq!(style.display, Display::Grid);
+    assert_eq!(style.grid_template_columns, vec![RepeatedGridTrack::flex(2, 1.0)]);
+    let children = world.get::<Children>(grid_id).unwrap().to_vec();
+    let placement = |world: &World, entity: Entity| {
+        let style = world.get::<Style>(entity).unwrap();
+        (style.grid_row, style.grid_column)
+    };
+    assert_eq!(placement(world, children[0]), (GridPlacement::start_end(1, 2), GridPlacement::start_end(1, 3)));
+    assert_eq!(placement(world, children[1]), (GridPlacement::start_end(2, 3), GridPlacement::start_end(2, 3)));
+    assert_eq!(placement(world, children[2]), (GridPlacement::default(), GridPlacement::default()));
+
+    areas.update_value(world, |x| *x = vec!["header main".to_string(), ". main".to_string(), ". main".to_string()]);
+    assert_eq!(placement(world, children[0]), (GridPlacement::start_end(1, 2), GridPlacement::start_end(1, 2)));
+    assert_eq!(placement(world, children[1]), (GridPlacement::start_end(1, 4), GridPlacement::start_end(2, 3)));
+
+    for child in world.get::<Children>(stack_id).unwrap().iter() {
+        assert_eq!(placement(world, *child), (GridPlacement::start(1), GridPlacement::start(1)));
+    }
+}
+
+#[test]
+fn test_children_placed_after_build() {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, LayoutPlugin));
+    let world = app.world_mut();
+    let mut shown = Signal::new(world, false);
+    let (grid_id, stack_id) = world.fgr_create_root(|world, _scope| {
+        let grid_id = view! { world,
+            <Grid areas={vec!["header header".to_string(), "sidebar main".to_string()]}>
+                <Show when={shown.clone()}>
+                    <GridItem area="main" />
+                </Show>
+            </Grid>
+        };
+        let stack_id = view!(world, <Stack> <NodeBundle /> </Stack>);
+        (grid_id, stack_id)
+    });
+    let placement = |world: &World, entity: Entity| {
+        let style = world.get::<Style>(entity).unwrap();
+        (style.grid_row, style.grid_column)
+    };
+    let show_id = app.world().get::<Children>(grid_id).unwrap()[0];
+    assert_eq!(placement(app.world(), show_id), (GridPlacement::default(), GridPlacement::default()));
+
+    // the `Show` takes the area of the item it shows
+    shown.update_value(app.world_mut(), |x| *x = true);
+    app.update();
+    assert_eq!(placement(app.world(), show_id), (GridPlacement::start_end(2, 3), GridPlacement::start_end(2, 3)));
+
+    // children added to a stack later on are placed like the ones it was built with
+    let late = app.world_mut().spawn(NodeBundle::default()).id();
+    app.world_mut().entity_mut(stack_id).add_child(late);
+    app.update();
+    assert_eq!(placement(app.world(), late), (GridPlacement::start(1), GridPlacement::start(1)));
+}
@@ -0,0 +1,37 @@
+use bevy::prelude::*;
+use bevy_editor_experiment_lib::{fgr::*, ui::{self, UiComponent}};
+
+#[test]
+fn test_list() {
+    let mut world = World::new();
+    world.insert_resource(FgrCtx::<World>::new());
+    let world = &mut world;
+    let mut items = ReactiveVec::new(world, vec![1, 2, 3]);
+    let (list_id, mut scope) = world.fgr_create_root(|world, scope| {
+        let list_id = ui::List::run(world, ui::ListProps {
+            items: items.clone(),
+            render_item: Box::new(|world, value: &i32| world.spawn(Name::new(value.to_string())).id()),
+        });
+        (list_id, scope)
+    });
+    let names = |world: &World| -> Vec<String> {
+        world.get::<Children>(list_id)
+            .map(|children| children.iter().map(|child| world.get::<Name>(*child).unwrap().to_string()).collect())
+            .unwrap_or_default()
+    };
+    assert_eq!(names(world), vec!["1", "2", "3"]);
+    let first_child = world.get::<Children>(list_id).unwrap()[0];
+    world.fgr_batch(|world| {
+        items.remove(world, 1);
+        items.push(world, 4);
+        items.move_item(world, 2, 1);
+    });
+    assert_eq!(names(world), vec!["1", "4", "3"]);
+    assert_eq!(world.get::<Children>(list_id).unwrap()[0], first_child);
+    items.set(world, 2, 5);
+    assert_eq!(names(world), vec!["1", "4", "5"]);
+    items.clear(world);
+    assert!(names(world).is_empty());
+    scope.dispose(world);
+    assert!(world.get_entity(list_id).is_none());
+}
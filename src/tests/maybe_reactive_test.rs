@@ -0,0 +1,32 @@
+use bevy_editor_experiment_lib::{cloned, fgr::*};
+
+#[test]
+fn test_maybe_reactive() {
+    struct Ctx {
+        fgr_ctx: FgrCtx<Ctx>,
+    }
+    impl HasFgrCtx for Ctx {
+        fn fgr_ctx<'a>(&'a mut self) -> impl std::ops::DerefMut<Target=FgrCtx<Ctx>> + 'a {
+            &mut self.fgr_ctx
+        }
+    }
+    let mut ctx = Ctx { fgr_ctx: FgrCtx::new() };
+    let ctx = &mut ctx;
+    let mut sa = Signal::new(ctx, 1);
+    let plain: MaybeReactive<Ctx, i32> = 5.into();
+    let text: MaybeReactive<Ctx, String> = "text".into();
+    let from_signal: MaybeReactive<Ctx, i32> = sa.clone().into();
+    let incremented = MaybeReactive::derived(cloned!((sa) => move |ctx| *sa.value(ctx) + 1));
+    let from_closure: MaybeReactive<Ctx, i32> = FromDeriveFn::from_derive_fn(cloned!((sa) => move |ctx: &mut Ctx| *sa.value(ctx) * 2));
+    assert!(plain.is_static());
+    assert!(!from_signal.is_static());
+    assert_eq!(*plain.value(ctx), 5);
+    assert_eq!(*text.value(ctx), "text");
+    assert_eq!(*from_signal.value(ctx), 1);
+    assert_eq!(*incremented.value(ctx), 2);
+    assert_eq!(*from_closure.value(ctx), 2);
+    sa.update_value(ctx, |a| *a = 10);
+    assert_eq!(*from_signal.value(ctx), 10);
+    assert_eq!(*incremented.value(ctx), 11);
+    assert_eq!(*from_closure.value(ctx), 20);
+}
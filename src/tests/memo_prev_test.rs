@@ -0,0 +1,32 @@
+use bevy_editor_experiment_lib::{cloned, fgr::*};
+
+#[test]
+fn test_memo_prev() {
+    struct Ctx {
+        fgr_ctx: FgrCtx<Ctx>,
+    }
+    impl HasFgrCtx for Ctx {
+        fn fgr_ctx<'a>(&'a mut self) -> impl std::ops::DerefMut<Target=FgrCtx<Ctx>> + 'a {
+            &mut self.fgr_ctx
+        }
+    }
+    let mut ctx = Ctx { fgr_ctx: FgrCtx::new() };
+    let ctx = &mut ctx;
+    let mut sa = Signal::new(ctx, 1);
+    let (total, history, mut scope) = ctx.fgr_create_root(|ctx, scope| {
+        let total = Memo::reduce(ctx, sa.clone(), 0, |total, a| total + a);
+        let history = Memo::new_with_prev(ctx, Vec::new(), cloned!((sa) => move |ctx, prev: &Vec<i32>| {
+            let mut next = prev.clone();
+            next.push(*sa.value(ctx));
+            next
+        }));
+        (total, history, scope)
+    });
+    assert_eq!(*total.value(ctx), 1);
+    assert_eq!(*history.value(ctx), vec![1]);
+    sa.update_value(ctx, |a| *a = 2);
+    sa.update_value(ctx, |a| *a = 5);
+    assert_eq!(*total.value(ctx), 8);
+    assert_eq!(*history.value(ctx), vec![1, 2, 5]);
+    scope.dispose(ctx);
+}
@@ -1 +1,27 @@
 pub mod fgr_test;
+pub mod accessor_ext_test;
+pub mod maybe_reactive_test;
+pub mod effect_on_test;
+pub mod memo_prev_test;
+pub mod reactive_collections_test;
+pub mod list_test;
+pub mod history_test;
+pub mod persisted_test;
+pub mod time_test;
+pub mod animation_test;
+pub mod event_stream_test;
+pub mod system_effect_test;
+pub mod contexts_test;
+pub mod parallel_test;
+pub mod labelled_test;
+pub mod view_test;
+pub mod component_test;
+pub mod children_test;
+pub mod style_test;
+pub mod theme_test;
+pub mod layout_test;
+pub mod document_test;
+pub mod registry_test;
+pub mod focus_test;
+pub mod pointer_test;
+pub mod compile_fail_test;
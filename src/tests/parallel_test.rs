@@ -0,0 +1,120 @@
+use std::{
+    sync::{atomic::{AtomicBool, Ordering}, Arc, Mutex},
+    thread,
+};
+
+use bevy::tasks::{ComputeTaskPool, TaskPool};
+use bevy_editor_experiment_lib::{cloned, fgr::*};
+
+type Ctx = FgrDataCtx<()>;
+
+struct Graph {
+    sizes: Vec<Signal<Ctx, u64>>,
+    sums: Vec<Memo<Ctx, u64>>,
+    total: Memo<Ctx, u64>,
+    largest: Memo<Ctx, u64>,
+}
+
+fn build_graph(ctx: &mut Ctx) -> Graph {
+    let sizes: Vec<Signal<Ctx, u64>> = (0..8).map(|i| Signal::new(ctx, 1000 * (i + 1))).collect();
+    ctx.fgr_create_root(|ctx, _scope| {
+        // independent pure memos, computed in the same wave.
+        let sums: Vec<Memo<Ctx, u64>> = sizes
+            .iter()
+            .map(|size| Memo::new_pure(ctx, size.clone(), |size| (0..*size).map(|x| x * x % 7).sum()))
+            .collect();
+        // a pure memo depending on the wave above.
+        let total = Memo::new_pure(ctx, sums.clone(), |sums| sums.iter().sum());
+        // a regular memo mixed in.
+        let largest = Memo::new(ctx, {
+            let sums = sums.clone();
+            move |ctx| sums.iter().map(|sum| *sum.value(ctx)).max().unwrap()
+        });
+        Graph { sizes, sums, total, largest }
+    })
+}
+
+fn snapshot(ctx: &mut Ctx, graph: &Graph) -> Vec<u64> {
+    let mut values: Vec<u64> = graph.sums.iter().map(|sum| *sum.value(ctx)).collect();
+    values.push(*graph.total.value(ctx));
+    values.push(*graph.largest.value(ctx));
+    values
+}
+
+fn run(parallel: bool) -> Vec<Vec<u64>> {
+    let mut ctx = FgrDataCtx::new(());
+    ctx.fgr_ctx().set_parallel(parallel);
+    let ctx = &mut ctx;
+    let mut graph = build_graph(ctx);
+    let mut snapshots = vec![snapshot(ctx, &graph)];
+    ctx.fgr_batch(|ctx| {
+        for (i, size) in graph.sizes.iter_mut().enumerate() {
+            size.update_value(ctx, |x| *x += 17 * i as u64);
+        }
+    });
+    snapshots.push(snapshot(ctx, &graph));
+    graph.sizes[3].update_value(ctx, |x| *x = 1);
+    snapshots.push(snapshot(ctx, &graph));
+    ctx.fgr_batch(|ctx| {
+        graph.sizes[0].update_value(ctx, |x| *x = 50_000);
+        graph.sizes[7].update_value(ctx, |x| *x = 2);
+    });
+    snapshots.push(snapshot(ctx, &graph));
+    snapshots
+}
+
+#[test]
+fn test_parallel_matches_sequential() {
+    let sequential = run(false);
+    // every batch is fully propagated, with no stale or glitched values.
+    for values in &sequential {
+        let sums = &values[..8];
+        assert_eq!(values[8], sums.iter().sum::<u64>());
+        assert_eq!(values[9], *sums.iter().max().unwrap());
+    }
+    assert_eq!(run(true), sequential);
+    // repeated runs give the same results.
+    assert_eq!(run(true), sequential);
+    assert_ne!(sequential[0], sequential[1]);
+}
+
+#[test]
+fn test_parallel_wave_runs_on_task_pool() {
+    let pool = ComputeTaskPool::get_or_init(TaskPool::default);
+    if pool.thread_num() == 0 {
+        // without threads of its own the pool runs the jobs on the graph's thread
+        eprintln!("skipping, the compute task pool has no threads");
+        return;
+    }
+    let measuring = Arc::new(AtomicBool::new(false));
+    let threads = Arc::new(Mutex::new(Vec::new()));
+    let mut ctx = FgrDataCtx::new(());
+    ctx.fgr_ctx().set_parallel(true);
+    let ctx = &mut ctx;
+    let mut sizes: Vec<Signal<Ctx, u64>> = (0..4).map(|i| Signal::new(ctx, i)).collect();
+    let doubled: Vec<Memo<Ctx, u64>> = ctx.fgr_create_root(|ctx, _scope| {
+        sizes
+            .iter()
+            .map(|size| Memo::new_pure(ctx, size.clone(), cloned!((measuring, threads) => move |size| {
+                if measuring.load(Ordering::SeqCst) {
+                    threads.lock().unwrap().push(thread::current().id());
+                }
+                size * 2
+            })))
+            .collect()
+    });
+    measuring.store(true, Ordering::SeqCst);
+    for round in 1..=3 {
+        ctx.fgr_batch(|ctx| {
+            for size in &mut sizes {
+                size.update_value(ctx, |x| *x += 10);
+            }
+        });
+        let expected: Vec<u64> = (0..4).map(|i| (i + 10 * round) * 2).collect();
+        assert_eq!(doubled.iter().map(|memo| *memo.value(ctx)).collect::<Vec<_>>(), expected);
+    }
+    // every memo of every wave was computed by the pool, none on the graph's thread.
+    let threads = threads.lock().unwrap();
+    assert_eq!(threads.len(), 12);
+    assert!(threads.iter().all(|thread| *thread != thread::current().id()));
+}
@@ -0,0 +1,169 @@
+use std::{collections::BTreeMap, path::PathBuf, time::{Duration, Instant}};
+
+use bevy_editor_experiment_lib::fgr::*;
+use serde::{Deserialize, Serialize};
+
+struct Ctx {
+    fgr_ctx: FgrCtx<Ctx>,
+}
+
+impl HasFgrCtx for Ctx {
+    fn fgr_ctx<'a>(&'a mut self) -> impl std::ops::DerefMut<Target=FgrCtx<Ctx>> + 'a {
+        &mut self.fgr_ctx
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+struct PanelLayout {
+    width: f32,
+    open_tabs: Vec<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+enum ThemeMode {
+    Light,
+    Dark,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+enum Tab {
+    Scene,
+    Asset(String),
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+struct Dock {
+    active: Option<Tab>,
+    tabs: Vec<Tab>,
+}
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("bevy-editor-experiment-{}-{}", std::process::id(), name));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn test_persisted_signal_round_trip() {
+    let dir = temp_dir("round-trip");
+    let path = dir.join("settings.ron");
+    let mut ctx = Ctx { fgr_ctx: FgrCtx::new() };
+    let ctx = &mut ctx;
+    let settings = PersistedSettings::load(&path);
+    let default_layout = PanelLayout { width: 200.0, open_tabs: Vec::new() };
+    let (mut checked, mut layout, mut scope) = ctx.fgr_create_root(|ctx, scope| {
+        let checked = settings.signal(ctx, "checked", false);
+        let layout = settings.signal(ctx, "layout", default_layout.clone());
+        (checked, layout, scope)
+    });
+    assert!(!settings.is_dirty());
+    checked.update_value(ctx, |x| *x = true);
+    layout.update_value(ctx, |x| x.open_tabs.push("scene".into()));
+    assert!(settings.is_dirty());
+    // not due yet with the default debounce.
+    settings.flush_if_due();
+    assert!(!path.exists());
+    settings.flush();
+    assert!(!settings.is_dirty());
+    scope.dispose(ctx);
+
+    let settings = PersistedSettings::load(&path);
+    let (checked, layout, mut scope) = ctx.fgr_create_root(|ctx, scope| {
+        let checked = settings.signal(ctx, "checked", false);
+        let layout = settings.signal(ctx, "layout", default_layout.clone());
+        (checked, layout, scope)
+    });
+    assert!(*checked.value(ctx));
+    assert_eq!(layout.value(ctx).open_tabs, vec!["scene"]);
+    scope.dispose(ctx);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_persisted_enum_round_trip() {
+    let dir = temp_dir("enum-round-trip");
+    let path = dir.join("settings.ron");
+    let mut ctx = Ctx { fgr_ctx: FgrCtx::new() };
+    let ctx = &mut ctx;
+    let settings = PersistedSettings::load(&path);
+    let default_dock = Dock { active: None, tabs: Vec::new() };
+    let (mut mode, mut dock, mut scope) = ctx.fgr_create_root(|ctx, scope| {
+        let mode = settings.signal(ctx, "mode", ThemeMode::Light);
+        let dock = settings.signal(ctx, "dock", default_dock.clone());
+        (mode, dock, scope)
+    });
+    mode.update_value(ctx, |x| *x = ThemeMode::Dark);
+    dock.update_value(ctx, |x| {
+        x.tabs = vec![Tab::Scene, Tab::Asset("player.ron".into())];
+        x.active = Some(Tab::Asset("player.ron".into()));
+    });
+    settings.flush();
+    scope.dispose(ctx);
+    // each value is kept as its own RON text
+    let entries: BTreeMap<String, String> = ron::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(entries["mode"], "Dark");
+    assert!(entries["dock"].contains("Asset(\"player.ron\")"), "{}", entries["dock"]);
+
+    let settings = PersistedSettings::load(&path);
+    let (mode, dock, mut scope) = ctx.fgr_create_root(|ctx, scope| {
+        let mode = settings.signal(ctx, "mode", ThemeMode::Light);
+        let dock = settings.signal(ctx, "dock", default_dock.clone());
+        (mode, dock, scope)
+    });
+    assert_eq!(*mode.value(ctx), ThemeMode::Dark);
+    assert_eq!(*dock.value(ctx), Dock {
+        active: Some(Tab::Asset("player.ron".into())),
+        tabs: vec![Tab::Scene, Tab::Asset("player.ron".into())],
+    });
+    scope.dispose(ctx);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_persisted_signal_schema_mismatch() {
+    let dir = temp_dir("schema-mismatch");
+    let path = dir.join("settings.ron");
+    std::fs::write(&path, r#"{ "layout": "\"not a layout\"", /* kept */ "checked": "true", "note": "r\"a ( b\"" }"#).unwrap();
+    let mut ctx = Ctx { fgr_ctx: FgrCtx::new() };
+    let ctx = &mut ctx;
+    let settings = PersistedSettings::load(&path);
+    let default_layout = PanelLayout { width: 200.0, open_tabs: Vec::new() };
+    let (checked, layout, mut scope) = ctx.fgr_create_root(|ctx, scope| {
+        let checked = settings.signal(ctx, "checked", false);
+        let layout = settings.signal(ctx, "layout", default_layout.clone());
+        (checked, layout, scope)
+    });
+    assert!(*checked.value(ctx));
+    assert_eq!(*layout.value(ctx), default_layout);
+    assert_eq!(settings.get::<String>("note"), Some("a ( b".to_string()));
+    scope.dispose(ctx);
+
+    std::fs::write(&path, "this is not ron").unwrap();
+    let settings = PersistedSettings::load(&path);
+    assert_eq!(settings.get::<bool>("checked"), None);
+    assert!(path.with_extension("ron.bak").exists());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_persisted_debounce() {
+    let dir = temp_dir("debounce");
+    let path = dir.join("settings.ron");
+    let settings = PersistedSettings::load(&path);
+    settings.set_debounce(Duration::from_millis(100));
+    let start = Instant::now();
+    let at = |millis| start + Duration::from_millis(millis);
+    settings.set("width", &1.0);
+    settings.flush_if_due_at(at(0));
+    settings.set("width", &2.0);
+    settings.flush_if_due_at(at(60));
+    // the first change is older than the debounce, the last is not
+    settings.flush_if_due_at(at(120));
+    assert!(!path.exists());
+    assert!(settings.is_dirty());
+    settings.flush_if_due_at(at(160));
+    assert!(!settings.is_dirty());
+    assert_eq!(PersistedSettings::load(&path).get::<f64>("width"), Some(2.0));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,164 @@
+use std::{sync::{Arc, RwLock}, time::Duration};
+
+use bevy::prelude::*;
+use bevy_editor_experiment_lib::{cloned, fgr::*, ui::{bind_pointer, component, view, CheckBox, PointerEvent, PointerEventKind, PointerManager, PointerProps, Row}};
+
+type Received = Arc<RwLock<Vec<(PointerEventKind, Entity, Option<MouseButton>)>>>;
+
+fn record(received: &Received) -> impl FnMut(&mut World, &PointerEvent) + Send + Sync + 'static {
+    let received = Arc::clone(received);
+    move |_world, event| received.write().unwrap().push((event.kind, event.target, event.button))
+}
+
+fn click(world: &mut World, manager: &PointerManager, button: MouseButton) {
+    manager.press(world, button);
+    manager.release(world, button);
+}
+
+#[test]
+fn test_pointer_events() {
+    let mut world = World::new();
+    world.insert_resource(FgrCtx::<World>::new());
+    world.insert_resource(Time::<()>::default());
+    world.insert_resource(ButtonInput::<KeyCode>::default());
+    let world = &mut world;
+    let manager = PointerManager::get(world);
+    let received = Received::default();
+    let dropped = Arc::new(RwLock::new(Vec::new()));
+    let (row, other, is_hovered) = world.fgr_create_root(|world, _scope| {
+        let row = view! { world,
+            <Row on:click={record(&received)} on:double_click={record(&received)} on:pointer_enter={record(&received)}
+                on:pointer_leave={record(&received)} on:drag_start={record(&received)} on:drag={record(&received)}
+                on:drag_end={record(&received)}>
+                <NodeBundle />
+            </Row>
+        };
+        let other = view! { world,
+            <Row on:drop={cloned!((dropped) => move |_world, event: &PointerEvent| dropped.write().unwrap().push(event.dragged))} />
+        };
+        (row, other, manager.is_hovered(world, row))
+    });
+    let child = world.get::<Children>(row).unwrap()[0];
+    let take = |received: &Received| std::mem::take(&mut *received.write().unwrap());
+
+    // entering the child enters the row, clicks bubble up from the child
+    manager.move_to(world, Vec2::new(10.0, 10.0), Some(child));
+    assert!(*is_hovered.value(world));
+    click(world, &manager, MouseButton::Right);
+    assert_eq!(take(&received), vec![
+        (PointerEventKind::Enter, row, None),
+        (PointerEventKind::Click, child, Some(MouseButton::Right)),
+    ]);
+
+    world.resource_mut::<Time>().advance_by(Duration::from_millis(100));
+    click(world, &manager, MouseButton::Right);
+    assert_eq!(take(&received), vec![
+        (PointerEventKind::Click, child, Some(MouseButton::Right)),
+        (PointerEventKind::DoubleClick, child, Some(MouseButton::Right)),
+    ]);
+
+    // too slow for a double click
+    world.resource_mut::<Time>().advance_by(Duration::from_secs(1));
+    click(world, &manager, MouseButton::Right);
+    assert_eq!(take(&received), vec![(PointerEventKind::Click, child, Some(MouseButton::Right))]);
+
+    // pressing and moving off without dragging is not a click
+    manager.press(world, MouseButton::Left);
+    manager.move_to(world, Vec2::new(12.0, 10.0), Some(other));
+    manager.release(world, MouseButton::Left);
+    assert_eq!(take(&received), vec![(PointerEventKind::Leave, row, None)]);
+    assert!(!*is_hovered.value(world));
+
+    // dragging the child onto the other row
+    manager.move_to(world, Vec2::new(10.0, 10.0), Some(child));
+    manager.press(world, MouseButton::Left);
+    manager.move_to(world, Vec2::new(30.0, 10.0), Some(child));
+    manager.move_to(world, Vec2::new(40.0, 10.0), Some(other));
+    manager.release(world, MouseButton::Left);
+    assert_eq!(take(&received), vec![
+        (PointerEventKind::Enter, row, None),
+        (PointerEventKind::DragStart, child, Some(MouseButton::Left)),
+        (PointerEventKind::Leave, row, None),
+        (PointerEventKind::Drag, child, Some(MouseButton::Left)),
+        (PointerEventKind::DragEnd, child, Some(MouseButton::Left)),
+    ]);
+    assert_eq!(*dropped.read().unwrap(), vec![Some(child)]);
+}
+
+#[test]
+fn test_check_box_clicks() {
+    let mut world = World::new();
+    world.insert_resource(FgrCtx::<World>::new());
+    let mut keys = ButtonInput::<KeyCode>::default();
+    keys.press(KeyCode::ShiftLeft);
+    world.insert_resource(keys);
+    let world = &mut world;
+    let manager = PointerManager::get(world);
+    let changes = Arc::new(RwLock::new(Vec::new()));
+    let shift_clicks = Arc::new(RwLock::new(0));
+    let check_box = world.fgr_create_root(|world, _scope| {
+        view! { world,
+            <CheckBox
+                on:changed={cloned!((changes) => move |_world, checked| changes.write().unwrap().push(checked))}
+                on:click={cloned!((shift_clicks) => move |_world, event: &PointerEvent| {
+                    if event.modifiers.shift {
+                        *shift_clicks.write().unwrap() += 1;
+                    }
+                })}
+            />
+        }
+    });
+    manager.move_to(world, Vec2::ZERO, Some(check_box));
+    click(world, &manager, MouseButton::Left);
+    click(world, &manager, MouseButton::Right);
+    click(world, &manager, MouseButton::Left);
+    assert_eq!(*changes.read().unwrap(), vec![true, false]);
+    assert_eq!(*shift_clicks.read().unwrap(), 3);
+
+    // a press that is released elsewhere does not toggle
+    manager.press(world, MouseButton::Left);
+    manager.move_to(world, Vec2::ZERO, None);
+    manager.release(world, MouseButton::Left);
+    assert_eq!(*changes.read().unwrap(), vec![true, false]);
+}
+
+type OnClick = Box<dyn FnMut(&mut World) + Send + Sync>;
+
+/// Handles clicks itself, other pointer events go to `pointer`.
+#[component]
+fn counter(
+    world: &mut World,
+    #[prop(optional)] on_click: Option<OnClick>,
+    #[prop(optional, events)] pointer: PointerProps,
+) -> Entity {
+    let entity = world.spawn(NodeBundle::default()).id();
+    let mut pointer = pointer;
+    if let Some(mut on_click) = on_click {
+        pointer.on_click = Some(Box::new(move |world, _event| on_click(world)));
+    }
+    bind_pointer(world, entity, pointer);
+    entity
+}
+
+#[test]
+fn test_own_event_props() {
+    let mut world = World::new();
+    world.insert_resource(FgrCtx::<World>::new());
+    world.insert_resource(Time::<()>::default());
+    world.insert_resource(ButtonInput::<KeyCode>::default());
+    let world = &mut world;
+    let manager = PointerManager::get(world);
+    let clicks = Arc::new(RwLock::new(0));
+    let received = Received::default();
+    let counter = world.fgr_create_root(|world, _scope| {
+        view! { world,
+            <Counter on:click={cloned!((clicks) => move |_world| *clicks.write().unwrap() += 1)} on:pointer_enter={record(&received)} />
+        }
+    });
+
+    // `on:click` goes to the component's own prop, `on:pointer_enter` to its `pointer` prop
+    manager.move_to(world, Vec2::ZERO, Some(counter));
+    click(world, &manager, MouseButton::Left);
+    assert_eq!(*clicks.read().unwrap(), 1);
+    assert_eq!(*received.read().unwrap(), vec![(PointerEventKind::Enter, counter, None)]);
+}
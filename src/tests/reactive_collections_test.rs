@@ -0,0 +1,72 @@
+use std::{collections::BTreeMap, sync::{Arc, RwLock}};
+
+use bevy_editor_experiment_lib::fgr::*;
+
+#[test]
+fn test_reactive_collections() {
+    struct Ctx {
+        fgr_ctx: FgrCtx<Ctx>,
+    }
+    impl HasFgrCtx for Ctx {
+        fn fgr_ctx<'a>(&'a mut self) -> impl std::ops::DerefMut<Target=FgrCtx<Ctx>> + 'a {
+            &mut self.fgr_ctx
+        }
+    }
+    let mut ctx = Ctx { fgr_ctx: FgrCtx::new() };
+    let ctx = &mut ctx;
+    let mut list = ReactiveVec::new(ctx, vec!['a', 'b']);
+    let mut map = ReactiveMap::new(ctx, BTreeMap::from([(1, "one")]));
+    ctx.fgr_batch(|ctx| {
+        list.push(ctx, 'c');
+        list.move_item(ctx, 2, 0);
+        list.remove(ctx, 1);
+    });
+    assert_eq!(*list.items().value(ctx), vec!['c', 'b']);
+    assert_eq!(*list.patches().value(ctx), vec![
+        VecPatch::Insert { index: 2, value: 'c' },
+        VecPatch::Move { from: 2, to: 0 },
+        VecPatch::Remove { index: 1 },
+    ]);
+    // a new batch starts a new patch list.
+    list.set(ctx, 0, 'z');
+    assert_eq!(*list.items().value(ctx), vec!['z', 'b']);
+    assert_eq!(*list.patches().value(ctx), vec![VecPatch::Update { index: 0, value: 'z' }]);
+    map.insert(ctx, 2, "two");
+    map.insert(ctx, 1, "uno");
+    map.remove(ctx, 3);
+    assert_eq!(map.len(ctx), 2);
+    assert_eq!(map.get(ctx, &1), Some("uno"));
+    assert_eq!(*map.patches().value(ctx), vec![MapPatch::Update { key: 1, value: "uno" }]);
+
+    // updating a missing key does nothing
+    let patch_counts = Arc::new(RwLock::new(Vec::new()));
+    ctx.fgr_create_root(|ctx, _scope| {
+        let patches = map.patches();
+        let patch_counts = Arc::clone(&patch_counts);
+        ctx.fgr_create_effect(move |ctx| patch_counts.write().unwrap().push(patches.value(ctx).len()));
+    });
+    map.update(ctx, 3, |value| *value = "three");
+    assert_eq!(map.get(ctx, &3), None);
+    map.update(ctx, 2, |value| *value = "dos");
+    assert_eq!(map.get(ctx, &2), Some("dos"));
+    assert_eq!(*map.patches().value(ctx), vec![MapPatch::Update { key: 2, value: "dos" }]);
+    assert_eq!(*patch_counts.read().unwrap(), vec![1, 1]);
+}
+
+#[test]
+fn test_reactive_vec_out_of_range() {
+    let mut ctx = FgrDataCtx::new(());
+    let ctx = &mut ctx;
+    let mut list = ReactiveVec::new(ctx, vec!['a', 'b']);
+    list.set(ctx, 0, 'z');
+    // operations on an index out of range do nothing, rather than panicking with the list locked
+    list.remove(ctx, 2);
+    list.set(ctx, 5, 'x');
+    list.update(ctx, 2, |value| *value = 'y');
+    list.move_item(ctx, 0, 2);
+    list.insert(ctx, 3, 'c');
+    assert_eq!(*list.items().value(ctx), vec!['z', 'b']);
+    assert_eq!(*list.patches().value(ctx), vec![VecPatch::Update { index: 0, value: 'z' }]);
+    list.insert(ctx, 2, 'c');
+    assert_eq!(*list.items().value(ctx), vec!['z', 'b', 'c']);
+}
@@ -0,0 +1,61 @@
+use bevy::{prelude::*, reflect::TypeInfo};
+use bevy_editor_experiment_lib::{fgr::*, ui::{self, prop, MaybeReactive, RowPropsData, Slot, UiComponentError, UiComponentRegistry}};
+
+#[derive(Reflect, Default, PartialEq, Debug)]
+#[reflect(Default)]
+struct BadgeProps {
+    label: String,
+    width: Val,
+}
+
+fn registry() -> UiComponentRegistry {
+    let registry = UiComponentRegistry::with_builtins();
+    registry.register("Badge", |world, props: MaybeReactive<BadgeProps>, mut children: Slot| {
+        let label = prop(world, &props, |props| props.label.clone());
+        let width = prop(world, &props, |props| props.width);
+        let label = world.fgr_untrack(|world| label.value(world).clone());
+        let badge_id = world.spawn((Name::new(label), NodeBundle::default())).id();
+        ui::bind_style(world, badge_id, ui::StyleProps::new().width(width));
+        children.mount(world, badge_id);
+        badge_id
+    });
+    registry
+}
+
+#[test]
+fn test_component_registry() {
+    let mut world = World::new();
+    world.insert_resource(FgrCtx::<World>::new());
+    let world = &mut world;
+    let registry = registry();
+    assert!(registry.names().iter().any(|name| name == "Badge"));
+    assert!(registry.names().iter().any(|name| name == "Row"));
+    assert!(registry.names().iter().any(|name| name == "Grid"));
+    let Some(TypeInfo::Struct(info)) = registry.props_info("Badge") else { panic!("badge props are a struct") };
+    assert_eq!(info.field_names(), ["label", "width"]);
+    // builtin components are registered with their own props
+    let Some(TypeInfo::Struct(info)) = registry.props_info("Row") else { panic!("row props are a struct") };
+    assert_eq!(info.field_names(), ["gap", "align", "justify", "wrap", "children", "style", "pointer"]);
+
+    let child = world.spawn(Name::new("child")).id();
+    let (from_ron, from_json, reflected, row) = world.fgr_create_root(|world, _scope| {
+        let from_ron = registry.construct_from_ron(world, "Badge", r#"(label: "ron", width: Px(5.0))"#, Slot::default()).unwrap();
+        // fields that are left out keep their defaults
+        let from_json = registry.construct_from_json(world, "Badge", r#"{"label": "json"}"#, Slot::default()).unwrap();
+        let props = BadgeProps { label: "reflected".into(), width: ui::percent(10.0) };
+        let reflected = registry.construct(world, "Badge", &props, Slot::from(child)).unwrap();
+        let row = registry.construct(world, "Row", &RowPropsData { gap: Some(ui::px(3.0)), wrap: Some(FlexWrap::Wrap), ..default() }, Slot::default()).unwrap();
+
+        assert!(matches!(registry.construct_from_ron(world, "Missing", "()", Slot::default()), Err(UiComponentError::UnknownComponent(_))));
+        assert!(matches!(registry.construct_from_ron(world, "Badge", "(size: 3)", Slot::default()), Err(UiComponentError::Props(_))));
+        (from_ron, from_json, reflected, row)
+    });
+    assert_eq!(world.get::<Name>(from_ron).unwrap().as_str(), "ron");
+    assert_eq!(world.get::<Style>(from_ron).unwrap().width, ui::px(5.0));
+    assert_eq!(world.get::<Name>(from_json).unwrap().as_str(), "json");
+    assert_eq!(world.get::<Style>(from_json).unwrap().width, Val::Auto);
+    assert_eq!(world.get::<Style>(reflected).unwrap().width, ui::percent(10.0));
+    assert_eq!(world.get::<Children>(reflected).unwrap().to_vec(), vec![child]);
+    assert_eq!(world.get::<Style>(row).unwrap().column_gap, ui::px(3.0));
+    assert_eq!(world.get::<Style>(row).unwrap().flex_wrap, FlexWrap::Wrap);
+}
@@ -0,0 +1,70 @@
+use bevy::prelude::*;
+use bevy_editor_experiment_lib::{fgr::*, ui::{self, bind_style, view, Panel, StyleProps}};
+
+#[test]
+fn test_bind_style() {
+    let mut world = World::new();
+    world.insert_resource(FgrCtx::<World>::new());
+    let world = &mut world;
+    let mut width = Signal::new(world, ui::px(10.0));
+    let mut color = Signal::new(world, Color::BLACK);
+    let entity = world.spawn(NodeBundle::default()).id();
+    let text_entity = world.spawn(TextBundle::from_section("", TextStyle::default())).id();
+    let mut scope = world.fgr_create_root(|world, scope| {
+        bind_style(world, entity, StyleProps::new()
+            .width(width.clone())
+            .padding(UiRect::all(ui::px(4.0)))
+            .background_color(color.clone())
+            .border_radius(ui::px(3.0))
+            .font_size(30.0));
+        bind_style(world, text_entity, StyleProps::new().font_size(12.0).text_color(color.clone()));
+        scope
+    });
+    let style = world.get::<Style>(entity).unwrap();
+    assert_eq!(style.width, ui::px(10.0));
+    assert_eq!(style.padding, UiRect::all(ui::px(4.0)));
+    assert_eq!(*world.get::<BorderRadius>(entity).unwrap(), BorderRadius::all(ui::px(3.0)));
+    assert_eq!(world.get::<Text>(text_entity).unwrap().sections[0].style.font_size, 12.0);
+
+    // only the components of fields that changed are marked changed
+    world.clear_trackers();
+    width.update_value(world, |x| *x = ui::px(20.0));
+    assert_eq!(world.get::<Style>(entity).unwrap().width, ui::px(20.0));
+    assert!(world.entity(entity).get_ref::<Style>().unwrap().is_changed());
+    assert!(!world.entity(entity).get_ref::<BackgroundColor>().unwrap().is_changed());
+
+    world.clear_trackers();
+    color.update_value(world, |x| *x = Color::WHITE);
+    assert_eq!(world.get::<BackgroundColor>(entity).unwrap().0, Color::WHITE);
+    assert_eq!(world.get::<Text>(text_entity).unwrap().sections[0].style.color, Color::WHITE);
+    assert!(!world.entity(entity).get_ref::<Style>().unwrap().is_changed());
+
+    // writing the same value again is not a change
+    world.clear_trackers();
+    color.update_value(world, |x| *x = Color::WHITE);
+    assert!(!world.entity(entity).get_ref::<BackgroundColor>().unwrap().is_changed());
+
+    scope.dispose(world);
+    width.update_value(world, |x| *x = ui::px(30.0));
+    assert_eq!(world.get::<Style>(entity).unwrap().width, ui::px(20.0));
+}
+
+#[test]
+fn test_component_style_props() {
+    let mut world = World::new();
+    world.insert_resource(FgrCtx::<World>::new());
+    let world = &mut world;
+    let mut direction = Signal::new(world, FlexDirection::Row);
+    let panel_id = world.fgr_create_root(|world, _scope| {
+        view!(world, <Panel style={StyleProps::new().padding(UiRect::all(ui::px(5.0)))} />)
+    });
+    let styled_panel_id = world.fgr_create_root(|world, _scope| {
+        view!(world, <Panel style={StyleProps::new().flex_direction(direction.clone())} />)
+    });
+    let style = world.get::<Style>(panel_id).unwrap();
+    assert_eq!(style.padding, UiRect::all(ui::px(5.0)));
+    assert_eq!(style.flex_direction, FlexDirection::Column);
+    assert_eq!(world.get::<Style>(styled_panel_id).unwrap().flex_direction, FlexDirection::Row);
+    direction.update_value(world, |x| *x = FlexDirection::RowReverse);
+    assert_eq!(world.get::<Style>(styled_panel_id).unwrap().flex_direction, FlexDirection::RowReverse);
+}
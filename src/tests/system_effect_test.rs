@@ -0,0 +1,200 @@
+use std::sync::{Arc, RwLock};
+
+use bevy::prelude::*;
+use bevy_editor_experiment_lib::fgr::*;
+
+#[derive(Component)]
+struct Greeted;
+
+#[derive(Resource, Default)]
+struct Greeting(String);
+
+#[test]
+fn test_system_effect() {
+    let mut world = World::new();
+    world.insert_resource(FgrCtx::<World>::new());
+    world.insert_resource(Greeting("hello".into()));
+    let world = &mut world;
+    let a = world.spawn(Name::new("a")).id();
+    let seen = Arc::new(RwLock::new(Vec::new()));
+    let runs = Arc::new(RwLock::new(0));
+    let mut scope = world.fgr_create_root({
+        let seen = Arc::clone(&seen);
+        let runs = Arc::clone(&runs);
+        move |world, scope| {
+            world.fgr_create_system_effect(move |names: Query<(Entity, &Name), Without<Greeted>>, greeting: Res<Greeting>, mut commands: Commands| {
+                *runs.write().unwrap() += 1;
+                let mut names: Vec<String> = names.iter().map(|(entity, name)| {
+                    commands.entity(entity).insert(Greeted);
+                    format!("{} {}", greeting.0, name)
+                }).collect();
+                names.sort();
+                seen.write().unwrap().extend(names);
+            });
+            scope
+        }
+    });
+    // the commands were applied right after the run.
+    assert!(world.get::<Greeted>(a).is_some());
+    assert_eq!(*seen.read().unwrap(), vec!["hello a".to_string()]);
+    assert_eq!(*runs.read().unwrap(), 1);
+    // nothing it accesses changed, including through its own commands.
+    world.fgr_update();
+    world.fgr_update();
+    assert_eq!(*runs.read().unwrap(), 1);
+    world.spawn(Name::new("b"));
+    world.fgr_update();
+    assert_eq!(*seen.read().unwrap(), vec!["hello a".to_string(), "hello b".to_string()]);
+    assert_eq!(*runs.read().unwrap(), 2);
+    world.resource_mut::<Greeting>().0 = "hi".into();
+    world.fgr_update();
+    assert_eq!(*runs.read().unwrap(), 3);
+    world.entity_mut(a).remove::<Name>();
+    world.fgr_update();
+    assert_eq!(*runs.read().unwrap(), 4);
+    world.fgr_update();
+    assert_eq!(*runs.read().unwrap(), 4);
+    scope.dispose(world);
+    world.spawn(Name::new("c"));
+    world.fgr_update();
+    assert_eq!(*runs.read().unwrap(), 4);
+}
+
+#[derive(Component)]
+struct Unread(u32);
+
+#[test]
+fn test_system_effect_tracks_new_archetypes() {
+    let mut world = World::new();
+    world.insert_resource(FgrCtx::<World>::new());
+    let world = &mut world;
+    let runs = Arc::new(RwLock::new(0));
+    world.fgr_create_root({
+        let runs = Arc::clone(&runs);
+        move |world, _scope| {
+            world.fgr_create_system_effect(move |_names: Query<&Name>| *runs.write().unwrap() += 1);
+        }
+    });
+    // spawned into an archetype that did not exist when the effect was created
+    let a = world.spawn((Name::new("a"), Unread(0))).id();
+    world.fgr_update();
+    assert_eq!(*runs.read().unwrap(), 2);
+    world.get_mut::<Unread>(a).unwrap().0 = 1;
+    world.fgr_update();
+    assert_eq!(*runs.read().unwrap(), 2);
+    world.get_mut::<Name>(a).unwrap().set("b");
+    world.fgr_update();
+    assert_eq!(*runs.read().unwrap(), 3);
+}
+
+#[test]
+fn test_system_effect_resource_presence() {
+    let mut world = World::new();
+    world.insert_resource(FgrCtx::<World>::new());
+    let world = &mut world;
+    let seen = Arc::new(RwLock::new(Vec::new()));
+    world.fgr_create_root({
+        let seen = Arc::clone(&seen);
+        move |world, _scope| {
+            world.fgr_create_system_effect(move |greeting: Option<Res<Greeting>>| {
+                seen.write().unwrap().push(greeting.map(|greeting| greeting.0.clone()));
+            });
+        }
+    });
+    world.insert_resource(Greeting("hello".into()));
+    world.fgr_update();
+    world.fgr_update();
+    world.remove_resource::<Greeting>();
+    world.fgr_update();
+    world.fgr_update();
+    assert_eq!(*seen.read().unwrap(), vec![None, Some("hello".to_string()), None]);
+}
+
+#[test]
+fn test_system_effect_reading_the_world() {
+    let mut world = World::new();
+    world.insert_resource(FgrCtx::<World>::new());
+    let world = &mut world;
+    let a = world.spawn(Name::new("a")).id();
+    let runs = Arc::new(RwLock::new(0));
+    world.fgr_create_root({
+        let runs = Arc::clone(&runs);
+        move |world, _scope| {
+            world.fgr_create_system_effect(move |_world: &World| *runs.write().unwrap() += 1);
+        }
+    });
+    world.fgr_update();
+    world.fgr_update();
+    assert_eq!(*runs.read().unwrap(), 1);
+    world.get_mut::<Name>(a).unwrap().set("b");
+    world.fgr_update();
+    assert_eq!(*runs.read().unwrap(), 2);
+    // components registered after the effect was created are tracked too
+    world.entity_mut(a).insert(Unread(0));
+    world.fgr_update();
+    assert_eq!(*runs.read().unwrap(), 3);
+    world.get_mut::<Unread>(a).unwrap().0 = 1;
+    world.fgr_update();
+    assert_eq!(*runs.read().unwrap(), 4);
+    world.fgr_update();
+    assert_eq!(*runs.read().unwrap(), 4);
+}
+
+#[derive(Component)]
+struct Count(u32);
+
+#[test]
+fn test_system_effects_see_each_others_writes() {
+    let mut world = World::new();
+    world.insert_resource(FgrCtx::<World>::new());
+    world.insert_resource(Greeting("hello".into()));
+    let world = &mut world;
+    let a = world.spawn(Count(0)).id();
+    let seen = Arc::new(RwLock::new(Vec::new()));
+    world.fgr_create_root({
+        let seen = Arc::clone(&seen);
+        move |world, _scope| {
+            // the second effect reads what the first one writes
+            world.fgr_create_system_effect(move |greeting: Res<Greeting>, mut counts: Query<&mut Count>| {
+                for mut count in &mut counts {
+                    count.0 = greeting.0.len() as u32;
+                }
+            });
+            world.fgr_create_system_effect(move |counts: Query<&Count>| {
+                seen.write().unwrap().extend(counts.iter().map(|count| count.0));
+            });
+        }
+    });
+    assert_eq!(*seen.read().unwrap(), vec![5]);
+    // the effects are not checked in any particular order, so the write is seen in the same
+    // update or the next one, and only once
+    world.resource_mut::<Greeting>().0 = "hi".into();
+    world.fgr_update();
+    world.fgr_update();
+    assert_eq!(*seen.read().unwrap(), vec![5, 2]);
+    world.fgr_update();
+    assert_eq!(*seen.read().unwrap(), vec![5, 2]);
+    world.get_mut::<Count>(a).unwrap().0 = 7;
+    world.fgr_update();
+    assert_eq!(*seen.read().unwrap(), vec![5, 2, 7]);
+}
+
+#[test]
+fn test_system_effect_past_the_scan_limit() {
+    let mut world = World::new();
+    world.insert_resource(FgrCtx::<World>::new());
+    let world = &mut world;
+    world.spawn_batch((0..=SYSTEM_EFFECT_SCAN_LIMIT as u32).map(Count));
+    let runs = Arc::new(RwLock::new(0));
+    world.fgr_create_root({
+        let runs = Arc::clone(&runs);
+        move |world, _scope| {
+            world.fgr_create_system_effect(move |_counts: Query<&Count>| *runs.write().unwrap() += 1);
+        }
+    });
+    // too many counts to look for changes in, so the effect runs on every update
+    let runs_before = *runs.read().unwrap();
+    world.fgr_update();
+    world.fgr_update();
+    assert_eq!(*runs.read().unwrap(), runs_before + 2);
+}
@@ -0,0 +1,75 @@
+use bevy::prelude::*;
+use bevy_editor_experiment_lib::{cloned, fgr::*, ui::{self, theme_token, view, Panel, TextBox, Theme, ThemeTokens}};
+
+#[test]
+fn test_theme_token() {
+    let mut world = World::new();
+    world.insert_resource(FgrCtx::<World>::new());
+    let world = &mut world;
+    let (border, font_size) = world.fgr_create_root(|world, _scope| {
+        (theme_token(world, |theme| theme.border), theme_token(world, |theme| theme.font_sizes.body))
+    });
+    // without a theme the tokens are the default theme's, and never change
+    assert!(border.is_static());
+    assert_eq!(*border.value(world), ThemeTokens::default().border);
+    assert_eq!(*font_size.value(world), 20.0);
+
+    let theme = Theme::new(world, ThemeTokens::dark());
+    world.insert_resource(theme.clone());
+    let border = world.fgr_create_root(|world, _scope| theme_token(world, |theme| theme.border));
+    let runs = Signal::new(world, 0);
+    world.fgr_create_root(|world, _scope| {
+        world.fgr_create_effect_on(border.clone(), cloned!((runs) => move |world, _border, _| {
+            runs.clone().update_value(world, |x| *x += 1);
+        }));
+    });
+    assert_eq!(*runs.value(world), 1);
+    theme.set(world, ThemeTokens::light());
+    assert_eq!(*border.value(world), ThemeTokens::light().border);
+    assert_eq!(*runs.value(world), 2);
+
+    // changing another token leaves the border alone
+    theme.set(world, ThemeTokens { spacing: vec![2.0], ..ThemeTokens::light() });
+    assert_eq!(*runs.value(world), 2);
+}
+
+#[test]
+fn test_theme_restyles_components() {
+    let mut world = World::new();
+    world.insert_resource(FgrCtx::<World>::new());
+    let world = &mut world;
+    let theme = Theme::new(world, ThemeTokens::dark());
+    world.insert_resource(theme.clone());
+    let (panel_id, text_box_id) = world.fgr_create_root(|world, _scope| {
+        let text_box_id = view!(world, <TextBox contents="text" />);
+        (view!(world, <Panel />), text_box_id)
+    });
+    let text_id = world.get::<Children>(text_box_id).unwrap()[0];
+    assert_eq!(world.get::<Style>(panel_id).unwrap().row_gap, ui::px(8.0));
+    assert_eq!(world.get::<BorderColor>(text_box_id).unwrap().0, ThemeTokens::dark().border);
+    assert_eq!(world.get::<Text>(text_id).unwrap().sections[0].style.color, ThemeTokens::dark().text);
+
+    theme.set(world, ThemeTokens { spacing: vec![0.0, 2.0, 6.0], ..ThemeTokens::light() });
+    assert_eq!(world.get::<Style>(panel_id).unwrap().row_gap, ui::px(6.0));
+    assert_eq!(world.get::<BorderColor>(text_box_id).unwrap().0, ThemeTokens::light().border);
+    assert_eq!(world.get::<Text>(text_id).unwrap().sections[0].style.color, ThemeTokens::light().text);
+}
+
+#[test]
+fn test_theme_tokens_ron() {
+    let tokens = ThemeTokens::light();
+    let ron = ron::ser::to_string(&tokens).unwrap();
+    assert!(ron.contains("surface:\"#FFFFFF\""));
+    assert_eq!(ron::de::from_str::<ThemeTokens>(&ron).unwrap(), tokens);
+    let editor = include_str!("../../assets/themes/editor.theme.ron");
+    assert!(ron::de::from_str::<ThemeTokens>(editor).is_ok());
+    assert!(ron::de::from_str::<ThemeTokens>(&ron.replace("#FFFFFF", "white")).is_err());
+}
+
+#[test]
+fn test_theme_space() {
+    let tokens = ThemeTokens { spacing: vec![0.0, 4.0, 8.0], ..ThemeTokens::dark() };
+    assert_eq!(tokens.space(1), ui::px(4.0));
+    assert_eq!(tokens.space(10), ui::px(8.0));
+    assert_eq!(ThemeTokens { spacing: vec![], ..tokens }.space(1), Val::ZERO);
+}
@@ -0,0 +1,64 @@
+use std::{sync::{Arc, Mutex}, time::Duration};
+
+use bevy::prelude::*;
+use bevy_editor_experiment_lib::{cloned, fgr::*};
+
+fn advance(world: &mut World, millis: u64) {
+    world.resource_mut::<Time>().advance_by(Duration::from_millis(millis));
+    world.fgr_update();
+}
+
+#[test]
+fn test_time_signals() {
+    let mut world = World::new();
+    world.insert_resource(FgrCtx::<World>::new());
+    world.insert_resource(Time::<()>::default());
+    let world = &mut world;
+    let mut source = Signal::new(world, 0);
+    let (debounced, throttled, delayed, ticks, mut scope) = world.fgr_create_root(|world, scope| {
+        let debounced = debounce(world, source.clone(), Duration::from_millis(100));
+        let throttled = throttle(world, source.clone(), Duration::from_millis(100));
+        let delayed = delayed(world, source.clone(), Duration::from_millis(100));
+        let ticks = interval(world, Duration::from_millis(50));
+        (debounced, throttled, delayed, ticks, scope)
+    });
+    source.update_value(world, |x| *x = 1);
+    assert_eq!(*throttled.value(world), 1);
+    advance(world, 60);
+    source.update_value(world, |x| *x = 2);
+    assert_eq!(*throttled.value(world), 1);
+    advance(world, 60);
+    assert_eq!(*debounced.value(world), 0);
+    assert_eq!(*throttled.value(world), 2);
+    assert_eq!(*delayed.value(world), 1);
+    assert_eq!(*ticks.value(world), 2);
+    advance(world, 60);
+    assert_eq!(*debounced.value(world), 2);
+    assert_eq!(*delayed.value(world), 2);
+    scope.dispose(world);
+    // disposed with the scope, so no more ticks.
+    advance(world, 100);
+    assert_eq!(*ticks.value(world), 3);
+}
+
+#[test]
+fn test_delayed_replays_every_change() {
+    let mut world = World::new();
+    world.insert_resource(FgrCtx::<World>::new());
+    world.insert_resource(Time::<()>::default());
+    let world = &mut world;
+    let mut source = Signal::new(world, 0);
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    world.fgr_create_root(|world, _scope| {
+        let delayed = delayed(world, source.clone(), Duration::from_millis(100));
+        world.fgr_create_effect_on(delayed, cloned!((seen) => move |_world, value, _| seen.lock().unwrap().push(*value)));
+    });
+    source.update_value(world, |x| *x = 1);
+    advance(world, 10);
+    source.update_value(world, |x| *x = 2);
+    advance(world, 10);
+    source.update_value(world, |x| *x = 3);
+    // all three fall due in the same update
+    advance(world, 200);
+    assert_eq!(*seen.lock().unwrap(), [0, 1, 2, 3]);
+}
@@ -0,0 +1,52 @@
+use bevy::prelude::*;
+use bevy_editor_experiment_lib::{fgr::*, ui::{view, For, Show}};
+
+#[test]
+fn test_view() {
+    let mut world = World::new();
+    world.insert_resource(FgrCtx::<World>::new());
+    let world = &mut world;
+    let mut shown = Signal::new(world, false);
+    let mut color = Signal::new(world, BackgroundColor(Color::BLACK));
+    let mut items = ReactiveVec::new(world, vec!["a".to_string(), "b".to_string()]);
+    let (root, mut scope) = world.fgr_create_root(|world, scope| {
+        let root = view! { world,
+            <NodeBundle bind:background_color={color.clone()}>
+                "title"
+                <Show when={shown.clone()}>
+                    <NodeBundle />
+                    <NodeBundle />
+                </Show>
+                <For each={items.clone()} render={|world, item: &String| world.spawn(Name::new(item.clone())).id()} />
+                {world.spawn(Name::new("block")).id()}
+            </NodeBundle>
+        };
+        (root, scope)
+    });
+    let children = world.get::<Children>(root).unwrap().to_vec();
+    assert_eq!(children.len(), 4);
+    assert_eq!(world.get::<Text>(children[0]).unwrap().sections[0].value, "title");
+    assert_eq!(world.get::<Name>(children[3]).unwrap().as_str(), "block");
+    let show_id = children[1];
+    let list_id = children[2];
+    let child_count = |world: &World, entity: Entity| world.get::<Children>(entity).map_or(0, |children| children.len());
+    assert_eq!(child_count(world, show_id), 0);
+    assert_eq!(child_count(world, list_id), 2);
+
+    shown.update_value(world, |x| *x = true);
+    assert_eq!(child_count(world, show_id), 2);
+    let shown_children = world.get::<Children>(show_id).unwrap().to_vec();
+    shown.update_value(world, |x| *x = false);
+    assert_eq!(child_count(world, show_id), 0);
+    assert!(shown_children.iter().all(|entity| world.get_entity(*entity).is_none()));
+
+    items.push(world, "c".to_string());
+    assert_eq!(child_count(world, list_id), 3);
+
+    color.update_value(world, |x| *x = BackgroundColor(Color::WHITE));
+    assert_eq!(world.get::<BackgroundColor>(root).unwrap().0, Color::WHITE);
+
+    scope.dispose(world);
+    assert!(world.get_entity(root).is_none());
+    assert!(children.iter().all(|entity| world.get_entity(*entity).is_none()));
+}
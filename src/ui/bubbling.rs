@@ -0,0 +1,87 @@
+use std::{collections::HashMap, sync::{atomic::{AtomicBool, Ordering}, Arc, RwLock}};
+
+use bevy::{hierarchy::Parent, prelude::{Entity, Resource, World}};
+
+use crate::fgr::{EventStream, FgrLabelExtensionMethods};
+
+/// Shared by the clones of an event, so a handler can keep it from bubbling further.
+#[derive(Clone, Default, Debug)]
+pub(super) struct Propagation(Arc<AtomicBool>);
+
+impl Propagation {
+    pub(super) fn stop(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub(super) fn is_stopped(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// An event that bubbles from its target up through the target's ancestors.
+pub(super) trait BubblingEvent: Clone + Send + Sync + 'static {
+    fn propagation(&self) -> &Propagation;
+}
+
+/// One `EventStream` per entity for events that bubble, such as key and pointer events. Clones
+/// share the streams.
+pub(super) struct BubblingEvents<E> {
+    streams: Arc<RwLock<HashMap<Entity, EventStream<World, E>>>>,
+}
+
+impl<E> Clone for BubblingEvents<E> {
+    fn clone(&self) -> Self {
+        Self {
+            streams: Arc::clone(&self.streams),
+        }
+    }
+}
+
+impl<E> Default for BubblingEvents<E> {
+    fn default() -> Self {
+        Self {
+            streams: Arc::default(),
+        }
+    }
+}
+
+impl<E: BubblingEvent> BubblingEvents<E> {
+    pub(super) fn events(&self, entity: Entity) -> EventStream<World, E> {
+        self.streams.write().unwrap().entry(entity).or_default().clone()
+    }
+
+    /// Hands `event` to `entity` alone.
+    pub(super) fn emit(&self, world: &mut World, entity: Entity, event: &E) {
+        let stream = self.streams.read().unwrap().get(&entity).cloned();
+        if let Some(stream) = stream {
+            stream.emit(world, event);
+        }
+    }
+
+    /// Hands `event` to `target` and then its ancestors, until a handler stops it.
+    pub(super) fn dispatch(&self, world: &mut World, target: Entity, event: &E) {
+        let mut entity = Some(target);
+        while let Some(current) = entity {
+            self.emit(world, current, event);
+            if event.propagation().is_stopped() {
+                break;
+            }
+            entity = world.get::<Parent>(current).map(Parent::get);
+        }
+    }
+
+    /// Drops the streams of despawned entities.
+    pub(super) fn prune(&self, world: &World) {
+        self.streams.write().unwrap().retain(|entity, _| world.get_entity(*entity).is_some());
+    }
+}
+
+/// The active graph's `R` resource, inserted with `new` if there is none yet.
+pub(super) fn get_or_insert_resource<R: Resource + Clone>(world: &mut World, new: impl FnOnce(&mut World) -> R) -> R {
+    if let Some(resource) = world.fgr_resource::<R>() {
+        return resource;
+    }
+    let resource = new(world);
+    world.fgr_insert_resource(resource.clone());
+    resource
+}
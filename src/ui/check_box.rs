@@ -1,84 +1,88 @@
-use std::sync::{Arc, RwLock};
+use std::{sync::{Arc, RwLock}, time::Duration};
 
-use bevy::{color::{palettes::css::RED, Color}, prelude::{ButtonBundle, Entity, World}, ui::{BackgroundColor, BorderRadius, Interaction, Style, Val}};
+use bevy::{input::{keyboard::KeyCode, mouse::MouseButton}, prelude::{ButtonBundle, DespawnRecursiveExt, Entity, World}, ui::{BackgroundColor, Val}};
 
-use crate::fgr::FgrExtensionMethods;
+use crate::{cloned, fgr::{tween, Accessor, BoxedAccessor, Easing, FgrExtensionMethods, History, Signal}};
 
-use super::UiComponent;
+use super::{bind_pointer, bind_style, component, theme_token, use_focus, PointerEventKind, PointerManager, PointerProps, StyleProps};
 
-pub struct CheckBoxProps {
-    pub on_changed: Option<Box<dyn FnMut(&mut World, bool) + Send + Sync>>,
-}
-
-impl Default for CheckBoxProps {
-    fn default() -> Self {
-        Self {
-            on_changed: Default::default(),
-        }
-    }
-}
+type OnChanged = Box<dyn FnMut(&mut World, bool) + Send + Sync>;
 
 struct CheckBoxState {
-    pub props: CheckBoxProps,
-    pub last_interaction: Interaction,
-    pub checked: bool,
+    pub on_changed: Option<OnChanged>,
 }
 
 impl CheckBoxState {
-    fn new(props: CheckBoxProps) -> Self {
+    fn new(on_changed: Option<OnChanged>) -> Self {
         Self {
-            props,
-            last_interaction: Interaction::None,
-            checked: false,
+            on_changed,
         }
     }
 }
 
-pub struct CheckBox;
+#[component(reflect)]
+pub fn check_box(
+    world: &mut World,
+    #[prop(optional)] on_changed: Option<OnChanged>,
+    #[prop(optional)] style: StyleProps,
+    #[prop(optional, events)] pointer: PointerProps,
+) -> Entity {
+    let state = Arc::new(RwLock::new(CheckBoxState::new(on_changed)));
+    let mut checked = Signal::new_recorded(world, false);
+    let mut style = style.or(
+        StyleProps::new()
+            .width(Val::Px(10.0))
+            .height(Val::Px(10.0))
+            .border_color(theme_token(world, |theme| theme.border))
+            .border_radius(Val::Px(5.0))
+    );
+    // the background is animated between the accent color and this one
+    let unchecked_color = style.background_color.take().unwrap_or_else(|| theme_token(world, |theme| theme.surface));
+    let checked_color = theme_token(world, |theme| theme.accent);
+    let color = tween(world, BoxedAccessor::derived(cloned!((checked) => move |world| {
+        if *checked.value(world) { *checked_color.value(world) } else { *unchecked_color.value(world) }
+    })), Duration::from_millis(150), Easing::EaseOut);
+    let checkbox_id = world.spawn(ButtonBundle::default()).id();
+    bind_style(world, checkbox_id, style);
+    bind_pointer(world, checkbox_id, pointer);
+    world.fgr_on_cleanup(move |world| {
+        if let Some(entity) = world.get_entity_mut(checkbox_id) {
+            entity.despawn_recursive();
+        }
+    });
+    world.fgr_create_effect(move |world| {
+        let color = *color.value(world);
+        if let Some(mut background_color) = world.get_mut::<BackgroundColor>(checkbox_id) {
+            background_color.0 = color;
+        }
+    });
+    let focus = use_focus(world, checkbox_id, 0);
+    let space_presses = focus.key_events().filter(world, |event| event.input.state.is_pressed() && event.input.key_code == KeyCode::Space);
+    space_presses.on_event(world, cloned!((state, checked) => move |world, event| {
+        toggle(world, &mut checked, &mut state.write().unwrap());
+        event.stop_propagation();
+    }));
+    let clicks = PointerManager::get(world)
+        .pointer_events(checkbox_id)
+        .filter(world, |event| event.kind == PointerEventKind::Click && event.button == Some(MouseButton::Left));
+    clicks.on_event(world, move |world, _event| {
+        toggle(world, &mut checked, &mut state.write().unwrap());
+    });
+    checkbox_id
+}
 
-impl UiComponent<CheckBoxProps> for CheckBox {
-    fn run(world: &mut World, props: CheckBoxProps) -> Entity {
-        let state = Arc::new(RwLock::new(CheckBoxState::new(props)));
-        let checkbox_id = world.spawn(
-                ButtonBundle {
-                    style: Style {
-                        width: Val::Px(10.0),
-                        height: Val::Px(10.0),
-                        ..Default::default()
-                    },
-                    border_color: Color::WHITE.into(),
-                    border_radius: BorderRadius::all(Val::Px(5.0)),
-                    background_color: Color::BLACK.into(),
-                    ..Default::default()
-                }
-            )
-            .id();
-        world.fgr_on_mount(|ctx| {
-            println!("checkbox mounted");
-        });
-        world.fgr_on_cleanup(move |world| {
-            world.despawn(checkbox_id);
-        });
-        world.fgr_on_update(move |world| {
-            let mut state = state.write().unwrap();
-            let entity = checkbox_id;
-            let Some(interaction) = world.get::<Interaction>(entity) else { return; };
-            if *interaction == state.last_interaction {
-                return;
-            }
-            state.last_interaction = *interaction;
-            if *interaction == Interaction::Pressed {
-                state.checked = !state.checked;
-                world
-                    .get_mut::<BackgroundColor>(entity)
-                    .unwrap()
-                    .0 = if state.checked { RED.into() } else { Color::BLACK };
-                let checked = state.checked;
-                if let Some(on_changed) = &mut state.props.on_changed {
-                    on_changed(world, checked);
-                }
-            }
-        });
-        checkbox_id
+/// Goes through the `History` resource when there is one, together with what `on_changed` writes.
+fn toggle(world: &mut World, checked: &mut Signal<World, bool>, state: &mut CheckBoxState) {
+    let history = world.get_resource::<History<World>>().cloned();
+    let mut toggle = |world: &mut World| {
+        checked.update_value(world, |checked| *checked = !*checked);
+        let checked = world.fgr_untrack(|world| *checked.value(world));
+        if let Some(on_changed) = &mut state.on_changed {
+            on_changed(world, checked);
+        }
+    };
+    match history {
+        Some(mut history) => history.transaction(world, "Toggle", toggle),
+        None => toggle(world),
     }
 }
@@ -0,0 +1,413 @@
+use std::{collections::{BTreeMap, HashMap}, fmt, sync::{Arc, Mutex}};
+
+use bevy::{
+    app::{App, Plugin, Update},
+    asset::{Asset, AssetApp, AssetEvent, AssetId, Assets, Handle},
+    ecs::event::{Events, ManualEventReader},
+    log::warn,
+    prelude::{BuildWorldChildren, DespawnRecursiveExt, Entity, Local, Mut, NodeBundle, Resource, World},
+    reflect::{serde::TypedReflectDeserializer, DynamicStruct, Reflect, ReflectRef, Struct, StructInfo, TypeInfo, TypePath, TypeRegistry},
+};
+use serde::{de::{self, DeserializeSeed, Deserializer, IgnoredAny, MapAccess, SeqAccess, Visitor}, Deserialize};
+
+use crate::fgr::{BoxedAccessor, FgrExtensionMethods, FgrPlugin, RootScope, Signal};
+
+use super::{component, registry::RegistryState, ron_asset::RonAssetLoader, MaybeReactive, Slot, UiComponentRegistry};
+
+/// A tree of registered components loaded from a `.ui.ron` file, rendered with `Document`:
+///
+/// ```ron
+/// (
+///     root: (
+///         component: "Column",
+///         props: (gap: Some(Px(8.0))),
+///         children: [
+///             (component: "Text", bind: { "text": "title" }),
+///             (component: "TextBox", props: (style: (width: Some(Percent(50.0))))),
+///             (component: "CheckBox", bind: { "style.width": "check_box_width" }),
+///         ],
+///     ),
+/// )
+/// ```
+///
+/// The `props` of a node are read as the props type of its component in the
+/// `UiComponentRegistry`, so `component` has to come first. Fields left out keep their defaults.
+#[derive(Asset, TypePath, Clone)]
+pub struct UiDocument {
+    pub root: UiNode,
+}
+
+/// One component of a `UiDocument`.
+#[derive(Default)]
+pub struct UiNode {
+    /// Name the component is registered under in the `UiComponentRegistry`.
+    pub component: String,
+    /// The fields of the component's props that are set, `None` if there are none.
+    pub props: Option<Box<dyn Reflect>>,
+    /// Props that follow a named signal of `UiSignals`, by prop name. Fields of nested props
+    /// are named by their path, such as `style.width`.
+    pub bind: BTreeMap<String, String>,
+    pub children: Vec<UiNode>,
+}
+
+impl Clone for UiNode {
+    fn clone(&self) -> Self {
+        Self {
+            component: self.component.clone(),
+            props: self.props.as_ref().map(|props| props.clone_value()),
+            bind: self.bind.clone(),
+            children: self.children.clone(),
+        }
+    }
+}
+
+impl UiDocument {
+    /// Parses a document in RON, the props of every node as the props type `registry` has for
+    /// its component. Nodes of components that are not registered keep no props.
+    pub fn from_ron(registry: &UiComponentRegistry, ron: &[u8]) -> Result<UiDocument, ron::error::SpannedError> {
+        let state = registry.read();
+        let mut deserializer = ron::Deserializer::from_bytes(ron)?;
+        let document = DocumentSeed(&state).deserialize(&mut deserializer).map_err(|err| deserializer.span_error(err))?;
+        deserializer.end().map_err(|err| deserializer.span_error(err))?;
+        Ok(document)
+    }
+
+    /// Builds the document in the current scope. Nodes with an unknown component are skipped
+    /// with a warning, as are bindings to unknown signals or props.
+    pub fn build(&self, world: &mut World, registry: &UiComponentRegistry) -> Option<Entity> {
+        build_node(world, registry, &self.root)
+    }
+}
+
+fn build_node(world: &mut World, registry: &UiComponentRegistry, node: &UiNode) -> Option<Entity> {
+    let Some(props_info) = registry.props_info(&node.component) else {
+        warn!("skipping unknown component {:?}", node.component);
+        return None;
+    };
+    let signals = world.get_resource::<UiSignals>();
+    let mut bindings = Vec::new();
+    for (prop, signal) in &node.bind {
+        if let TypeInfo::Struct(info) = props_info {
+            if !has_prop(&registry.read().types, info, prop) {
+                warn!("not binding {:?}, {:?} has no such prop", prop, node.component);
+                continue;
+            }
+        }
+        match signals.and_then(|signals| signals.signals.get(signal)) {
+            Some(accessor) => bindings.push((prop.clone(), accessor.clone())),
+            None => warn!("not binding {:?} of {:?}, there is no signal {:?}", prop, node.component, signal),
+        }
+    }
+    let fields = match node.props.as_deref().map(Reflect::reflect_ref) {
+        Some(ReflectRef::Struct(props)) => props.clone_dynamic(),
+        _ => DynamicStruct::default(),
+    };
+    let props = match bindings.is_empty() {
+        true => MaybeReactive::from(Box::new(fields) as Box<dyn Reflect>),
+        false => MaybeReactive::derived(move |world| {
+            let mut fields = fields.clone_dynamic();
+            for (prop, signal) in &bindings {
+                insert_prop(&mut fields, prop, signal.value(world).clone_value());
+            }
+            Box::new(fields) as Box<dyn Reflect>
+        }),
+    };
+    let children = node.children.clone();
+    let children_registry = registry.clone();
+    let children = Slot::new(move |world: &mut World| {
+        children.iter().filter_map(|child| build_node(world, &children_registry, child)).collect()
+    });
+    registry.construct_reactive(world, &node.component, props, children).ok()
+}
+
+/// Whether the props of `info` have the field at `path`, which is dotted for fields of nested
+/// structs such as `style.width`.
+fn has_prop(types: &TypeRegistry, info: &StructInfo, path: &str) -> bool {
+    let (name, rest) = match path.split_once('.') {
+        Some((name, rest)) => (name, Some(rest)),
+        None => (path, None),
+    };
+    let Some(field) = info.field(name) else { return false; };
+    match (rest, types.get_type_info(field.type_id())) {
+        (None, _) => true,
+        (Some(rest), Some(TypeInfo::Struct(info))) => has_prop(types, info, rest),
+        (Some(_), _) => false,
+    }
+}
+
+/// Sets the field at the dotted `path` of `fields`, creating the nested structs on the way.
+fn insert_prop(fields: &mut DynamicStruct, path: &str, value: Box<dyn Reflect>) {
+    let Some((name, rest)) = path.split_once('.') else {
+        fields.insert_boxed(path, value);
+        return;
+    };
+    let mut nested = match fields.field(name).map(Reflect::reflect_ref) {
+        Some(ReflectRef::Struct(nested)) => nested.clone_dynamic(),
+        _ => DynamicStruct::default(),
+    };
+    insert_prop(&mut nested, rest, value);
+    fields.insert(name, nested);
+}
+
+const DOCUMENT_FIELDS: &[&str] = &["root"];
+const NODE_FIELDS: &[&str] = &["component", "props", "bind", "children"];
+
+#[derive(Deserialize)]
+#[serde(field_identifier, rename_all = "lowercase")]
+enum DocumentField {
+    Root,
+}
+
+#[derive(Deserialize)]
+#[serde(field_identifier, rename_all = "lowercase")]
+enum NodeField {
+    Component,
+    Props,
+    Bind,
+    Children,
+}
+
+struct DocumentSeed<'a>(&'a RegistryState);
+
+impl<'de> DeserializeSeed<'de> for DocumentSeed<'_> {
+    type Value = UiDocument;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<UiDocument, D::Error> {
+        deserializer.deserialize_struct("UiDocument", DOCUMENT_FIELDS, self)
+    }
+}
+
+impl<'de> Visitor<'de> for DocumentSeed<'_> {
+    type Value = UiDocument;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a ui document")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<UiDocument, A::Error> {
+        let mut root = None;
+        while let Some(DocumentField::Root) = map.next_key()? {
+            root = Some(map.next_value_seed(NodeSeed(self.0))?);
+        }
+        let root = root.ok_or_else(|| de::Error::missing_field("root"))?;
+        Ok(UiDocument { root })
+    }
+}
+
+struct NodeSeed<'a>(&'a RegistryState);
+
+impl<'de> DeserializeSeed<'de> for NodeSeed<'_> {
+    type Value = UiNode;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<UiNode, D::Error> {
+        deserializer.deserialize_struct("UiNode", NODE_FIELDS, self)
+    }
+}
+
+impl<'de> Visitor<'de> for NodeSeed<'_> {
+    type Value = UiNode;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a ui node")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<UiNode, A::Error> {
+        let mut node = UiNode::default();
+        let mut component = None;
+        while let Some(key) = map.next_key()? {
+            match key {
+                NodeField::Component => component = Some(map.next_value::<String>()?),
+                NodeField::Props => {
+                    let Some(component) = &component else {
+                        return Err(de::Error::custom("`component` has to come before `props`"));
+                    };
+                    match self.0.props_registration(component) {
+                        Some(registration) => node.props = Some(map.next_value_seed(TypedReflectDeserializer::new(registration, &self.0.types))?),
+                        None => {
+                            map.next_value::<IgnoredAny>()?;
+                        }
+                    }
+                }
+                NodeField::Bind => node.bind = map.next_value()?,
+                NodeField::Children => node.children = map.next_value_seed(ChildrenSeed(self.0))?,
+            }
+        }
+        node.component = component.ok_or_else(|| de::Error::missing_field("component"))?;
+        Ok(node)
+    }
+}
+
+struct ChildrenSeed<'a>(&'a RegistryState);
+
+impl<'de> DeserializeSeed<'de> for ChildrenSeed<'_> {
+    type Value = Vec<UiNode>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Vec<UiNode>, D::Error> {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de> Visitor<'de> for ChildrenSeed<'_> {
+    type Value = Vec<UiNode>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a list of ui nodes")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Vec<UiNode>, A::Error> {
+        let mut children = Vec::new();
+        while let Some(child) = seq.next_element_seed(NodeSeed(self.0))? {
+            children.push(child);
+        }
+        Ok(children)
+    }
+}
+
+/// The signals documents can bind props to, by name. They are owned by the app rather than the
+/// document, so their state survives rebuilding the document.
+#[derive(Resource, Default)]
+pub struct UiSignals {
+    signals: HashMap<String, BoxedAccessor<World, Box<dyn Reflect>>>,
+}
+
+impl UiSignals {
+    /// Makes `signal` available to bindings as `name`. Props bound to it have to be of type `T`.
+    pub fn insert<T: Reflect>(&mut self, name: impl Into<String>, signal: impl Into<BoxedAccessor<World, T>>) {
+        let signal = signal.into();
+        self.signals.insert(name.into(), BoxedAccessor::derived(move |world| signal.value(world).clone_value()));
+    }
+}
+
+/// The loaded state of every document that is rendered, updated as the assets change.
+#[derive(Resource, Default)]
+pub struct UiDocuments {
+    documents: HashMap<AssetId<UiDocument>, RenderedDocument>,
+}
+
+struct RenderedDocument {
+    document: Signal<World, Option<Arc<UiDocument>>>,
+    users: usize,
+}
+
+impl UiDocuments {
+    /// The state of the document `id`, shared by everything rendering it. Every call has to be
+    /// paired with a `release` once the signal is no longer used.
+    pub fn document(&mut self, world: &mut World, id: AssetId<UiDocument>) -> Signal<World, Option<Arc<UiDocument>>> {
+        let rendered = self.documents.entry(id).or_insert_with(|| {
+            let document = world.get_resource::<Assets<UiDocument>>().and_then(|documents| documents.get(id)).cloned();
+            RenderedDocument { document: Signal::new(world, document.map(Arc::new)), users: 0 }
+        });
+        rendered.users += 1;
+        rendered.document.clone()
+    }
+
+    /// Stops tracking the document `id` once the last of its users releases it.
+    pub fn release(&mut self, id: AssetId<UiDocument>) {
+        let Some(rendered) = self.documents.get_mut(&id) else { return; };
+        rendered.users -= 1;
+        if rendered.users == 0 {
+            self.documents.remove(&id);
+        }
+    }
+
+    pub fn is_rendered(&self, id: AssetId<UiDocument>) -> bool {
+        self.documents.contains_key(&id)
+    }
+}
+
+fn update_ui_documents(world: &mut World, mut reader: Local<ManualEventReader<AssetEvent<UiDocument>>>) {
+    let events = world.resource::<Events<AssetEvent<UiDocument>>>();
+    let changed: Vec<AssetId<UiDocument>> = reader
+        .read(events)
+        .filter_map(|event| match event {
+            AssetEvent::Added { id } | AssetEvent::Modified { id } | AssetEvent::Removed { id } => Some(*id),
+            _ => None,
+        })
+        .collect();
+    for id in changed {
+        let Some(signal) = world.resource::<UiDocuments>().documents.get(&id).map(|rendered| rendered.document.clone()) else { continue; };
+        let document = world.resource::<Assets<UiDocument>>().get(id).cloned().map(Arc::new);
+        signal.clone().update_value(world, |x| *x = document);
+    }
+}
+
+struct Built {
+    entity: Option<Entity>,
+    scope: RootScope<World>,
+}
+
+fn dispose(world: &mut World, built: &Mutex<Option<Built>>) {
+    let Some(mut built) = built.lock().unwrap().take() else { return; };
+    built.scope.dispose(world);
+    if let Some(entity) = built.entity.and_then(|entity| world.get_entity_mut(entity)) {
+        entity.despawn_recursive();
+    }
+}
+
+/// Renders the `UiDocument` `source`. Every time the asset is loaded or changes, the document is
+/// rebuilt in a new scope, the old one disposed.
+///
+/// Rebuilding starts every component of the document over, so state the components keep
+/// themselves, such as the contents of a `TextBox` or whether a `CheckBox` is checked, is lost
+/// on each reload. State that should survive a reload belongs in a `UiSignals` signal the prop
+/// is bound to.
+#[component]
+pub fn document(world: &mut World, #[prop(optional)] source: Handle<UiDocument>) -> Entity {
+    let source_id = source.id();
+    let tracked = world.contains_resource::<UiDocuments>();
+    let document = match tracked {
+        true => world.resource_scope(|world, mut documents: Mut<UiDocuments>| documents.document(world, source_id)),
+        false => Signal::new(world, None),
+    };
+    // the handle keeps the document loaded for as long as it is rendered
+    let document_id = world.spawn((NodeBundle::default(), source)).id();
+    let built = Arc::new(Mutex::new(None));
+    world.fgr_create_effect_on(document, {
+        let built = Arc::clone(&built);
+        move |world, document, _| {
+            dispose(world, &built);
+            let Some(document) = document.clone() else { return; };
+            let registry = world.get_resource::<UiComponentRegistry>().cloned().unwrap_or_default();
+            let (entity, scope) = world.fgr_create_root(|world, scope| (document.build(world, &registry), scope));
+            if let Some(entity) = entity {
+                world.entity_mut(document_id).add_child(entity);
+            }
+            *built.lock().unwrap() = Some(Built { entity, scope });
+        }
+    });
+    world.fgr_on_cleanup(move |world| {
+        dispose(world, &built);
+        if let Some(entity) = world.get_entity_mut(document_id) {
+            entity.despawn_recursive();
+        }
+        if let Some(mut documents) = world.get_resource_mut::<UiDocuments>().filter(|_| tracked) {
+            documents.release(source_id);
+        }
+    });
+    document_id
+}
+
+/// Loads `.ui.ron` documents, against the `UiComponentRegistry` resource or, without one, a
+/// registry of the components of `ui`. With the `dev` feature (on by default), edited documents
+/// are rebuilt as soon as they are saved.
+#[derive(Default)]
+pub struct UiDocumentPlugin;
+
+impl Plugin for UiDocumentPlugin {
+    fn build(&self, app: &mut App) {
+        FgrPlugin::ensure(app);
+        let registry = match app.world().get_resource::<UiComponentRegistry>() {
+            Some(registry) => registry.clone(),
+            None => UiComponentRegistry::with_builtins(),
+        };
+        app.init_asset::<UiDocument>()
+            .register_asset_loader(RonAssetLoader::new(&["ui.ron"], {
+                let registry = registry.clone();
+                move |bytes| UiDocument::from_ron(&registry, bytes)
+            }))
+            .insert_resource(registry)
+            .init_resource::<UiSignals>()
+            .init_resource::<UiDocuments>()
+            .add_systems(Update, update_ui_documents);
+    }
+}
@@ -0,0 +1,315 @@
+use std::{collections::HashSet, sync::{Arc, RwLock}};
+
+use bevy::{
+    app::{App, Plugin, Update},
+    color::Color,
+    ecs::{component::Component, event::{Events, ManualEventReader}, query::{With, Without}},
+    hierarchy::{Children, Parent},
+    input::{keyboard::{KeyCode, KeyboardInput}, mouse::MouseButton, ButtonInput},
+    prelude::{Entity, Local, Resource, World},
+    ui::{Node, Outline, Val},
+};
+
+use crate::fgr::{Accessor, AccessorExt, BoxedAccessor, EventStream, FgrExtensionMethods, FgrLabelExtensionMethods, FgrPlugin, Signal};
+
+use super::{bubbling::{get_or_insert_resource, BubblingEvent, BubblingEvents, Propagation}, bind_style, theme_token, MaybeReactive, PointerEventKind, PointerManager, StyleProps};
+
+/// Marks an entity that can take keyboard focus, see `use_focus`. Tab moves through focusable
+/// entities by ascending `tab_index`, those with the same index in hierarchy order.
+#[derive(Component, Clone, Copy, Default, Debug, PartialEq, Eq)]
+pub struct Focusable {
+    pub tab_index: i32,
+}
+
+/// A key press or release routed by the `FocusManager`. The handlers of the focused entity see it
+/// first, then those of its ancestors, until one of them calls `stop_propagation`.
+#[derive(Clone, Debug)]
+pub struct KeyEvent {
+    pub input: KeyboardInput,
+    /// The focused entity the event was routed to.
+    pub target: Entity,
+    propagation: Propagation,
+}
+
+impl KeyEvent {
+    /// Keeps the event from bubbling further up than the entity being handled.
+    pub fn stop_propagation(&self) {
+        self.propagation.stop();
+    }
+
+    pub fn is_propagation_stopped(&self) -> bool {
+        self.propagation.is_stopped()
+    }
+}
+
+impl BubblingEvent for KeyEvent {
+    fn propagation(&self) -> &Propagation {
+        &self.propagation
+    }
+}
+
+/// Which entity has keyboard focus, as a resource. Keyboard input only goes to the focused
+/// entity and its ancestors, see `key_events`.
+///
+/// Each graph label has a manager of its own (see `FgrLabelExtensionMethods::fgr_resource`),
+/// for the entities made focusable in that graph. Presses blur the managers of the other graphs,
+/// so only one has a focused entity and gets the keyboard.
+#[derive(Resource, Clone)]
+pub struct FocusManager {
+    focused: Signal<World, Option<Entity>>,
+    focus_visible: Signal<World, bool>,
+    key_events: BubblingEvents<KeyEvent>,
+    /// The entities made focusable with this manager, which Tab moves through.
+    focusable: Arc<RwLock<HashSet<Entity>>>,
+}
+
+impl FocusManager {
+    pub fn new(world: &mut World) -> Self {
+        Self {
+            focused: Signal::new(world, None),
+            focus_visible: Signal::new(world, false),
+            key_events: BubblingEvents::default(),
+            focusable: Arc::default(),
+        }
+    }
+
+    /// The `FocusManager` of the active graph, inserted if there is none yet.
+    pub fn get(world: &mut World) -> Self {
+        get_or_insert_resource(world, FocusManager::new)
+    }
+
+    pub fn focused(&self) -> Signal<World, Option<Entity>> {
+        self.focused.clone()
+    }
+
+    /// Whether the focus should be shown, which it is when it was last moved with the keyboard.
+    pub fn focus_visible(&self) -> Signal<World, bool> {
+        self.focus_visible.clone()
+    }
+
+    pub fn is_focused(&self, world: &mut World, entity: Entity) -> BoxedAccessor<World, bool> {
+        self.focused.map(world, move |focused| *focused == Some(entity))
+    }
+
+    /// Focuses `entity` as a click would, without showing the focus.
+    pub fn focus(&self, world: &mut World, entity: Entity) {
+        self.set_focus(world, Some(entity), false);
+    }
+
+    pub fn blur(&self, world: &mut World) {
+        self.set_focus(world, None, false);
+    }
+
+    /// Moves the focus to the next focusable entity in tab order, wrapping around, as Tab does.
+    pub fn focus_next(&self, world: &mut World) {
+        self.step_focus(world, true);
+    }
+
+    /// Moves the focus to the previous focusable entity in tab order, as Shift+Tab does.
+    pub fn focus_previous(&self, world: &mut World) {
+        self.step_focus(world, false);
+    }
+
+    /// The key events bubbling through `entity`: those sent while it or one of its descendants
+    /// is focused. Handlers are removed with the scope they are registered in.
+    pub fn key_events(&self, entity: Entity) -> EventStream<World, KeyEvent> {
+        self.key_events.events(entity)
+    }
+
+    /// Routes `input` to the focused entity and then its ancestors, as `FocusPlugin` does for
+    /// every `KeyboardInput` other than Tab. Without a focused entity it goes nowhere.
+    pub fn dispatch(&self, world: &mut World, input: &KeyboardInput) {
+        let Some(target) = world.fgr_untrack(|world| *self.focused.value(world)) else { return; };
+        let event = KeyEvent {
+            input: input.clone(),
+            target,
+            propagation: Propagation::default(),
+        };
+        self.key_events.dispatch(world, target, &event);
+    }
+
+    fn set_focus(&self, world: &mut World, entity: Option<Entity>, visible: bool) {
+        world.fgr_batch(|world| {
+            self.focused.clone().update_value(world, |x| *x = entity);
+            self.focus_visible.clone().update_value(world, |x| *x = visible);
+        });
+    }
+
+    fn step_focus(&self, world: &mut World, forward: bool) {
+        let order = tab_order(world, &self.focusable.read().unwrap());
+        if order.is_empty() {
+            return;
+        }
+        let focused = world.fgr_untrack(|world| *self.focused.value(world));
+        let position = focused.and_then(|focused| order.iter().position(|entity| *entity == focused));
+        let next = match (position, forward) {
+            (Some(position), true) => (position + 1) % order.len(),
+            (Some(position), false) => (position + order.len() - 1) % order.len(),
+            (None, true) => 0,
+            (None, false) => order.len() - 1,
+        };
+        self.set_focus(world, Some(order[next]), true);
+    }
+}
+
+/// The entities of `focusable` by `tab_index`, then depth first through the UI hierarchy.
+fn tab_order(world: &mut World, focusable: &HashSet<Entity>) -> Vec<Entity> {
+    let mut roots: Vec<Entity> = world.query_filtered::<Entity, (With<Node>, Without<Parent>)>().iter(world).collect();
+    roots.sort();
+    let mut stack: Vec<Entity> = roots.into_iter().rev().collect();
+    let mut order = Vec::new();
+    while let Some(entity) = stack.pop() {
+        if let Some(focusable) = world.get::<Focusable>(entity).filter(|_| focusable.contains(&entity)) {
+            order.push((focusable.tab_index, entity));
+        }
+        if let Some(children) = world.get::<Children>(entity) {
+            stack.extend(children.iter().rev());
+        }
+    }
+    order.sort_by_key(|(tab_index, _)| *tab_index);
+    order.into_iter().map(|(_, entity)| entity).collect()
+}
+
+/// Makes `entity` focusable until the current scope is disposed. Pressing it focuses it, and
+/// while it has visible focus it is outlined in the theme's accent color.
+pub fn use_focus(world: &mut World, entity: Entity, tab_index: i32) -> Focus {
+    let manager = FocusManager::get(world);
+    world.entity_mut(entity).insert((Focusable { tab_index }, Outline::new(Val::Px(2.0), Val::Px(1.0), Color::NONE)));
+    manager.focusable.write().unwrap().insert(entity);
+    let focus = Focus { entity, manager };
+    // a press focuses the nearest focusable entity it bubbles through
+    let presses = PointerManager::get(world)
+        .pointer_events(entity)
+        .filter(world, |event| event.kind == PointerEventKind::Down && event.button == Some(MouseButton::Left));
+    presses.on_event(world, {
+        let focus = focus.clone();
+        move |world, event| {
+            if focusable_ancestor(world, event.target) == Some(entity) {
+                focus.focus(world);
+            }
+        }
+    });
+    let is_focused = focus.is_focused(world);
+    let focus_visible = focus.manager.focus_visible();
+    let accent = theme_token(world, |theme| theme.accent);
+    let outline_color = MaybeReactive::derived(move |world| {
+        match *is_focused.value(world) && *focus_visible.value(world) {
+            true => *accent.value(world),
+            false => Color::NONE,
+        }
+    });
+    bind_style(world, entity, StyleProps::new().outline_color(outline_color));
+    world.fgr_on_cleanup({
+        let focus = focus.clone();
+        move |world| {
+            if world.fgr_untrack(|world| *focus.manager.focused.value(world)) == Some(entity) {
+                focus.manager.blur(world);
+            }
+            focus.manager.focusable.write().unwrap().remove(&entity);
+            if let Some(mut entity) = world.get_entity_mut(entity) {
+                entity.remove::<Focusable>();
+            }
+        }
+    });
+    focus
+}
+
+/// `entity` if it is focusable, otherwise its nearest focusable ancestor.
+fn focusable_ancestor(world: &World, entity: Entity) -> Option<Entity> {
+    let mut entity = Some(entity);
+    while let Some(current) = entity {
+        if world.get::<Focusable>(current).is_some() {
+            return Some(current);
+        }
+        entity = world.get::<Parent>(current).map(Parent::get);
+    }
+    None
+}
+
+/// The focus of one entity, returned by `use_focus`.
+#[derive(Clone)]
+pub struct Focus {
+    entity: Entity,
+    manager: FocusManager,
+}
+
+impl Focus {
+    pub fn entity(&self) -> Entity {
+        self.entity
+    }
+
+    pub fn is_focused(&self, world: &mut World) -> BoxedAccessor<World, bool> {
+        self.manager.is_focused(world, self.entity)
+    }
+
+    pub fn focus(&self, world: &mut World) {
+        self.manager.focus(world, self.entity);
+    }
+
+    /// See `FocusManager::key_events`.
+    pub fn key_events(&self) -> EventStream<World, KeyEvent> {
+        self.manager.key_events(self.entity)
+    }
+}
+
+/// Inserts the `FocusManager` and drives it: left presses reported by the `PointerManager` focus
+/// the focusable entity pressed (or blur when pressing elsewhere), Tab and Shift+Tab move the
+/// focus, and all other keyboard input is routed to the focused entity.
+#[derive(Default)]
+pub struct FocusPlugin;
+
+impl Plugin for FocusPlugin {
+    fn build(&self, app: &mut App) {
+        FgrPlugin::ensure(app);
+        let manager = FocusManager::new(app.world_mut());
+        app.insert_resource(manager)
+            .add_systems(Update, update_focus);
+    }
+}
+
+fn update_focus(world: &mut World, mut reader: Local<ManualEventReader<KeyboardInput>>) {
+    // presses on focusable entities focus them, see `use_focus`, those elsewhere blur
+    let clicked = world.get_resource::<ButtonInput<MouseButton>>().is_some_and(|buttons| buttons.just_pressed(MouseButton::Left));
+    let hovered = world
+        .fgr_resources::<PointerManager>()
+        .into_iter()
+        .find_map(|(label, pointer)| world.fgr_in_label(label, |world| pointer.hovered_entity(world)));
+    let pressed = hovered.and_then(|entity| focusable_ancestor(world, entity));
+    let mut keyboard = None;
+    for (label, manager) in world.fgr_resources::<FocusManager>() {
+        world.fgr_in_label(label, |world| {
+            manager.key_events.prune(world);
+            let focused = world.fgr_untrack(|world| *manager.focused.value(world));
+            if focused.is_some_and(|focused| world.get_entity(focused).is_none() || (clicked && Some(focused) != pressed)) {
+                manager.blur(world);
+            } else if focused.is_some() && keyboard.is_none() {
+                keyboard = Some((label, manager.clone()));
+            }
+        });
+    }
+    // the keyboard goes to the graph with a focused entity, Tab starts in the unlabelled one
+    let (label, manager) = match keyboard {
+        Some(keyboard) => keyboard,
+        None => (world.fgr_active_label(), FocusManager::get(world)),
+    };
+    let Some(events) = world.get_resource::<Events<KeyboardInput>>() else { return; };
+    let inputs: Vec<KeyboardInput> = reader.read(events).cloned().collect();
+    world.fgr_in_label(label, |world| {
+        for input in &inputs {
+            if input.key_code != KeyCode::Tab {
+                manager.dispatch(world, input);
+                continue;
+            }
+            if !input.state.is_pressed() {
+                continue;
+            }
+            let shift = world
+                .get_resource::<ButtonInput<KeyCode>>()
+                .is_some_and(|keys| keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]));
+            match shift {
+                true => manager.focus_previous(world),
+                false => manager.focus_next(world),
+            }
+        }
+    });
+}
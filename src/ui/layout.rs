@@ -0,0 +1,270 @@
+use std::collections::HashMap;
+
+use bevy::{
+    app::{App, Plugin, PostUpdate},
+    prelude::{Added, Changed, Children, Component, DespawnRecursiveExt, Entity, IntoSystemConfigs, NodeBundle, Or, Parent, With, World},
+    ui::{AlignItems, Display, FlexDirection, FlexWrap, GridPlacement, JustifyContent, RepeatedGridTrack, Style, UiSystem, Val},
+};
+
+use crate::fgr::{Accessor, FgrExtensionMethods, FgrPlugin};
+
+use super::{bind_pointer, bind_style, component, MaybeReactive, PointerProps, Slot, StyleProps};
+
+/// Lays out the children left to right, as a flex row.
+#[component(reflect)]
+pub fn row(
+    world: &mut World,
+    #[prop(default = Val::ZERO.into())] gap: MaybeReactive<Val>,
+    #[prop(optional)] align: MaybeReactive<AlignItems>,
+    #[prop(optional)] justify: MaybeReactive<JustifyContent>,
+    #[prop(optional)] wrap: MaybeReactive<FlexWrap>,
+    #[prop(optional)] mut children: Slot,
+    #[prop(optional)] style: StyleProps,
+    #[prop(optional, events)] pointer: PointerProps,
+) -> Entity {
+    let row_id = layout_node(world, style.or(
+        StyleProps::new()
+            .flex_direction(FlexDirection::Row)
+            .gap(gap)
+            .align_items(align)
+            .justify_content(justify)
+            .flex_wrap(wrap)
+    ), pointer);
+    Row::mount_children(world, row_id, &mut children);
+    row_id
+}
+
+/// Lays out the children top to bottom, as a flex column.
+#[component(reflect)]
+pub fn column(
+    world: &mut World,
+    #[prop(default = Val::ZERO.into())] gap: MaybeReactive<Val>,
+    #[prop(optional)] align: MaybeReactive<AlignItems>,
+    #[prop(optional)] justify: MaybeReactive<JustifyContent>,
+    #[prop(optional)] wrap: MaybeReactive<FlexWrap>,
+    #[prop(optional)] mut children: Slot,
+    #[prop(optional)] style: StyleProps,
+    #[prop(optional, events)] pointer: PointerProps,
+) -> Entity {
+    let column_id = layout_node(world, style.or(
+        StyleProps::new()
+            .flex_direction(FlexDirection::Column)
+            .gap(gap)
+            .align_items(align)
+            .justify_content(justify)
+            .flex_wrap(wrap)
+    ), pointer);
+    Column::mount_children(world, column_id, &mut children);
+    column_id
+}
+
+/// Places the children on top of each other, later children in front, all in the single cell
+/// of a grid. Children added later on, e.g. by a `Show`, are placed by `LayoutPlugin`.
+#[component(reflect)]
+pub fn stack(
+    world: &mut World,
+    #[prop(optional)] mut children: Slot,
+    #[prop(optional)] style: StyleProps,
+    #[prop(optional, events)] pointer: PointerProps,
+) -> Entity {
+    let stack_id = layout_node(world, style.or(
+        StyleProps::new()
+            .display(Display::Grid)
+            .grid_template_columns(vec![RepeatedGridTrack::flex(1, 1.0)])
+            .grid_template_rows(vec![RepeatedGridTrack::flex(1, 1.0)])
+    ), pointer);
+    world.entity_mut(stack_id).insert(ChildPlacement::Stack);
+    Stack::mount_children(world, stack_id, &mut children);
+    place_children(world, stack_id);
+    stack_id
+}
+
+/// Takes up the free space along its parent's main axis, pushing its siblings apart. Spacers
+/// share the space in proportion to their `grow`.
+#[component(reflect)]
+pub fn spacer(
+    world: &mut World,
+    #[prop(default = 1.0.into())] grow: MaybeReactive<f32>,
+    #[prop(optional)] style: StyleProps,
+    #[prop(optional, events)] pointer: PointerProps,
+) -> Entity {
+    layout_node(world, style.or(StyleProps::new().flex_grow(grow)), pointer)
+}
+
+/// Marks a child of a `Grid` as covering one of the grid's named `areas`.
+#[derive(Component, Clone, Debug, PartialEq)]
+pub struct GridArea(pub String);
+
+/// A CSS grid. `areas` names the cells row by row, in the form of `grid-template-areas`:
+///
+/// ```text
+/// areas={vec!["header header".to_string(), "sidebar main".to_string()]}
+/// ```
+///
+/// Children with a `GridArea`, like a `GridItem`, are placed on the rectangle spanning every
+/// cell of their area, `.` leaves a cell unnamed. A child without a `GridArea` of its own, such
+/// as the node of a `Show` or `List`, takes the area of the first `GridArea` inside it. Children
+/// added later on are placed by `LayoutPlugin`. Without `columns` the grid gets a column of equal
+/// width per column of `areas`.
+#[component(reflect)]
+pub fn grid(
+    world: &mut World,
+    #[prop(optional)] columns: MaybeReactive<Vec<RepeatedGridTrack>>,
+    #[prop(optional)] rows: MaybeReactive<Vec<RepeatedGridTrack>>,
+    #[prop(optional)] areas: MaybeReactive<Vec<String>>,
+    #[prop(default = Val::ZERO.into())] gap: MaybeReactive<Val>,
+    #[prop(optional)] align: MaybeReactive<AlignItems>,
+    #[prop(optional)] justify: MaybeReactive<JustifyContent>,
+    #[prop(optional)] mut children: Slot,
+    #[prop(optional)] style: StyleProps,
+    #[prop(optional, events)] pointer: PointerProps,
+) -> Entity {
+    let template_columns = MaybeReactive::derived({
+        let areas = areas.clone();
+        move |world| {
+            let columns = columns.value(world).clone();
+            let area_columns = areas.value(world).iter().map(|row| row.split_whitespace().count()).max().unwrap_or(0);
+            if columns.is_empty() && area_columns > 0 {
+                return vec![RepeatedGridTrack::flex(area_columns as u16, 1.0)];
+            }
+            columns
+        }
+    });
+    let grid_id = layout_node(world, style.or(
+        StyleProps::new()
+            .display(Display::Grid)
+            .grid_template_columns(template_columns)
+            .grid_template_rows(rows)
+            .gap(gap)
+            .align_items(align)
+            .justify_content(justify)
+    ), pointer);
+    Grid::mount_children(world, grid_id, &mut children);
+    world.fgr_create_effect_on(areas, move |world, areas, _| {
+        world.entity_mut(grid_id).insert(ChildPlacement::Areas(area_placements(areas)));
+        place_children(world, grid_id);
+    });
+    grid_id
+}
+
+/// A node covering the named `area` of the `Grid` it is a child of.
+#[component(reflect)]
+pub fn grid_item(
+    world: &mut World,
+    #[prop(optional)] area: String,
+    #[prop(optional)] mut children: Slot,
+    #[prop(optional)] style: StyleProps,
+    #[prop(optional, events)] pointer: PointerProps,
+) -> Entity {
+    let item_id = layout_node(world, style, pointer);
+    world.entity_mut(item_id).insert(GridArea(area));
+    GridItem::mount_children(world, item_id, &mut children);
+    item_id
+}
+
+/// How a `Stack` or `Grid` places its children, kept on its entity for `LayoutPlugin`.
+#[derive(Component)]
+enum ChildPlacement {
+    Stack,
+    Areas(HashMap<String, (GridPlacement, GridPlacement)>),
+}
+
+/// Places the current children of `parent` as its `ChildPlacement` says.
+fn place_children(world: &mut World, parent: Entity) {
+    let Some(children) = world.get::<Children>(parent).map(|children| children.to_vec()) else { return; };
+    for child in children {
+        let placement = match world.get::<ChildPlacement>(parent) {
+            Some(ChildPlacement::Stack) => (GridPlacement::start(1), GridPlacement::start(1)),
+            Some(ChildPlacement::Areas(placements)) => {
+                let Some(GridArea(area)) = find_grid_area(world, child) else { continue; };
+                placements.get(area).copied().unwrap_or_default()
+            }
+            None => return,
+        };
+        // only written when it differs, so the layout is not redone every frame
+        if world.get::<Style>(child).is_some_and(|style| (style.grid_row, style.grid_column) != placement) {
+            let mut style = world.get_mut::<Style>(child).unwrap();
+            (style.grid_row, style.grid_column) = placement;
+        }
+    }
+}
+
+/// The `GridArea` of `entity`, or else the first one among its descendants.
+fn find_grid_area(world: &World, entity: Entity) -> Option<&GridArea> {
+    if let Some(area) = world.get::<GridArea>(entity) {
+        return Some(area);
+    }
+    world.get::<Children>(entity)?.iter().find_map(|child| find_grid_area(world, *child))
+}
+
+/// Places the children that a `Stack` or `Grid` gets after it is built, e.g. those shown by a
+/// `Show` or rendered by a `List`, before the UI is laid out.
+#[derive(Default)]
+pub struct LayoutPlugin;
+
+impl Plugin for LayoutPlugin {
+    fn build(&self, app: &mut App) {
+        FgrPlugin::ensure(app);
+        app.add_systems(PostUpdate, update_child_placement.before(UiSystem::Layout));
+    }
+}
+
+fn update_child_placement(world: &mut World) {
+    let mut parents: Vec<Entity> = world
+        .query_filtered::<Entity, (With<ChildPlacement>, Changed<Children>)>()
+        .iter(world)
+        .collect();
+    // an area appearing anywhere below a grid, such as inside a `Show`, may place its child
+    let moved: Vec<Entity> = world
+        .query_filtered::<Entity, (With<GridArea>, Or<(Added<GridArea>, Changed<GridArea>, Changed<Parent>)>)>()
+        .iter(world)
+        .collect();
+    for mut entity in moved {
+        while let Some(parent) = world.get::<Parent>(entity).map(Parent::get) {
+            if world.get::<ChildPlacement>(parent).is_some() {
+                parents.push(parent);
+                break;
+            }
+            entity = parent;
+        }
+    }
+    parents.sort();
+    parents.dedup();
+    for parent in parents {
+        place_children(world, parent);
+    }
+}
+
+/// The row and column placement of every named area, covering the bounding box of its cells.
+fn area_placements(areas: &[String]) -> HashMap<String, (GridPlacement, GridPlacement)> {
+    let mut bounds: HashMap<String, (usize, usize, usize, usize)> = HashMap::new();
+    for (row, names) in areas.iter().enumerate() {
+        for (column, name) in names.split_whitespace().enumerate() {
+            if name == "." {
+                continue;
+            }
+            let bound = bounds.entry(name.to_string()).or_insert((row, row, column, column));
+            *bound = (bound.0.min(row), bound.1.max(row), bound.2.min(column), bound.3.max(column));
+        }
+    }
+    bounds
+        .into_iter()
+        .map(|(name, (first_row, last_row, first_column, last_column))| {
+            let row = GridPlacement::start_end(first_row as i16 + 1, last_row as i16 + 2);
+            let column = GridPlacement::start_end(first_column as i16 + 1, last_column as i16 + 2);
+            (name, (row, column))
+        })
+        .collect()
+}
+
+fn layout_node(world: &mut World, style: StyleProps, pointer: PointerProps) -> Entity {
+    let node_id = world.spawn(NodeBundle::default()).id();
+    bind_style(world, node_id, style);
+    bind_pointer(world, node_id, pointer);
+    world.fgr_on_cleanup(move |world| {
+        if let Some(entity) = world.get_entity_mut(node_id) {
+            entity.despawn_recursive();
+        }
+    });
+    node_id
+}
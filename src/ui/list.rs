@@ -0,0 +1,122 @@
+use std::sync::{Arc, RwLock};
+
+use bevy::{prelude::{default, BuildWorldChildren, DespawnRecursiveExt, Entity, NodeBundle, World}, ui::{FlexDirection, Style}};
+
+use crate::fgr::{FgrExtensionMethods, ReactiveVec, RootScope, VecPatch};
+
+use super::UiComponent;
+
+pub type RenderItem<T> = Box<dyn FnMut(&mut World, &T) -> Entity + Send + Sync>;
+
+pub struct ListProps<T> {
+    pub items: ReactiveVec<World, T>,
+    pub render_item: RenderItem<T>,
+}
+
+/// Renders a `ReactiveVec` as a column of children. Each item is rendered in its own root scope
+/// and the list's patches are applied to the children one by one, so unchanged items keep their
+/// entities and state.
+pub struct List;
+
+/// `<For each={reactive_vec} render={|world, item| ..} />` in `view!`, which renders a `List`.
+pub struct For;
+
+struct ListItem {
+    entity: Entity,
+    scope: RootScope<World>,
+}
+
+struct ListState<T> {
+    render_item: RenderItem<T>,
+    items: Vec<ListItem>,
+}
+
+impl<T> ListState<T> {
+    fn render_item(&mut self, world: &mut World, value: &T) -> ListItem {
+        let render_item = &mut self.render_item;
+        world.fgr_create_root(|world, scope| {
+            let entity = render_item(world, value);
+            ListItem { entity, scope }
+        })
+    }
+}
+
+fn dispose_item(world: &mut World, mut item: ListItem) {
+    item.scope.dispose(world);
+    if let Some(entity) = world.get_entity_mut(item.entity) {
+        entity.despawn_recursive();
+    }
+}
+
+impl<T: Clone + Send + Sync + 'static> UiComponent<ListProps<T>> for List {
+    fn run(world: &mut World, props: ListProps<T>) -> Entity {
+        let list_id = world
+            .spawn(NodeBundle {
+                style: Style {
+                    flex_direction: FlexDirection::Column,
+                    ..default()
+                },
+                ..default()
+            })
+            .id();
+        let state = Arc::new(RwLock::new(ListState {
+            render_item: props.render_item,
+            items: Vec::new(),
+        }));
+        {
+            let items = props.items.items();
+            let initial_items = world.fgr_untrack(|world| items.value(world).clone());
+            let mut state = state.write().unwrap();
+            for value in &initial_items {
+                let item = state.render_item(world, value);
+                state.items.push(item);
+            }
+            let children: Vec<Entity> = state.items.iter().map(|item| item.entity).collect();
+            world.entity_mut(list_id).push_children(&children);
+        }
+        let state2 = Arc::clone(&state);
+        world.fgr_create_effect_on_deferred(props.items.patches(), move |world, patches, _| {
+            let mut state = state2.write().unwrap();
+            for patch in patches {
+                match patch {
+                    VecPatch::Insert { index, value } => {
+                        let item = state.render_item(world, value);
+                        world.entity_mut(list_id).insert_children(*index, &[item.entity]);
+                        state.items.insert(*index, item);
+                    }
+                    VecPatch::Remove { index } => {
+                        let item = state.items.remove(*index);
+                        dispose_item(world, item);
+                    }
+                    VecPatch::Move { from, to } => {
+                        let item = state.items.remove(*from);
+                        world.entity_mut(list_id)
+                            .remove_children(&[item.entity])
+                            .insert_children(*to, &[item.entity]);
+                        state.items.insert(*to, item);
+                    }
+                    VecPatch::Update { index, value } => {
+                        let item = state.render_item(world, value);
+                        world.entity_mut(list_id).insert_children(*index, &[item.entity]);
+                        let old_item = std::mem::replace(&mut state.items[*index], item);
+                        dispose_item(world, old_item);
+                    }
+                    VecPatch::Clear => {
+                        for item in std::mem::take(&mut state.items) {
+                            dispose_item(world, item);
+                        }
+                    }
+                }
+            }
+        });
+        world.fgr_on_cleanup(move |world| {
+            for item in std::mem::take(&mut state.write().unwrap().items) {
+                dispose_item(world, item);
+            }
+            if let Some(entity) = world.get_entity_mut(list_id) {
+                entity.despawn_recursive();
+            }
+        });
+        list_id
+    }
+}
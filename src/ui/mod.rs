@@ -1,39 +1,164 @@
+mod bubbling;
 mod check_box;
+mod document;
+mod focus;
+mod slot;
+mod style;
+mod layout;
+mod list;
+mod panel;
+mod pointer;
+mod registry;
+mod ron_asset;
+mod show;
 mod text_box;
+mod theme;
 mod ui_component;
+mod view;
 
 use bevy::app::App;
-use bevy::app::Update;
 use bevy::prelude::Entity;
 use bevy::prelude::World;
+use bevy::ui::Val;
 pub use check_box::CheckBox;
 pub use check_box::CheckBoxProps;
+pub use check_box::CheckBoxPropsData;
+pub use document::Document;
+pub use document::DocumentProps;
+pub use document::UiDocument;
+pub use document::UiDocumentPlugin;
+pub use document::UiDocuments;
+pub use document::UiNode;
+pub use document::UiSignals;
+pub use focus::use_focus;
+pub use focus::Focus;
+pub use focus::FocusManager;
+pub use focus::FocusPlugin;
+pub use focus::Focusable;
+pub use focus::KeyEvent;
+pub use slot::Slot;
+pub use slot::ViewFn;
+pub use style::bind_style;
+pub use style::OptionalStyleProps;
+pub use style::StyleProps;
+pub use style::StylePropsData;
+pub use layout::Column;
+pub use layout::ColumnProps;
+pub use layout::ColumnPropsData;
+pub use layout::Grid;
+pub use layout::GridArea;
+pub use layout::GridItem;
+pub use layout::GridItemProps;
+pub use layout::GridItemPropsData;
+pub use layout::GridProps;
+pub use layout::GridPropsData;
+pub use layout::LayoutPlugin;
+pub use layout::Row;
+pub use layout::RowProps;
+pub use layout::RowPropsData;
+pub use layout::Spacer;
+pub use layout::SpacerProps;
+pub use layout::SpacerPropsData;
+pub use layout::Stack;
+pub use layout::StackProps;
+pub use layout::StackPropsData;
+pub use list::For;
+pub use list::List;
+pub use list::ListProps;
+pub use list::RenderItem;
+pub use panel::Panel;
+pub use panel::PanelProps;
+pub use panel::PanelPropsData;
+pub use pointer::bind_pointer;
+pub use pointer::Modifiers;
+pub use pointer::PointerEvent;
+pub use pointer::PointerEvents;
+pub use pointer::PointerEventKind;
+pub use pointer::PointerHandler;
+pub use pointer::PointerManager;
+pub use pointer::PointerPlugin;
+pub use pointer::PointerProps;
+pub use registry::optional_prop;
+pub use registry::prop;
+pub use registry::DataProp;
+pub use registry::DataProps;
+pub use registry::TextProps;
+pub use registry::UiComponentError;
+pub use registry::UiComponentRegistry;
+pub use show::Show;
+pub use show::ShowProps;
 pub use text_box::TextBox;
 pub use text_box::TextBoxProps;
+pub use text_box::TextBoxPropsData;
+pub use theme::theme_token;
+pub use theme::FontSizes;
+pub use theme::Theme;
+pub use theme::ThemePlugin;
+pub use theme::ThemeTokens;
+pub use ui_component::component;
+pub use ui_component::EventProps;
 pub use ui_component::UiComponent;
+pub use view::view;
+pub use view::ViewChildren;
+pub use view::ViewElement;
+#[doc(hidden)]
+pub use view::__view;
 
-use crate::fgr::FgrCtx;
+use crate::fgr::FgrPlugin;
 use crate::fgr::RootScope;
 use crate::fgr::FgrExtensionMethods;
 
+/// Prop type used by all `ui` components: a plain value or any accessor over the `World`.
+pub type MaybeReactive<A> = crate::fgr::MaybeReactive<World, A>;
+
+pub fn px(value: f32) -> Val {
+    Val::Px(value)
+}
+
+pub fn percent(value: f32) -> Val {
+    Val::Percent(value)
+}
+
+/// `200.0.px()` and `50.0.percent()`, for `Val` props.
+pub trait ValExt {
+    fn px(self) -> Val;
+    fn percent(self) -> Val;
+}
+
+impl ValExt for f32 {
+    fn px(self) -> Val {
+        Val::Px(self)
+    }
+
+    fn percent(self) -> Val {
+        Val::Percent(self)
+    }
+}
+
 pub fn render<'a, CALLBACK: FnOnce(&mut World) -> Entity>(app: &mut App, callback: CALLBACK) -> RootScope<World> {
     let element;
     let root_scope;
-    app.insert_resource(FgrCtx::<World>::new());
+    FgrPlugin::ensure(app);
+    if !app.is_plugin_added::<FocusPlugin>() {
+        app.add_plugins(FocusPlugin);
+    }
+    if !app.is_plugin_added::<PointerPlugin>() {
+        app.add_plugins(PointerPlugin);
+    }
+    if !app.is_plugin_added::<LayoutPlugin>() {
+        app.add_plugins(LayoutPlugin);
+    }
     {
         let world = app.world_mut();
+        if !world.contains_resource::<Theme>() {
+            let theme = Theme::new(world, ThemeTokens::default());
+            world.insert_resource(theme);
+        }
         let ctx = world;
         (element, root_scope) = ctx.fgr_create_root(|ctx, root_scope| {
             let element = callback(ctx);
             (element, root_scope)
         });
     }
-    app
-        .add_systems(
-            Update,
-            move |world: &mut World| {
-                world.fgr_update();
-            }
-        );
     root_scope
 }
@@ -0,0 +1,40 @@
+use bevy::{core::Name, prelude::{BuildWorldChildren, DespawnRecursiveExt, Entity, NodeBundle, World}, ui::FlexDirection};
+
+use crate::fgr::FgrExtensionMethods;
+
+use super::{bind_pointer, bind_style, component, theme_token, PointerProps, Slot, StyleProps};
+
+/// A column of a `header`, the children and a `footer`. Each part gets a node of its own, named
+/// after it, unless it renders nothing. The parts are spaced by step 2 of the theme's spacing scale.
+///
+/// In `view!` the header and footer are passed as slots:
+/// `<Panel> <slot:header> "Title" </slot:header> "body" </Panel>`.
+#[component(reflect)]
+pub fn panel(
+    world: &mut World,
+    #[prop(optional)] header: Slot,
+    #[prop(optional)] children: Slot,
+    #[prop(optional)] footer: Slot,
+    #[prop(optional)] style: StyleProps,
+    #[prop(optional, events)] pointer: PointerProps,
+) -> Entity {
+    let panel_id = world.spawn(NodeBundle::default()).id();
+    let gap = theme_token(world, |theme| theme.space(2));
+    bind_style(world, panel_id, style.or(StyleProps::new().flex_direction(FlexDirection::Column).gap(gap)));
+    bind_pointer(world, panel_id, pointer);
+    for (name, mut section) in [("header", header), ("body", children), ("footer", footer)] {
+        let entities = section.render(world);
+        if entities.is_empty() {
+            continue;
+        }
+        let section_id = world.spawn((Name::new(name), NodeBundle::default())).id();
+        world.entity_mut(section_id).push_children(&entities);
+        world.entity_mut(panel_id).add_child(section_id);
+    }
+    world.fgr_on_cleanup(move |world| {
+        if let Some(entity) = world.get_entity_mut(panel_id) {
+            entity.despawn_recursive();
+        }
+    });
+    panel_id
+}
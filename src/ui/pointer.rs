@@ -0,0 +1,442 @@
+use std::{collections::HashMap, sync::{Arc, Mutex}, time::Duration};
+
+use bevy::{
+    app::{App, Plugin, Update},
+    ecs::query::With,
+    hierarchy::Parent,
+    input::{keyboard::KeyCode, mouse::MouseButton, ButtonInput},
+    math::{Rect, Vec2},
+    prelude::{Entity, GlobalTransform, Resource, ViewVisibility, World},
+    time::Time,
+    ui::{CalculatedClip, Node, UiScale, UiStack},
+    window::{PrimaryWindow, Window},
+};
+
+use crate::fgr::{AccessorExt, BoxedAccessor, EventStream, FgrExtensionMethods, FgrLabelExtensionMethods, FgrPlugin, Signal};
+
+use super::{bubbling::{get_or_insert_resource, BubblingEvent, BubblingEvents, Propagation}, EventProps};
+
+/// How far the pointer has to move with a button held before it is a drag rather than a click.
+const DRAG_THRESHOLD: f32 = 4.0;
+
+/// The longest time between two clicks that still makes them a double click.
+const DOUBLE_CLICK_TIME: Duration = Duration::from_millis(500);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PointerEventKind {
+    /// The pointer moved onto the entity or one of its descendants. Not bubbled.
+    Enter,
+    /// The pointer left the entity and its descendants. Not bubbled.
+    Leave,
+    Down,
+    Up,
+    /// A button was pressed and released over the same entity, without dragging.
+    Click,
+    /// Sent after the second `Click` in a row.
+    DoubleClick,
+    /// Sent to the pressed entity once the pointer moves far enough with the button held.
+    DragStart,
+    Drag,
+    /// Sent to the dragged entity when the button is released, wherever that is.
+    DragEnd,
+    /// Sent to the entity under the pointer when a drag ends over it.
+    Drop,
+}
+
+/// The modifier keys held while a pointer event happened.
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq)]
+pub struct Modifiers {
+    pub shift: bool,
+    pub ctrl: bool,
+    pub alt: bool,
+    pub super_key: bool,
+}
+
+impl Modifiers {
+    fn read(world: &World) -> Self {
+        let Some(keys) = world.get_resource::<ButtonInput<KeyCode>>() else { return Modifiers::default(); };
+        Modifiers {
+            shift: keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]),
+            ctrl: keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]),
+            alt: keys.any_pressed([KeyCode::AltLeft, KeyCode::AltRight]),
+            super_key: keys.any_pressed([KeyCode::SuperLeft, KeyCode::SuperRight]),
+        }
+    }
+}
+
+/// A pointer event routed by the `PointerManager`. Other than `Enter` and `Leave` events bubble
+/// from `target` up through its ancestors, until a handler calls `stop_propagation`.
+#[derive(Clone, Debug)]
+pub struct PointerEvent {
+    pub kind: PointerEventKind,
+    /// The entity the event happened on, for drags the one being dragged.
+    pub target: Entity,
+    /// The button pressed or released, `None` for `Enter` and `Leave`.
+    pub button: Option<MouseButton>,
+    /// In logical pixels, like the layout of UI nodes.
+    pub position: Vec2,
+    /// How far the pointer moved since the previous `Drag`, or since the press for `DragStart`.
+    pub delta: Vec2,
+    /// Clicks in a row on `target`, 2 for a double click.
+    pub click_count: u32,
+    pub modifiers: Modifiers,
+    /// For `Drop`, the entity that was dragged.
+    pub dragged: Option<Entity>,
+    propagation: Propagation,
+}
+
+impl PointerEvent {
+    fn new(kind: PointerEventKind, target: Entity, button: Option<MouseButton>, position: Vec2, modifiers: Modifiers) -> Self {
+        Self {
+            kind,
+            target,
+            button,
+            position,
+            delta: Vec2::ZERO,
+            click_count: 0,
+            modifiers,
+            dragged: None,
+            propagation: Propagation::default(),
+        }
+    }
+
+    /// Keeps the event from bubbling further up than the entity being handled.
+    pub fn stop_propagation(&self) {
+        self.propagation.stop();
+    }
+
+    pub fn is_propagation_stopped(&self) -> bool {
+        self.propagation.is_stopped()
+    }
+}
+
+impl BubblingEvent for PointerEvent {
+    fn propagation(&self) -> &Propagation {
+        &self.propagation
+    }
+}
+
+pub type PointerHandler = Box<dyn FnMut(&mut World, &PointerEvent) + Send + Sync>;
+
+macro_rules! pointer_props {
+    ($($field:ident, $setter:ident => $kind:ident;)*) => {
+        /// Pointer handlers taken by every `ui` widget as its `pointer` prop. In `view!` they
+        /// are set with the event name, e.g. `on:click={..}` or `on:drag_start={..}`, on any
+        /// component whose props take their events from a `#[prop(events)] pointer`, see
+        /// `PointerEvents`.
+        #[derive(Default)]
+        pub struct PointerProps {
+            $(pub $field: Option<PointerHandler>,)*
+        }
+
+        impl PointerProps {
+            pub fn new() -> Self {
+                Self::default()
+            }
+
+            $(
+                pub fn $field(mut self, handler: impl FnMut(&mut World, &PointerEvent) + Send + Sync + 'static) -> Self {
+                    self.$field = Some(Box::new(handler));
+                    self
+                }
+            )*
+
+            fn handler(&mut self, kind: PointerEventKind) -> Option<&mut PointerHandler> {
+                match kind {
+                    $(PointerEventKind::$kind => self.$field.as_mut(),)*
+                }
+            }
+
+            fn is_empty(&self) -> bool {
+                $(self.$field.is_none())&&*
+            }
+        }
+
+        /// Sets the handlers of props taking their events from `PointerProps`, which is how
+        /// `view!` sets `on:click={..}` on components without an `on_click` prop of their own.
+        pub trait PointerEvents {
+            $(fn $setter(&mut self, handler: Option<PointerHandler>);)*
+        }
+
+        impl<P: EventProps<Events = PointerProps>> PointerEvents for P {
+            $(
+                fn $setter(&mut self, handler: Option<PointerHandler>) {
+                    self.events_mut().$field = handler;
+                }
+            )*
+        }
+    };
+}
+
+pointer_props! {
+    on_pointer_enter, set_on_pointer_enter => Enter;
+    on_pointer_leave, set_on_pointer_leave => Leave;
+    on_pointer_down, set_on_pointer_down => Down;
+    on_pointer_up, set_on_pointer_up => Up;
+    on_click, set_on_click => Click;
+    on_double_click, set_on_double_click => DoubleClick;
+    on_drag_start, set_on_drag_start => DragStart;
+    on_drag, set_on_drag => Drag;
+    on_drag_end, set_on_drag_end => DragEnd;
+    on_drop, set_on_drop => Drop;
+}
+
+/// Calls the handlers of `pointer` for the pointer events reaching `entity`, until the current
+/// scope is disposed.
+pub fn bind_pointer(world: &mut World, entity: Entity, mut pointer: PointerProps) {
+    if pointer.is_empty() {
+        return;
+    }
+    let events = PointerManager::get(world).pointer_events(entity);
+    events.on_event(world, move |world, event| {
+        if let Some(handler) = pointer.handler(event.kind) {
+            handler(world, event);
+        }
+    });
+}
+
+struct Press {
+    target: Entity,
+    position: Vec2,
+}
+
+struct Drag {
+    source: Entity,
+    button: MouseButton,
+    position: Vec2,
+}
+
+struct LastClick {
+    target: Entity,
+    button: MouseButton,
+    at: Duration,
+    count: u32,
+}
+
+#[derive(Default)]
+struct PointerState {
+    position: Vec2,
+    presses: HashMap<MouseButton, Press>,
+    drag: Option<Drag>,
+    last_click: Option<LastClick>,
+}
+
+/// Turns pointer movement and button presses into `PointerEvent`s, as a resource. `PointerPlugin`
+/// feeds it the mouse, the methods can also be called directly, e.g. from tests. Each graph label
+/// has a manager of its own, see `FgrLabelExtensionMethods::fgr_resource`, which `PointerPlugin`
+/// feeds the same mouse with its graph active.
+#[derive(Resource, Clone)]
+pub struct PointerManager {
+    /// The entity under the pointer followed by its ancestors.
+    hovered: Signal<World, Vec<Entity>>,
+    state: Arc<Mutex<PointerState>>,
+    events: BubblingEvents<PointerEvent>,
+}
+
+impl PointerManager {
+    pub fn new(world: &mut World) -> Self {
+        Self {
+            hovered: Signal::new(world, Vec::new()),
+            state: Arc::default(),
+            events: BubblingEvents::default(),
+        }
+    }
+
+    /// The `PointerManager` of the active graph, inserted if there is none yet.
+    pub fn get(world: &mut World) -> Self {
+        get_or_insert_resource(world, PointerManager::new)
+    }
+
+    /// Whether the pointer is over `entity` or one of its descendants.
+    pub fn is_hovered(&self, world: &mut World, entity: Entity) -> BoxedAccessor<World, bool> {
+        self.hovered.map(world, move |hovered| hovered.contains(&entity))
+    }
+
+    /// The topmost entity under the pointer.
+    pub fn hovered_entity(&self, world: &mut World) -> Option<Entity> {
+        world.fgr_untrack(|world| self.hovered.value(world).first().copied())
+    }
+
+    /// The pointer events reaching `entity`. Handlers are removed with the scope they are
+    /// registered in.
+    pub fn pointer_events(&self, entity: Entity) -> EventStream<World, PointerEvent> {
+        self.events.events(entity)
+    }
+
+    /// Moves the pointer to `position`, over `target` (the topmost entity there) or over nothing.
+    pub fn move_to(&self, world: &mut World, position: Vec2, target: Option<Entity>) {
+        let modifiers = Modifiers::read(world);
+        let mut path = Vec::new();
+        let mut entity = target;
+        while let Some(current) = entity {
+            path.push(current);
+            entity = world.get::<Parent>(current).map(Parent::get);
+        }
+        let old_path = world.fgr_untrack(|world| self.hovered.value(world).clone());
+        if path != old_path {
+            for entity in old_path.iter().filter(|entity| !path.contains(entity)) {
+                self.events.emit(world, *entity, &PointerEvent::new(PointerEventKind::Leave, *entity, None, position, modifiers));
+            }
+            for entity in path.iter().rev().filter(|entity| !old_path.contains(entity)) {
+                self.events.emit(world, *entity, &PointerEvent::new(PointerEventKind::Enter, *entity, None, position, modifiers));
+            }
+            self.hovered.clone().update_value(world, |x| *x = path);
+        }
+        let event = {
+            let mut state = self.state.lock().unwrap();
+            state.position = position;
+            match &mut state.drag {
+                Some(drag) if drag.position != position => {
+                    let mut event = PointerEvent::new(PointerEventKind::Drag, drag.source, Some(drag.button), position, modifiers);
+                    event.delta = position - drag.position;
+                    drag.position = position;
+                    Some(event)
+                }
+                Some(_) => None,
+                None => {
+                    let started = state.presses.iter().find(|(_, press)| press.position.distance(position) > DRAG_THRESHOLD);
+                    let drag = started.map(|(button, press)| {
+                        let mut event = PointerEvent::new(PointerEventKind::DragStart, press.target, Some(*button), position, modifiers);
+                        event.delta = position - press.position;
+                        (Drag { source: press.target, button: *button, position }, event)
+                    });
+                    drag.map(|(drag, event)| {
+                        state.drag = Some(drag);
+                        event
+                    })
+                }
+            }
+        };
+        if let Some(event) = event {
+            self.dispatch(world, event);
+        }
+    }
+
+    /// Presses `button` over the entity under the pointer.
+    pub fn press(&self, world: &mut World, button: MouseButton) {
+        let Some(target) = self.hovered_entity(world) else { return; };
+        let position = {
+            let mut state = self.state.lock().unwrap();
+            let position = state.position;
+            state.presses.insert(button, Press { target, position });
+            position
+        };
+        let modifiers = Modifiers::read(world);
+        self.dispatch(world, PointerEvent::new(PointerEventKind::Down, target, Some(button), position, modifiers));
+    }
+
+    /// Releases `button`, which ends a drag or clicks the pressed entity if the pointer is
+    /// still over it.
+    pub fn release(&self, world: &mut World, button: MouseButton) {
+        let target = self.hovered_entity(world);
+        let modifiers = Modifiers::read(world);
+        let now = world.get_resource::<Time>().map(|time| time.elapsed()).unwrap_or_default();
+        let (position, press, drag) = {
+            let mut state = self.state.lock().unwrap();
+            let press = state.presses.remove(&button);
+            let drag = match &state.drag {
+                Some(drag) if drag.button == button => state.drag.take(),
+                _ => None,
+            };
+            (state.position, press, drag)
+        };
+        if let Some(target) = target {
+            self.dispatch(world, PointerEvent::new(PointerEventKind::Up, target, Some(button), position, modifiers));
+        }
+        if let Some(drag) = drag {
+            if let Some(target) = target {
+                let mut event = PointerEvent::new(PointerEventKind::Drop, target, Some(button), position, modifiers);
+                event.dragged = Some(drag.source);
+                self.dispatch(world, event);
+            }
+            self.dispatch(world, PointerEvent::new(PointerEventKind::DragEnd, drag.source, Some(button), position, modifiers));
+            return;
+        }
+        let Some(press) = press else { return; };
+        let released_over_press = world.fgr_untrack(|world| self.hovered.value(world).contains(&press.target));
+        if !released_over_press {
+            return;
+        }
+        let click_count = {
+            let mut state = self.state.lock().unwrap();
+            let click_count = match &state.last_click {
+                Some(last) if last.target == press.target && last.button == button && now - last.at <= DOUBLE_CLICK_TIME => last.count + 1,
+                _ => 1,
+            };
+            state.last_click = Some(LastClick { target: press.target, button, at: now, count: click_count });
+            click_count
+        };
+        let mut event = PointerEvent::new(PointerEventKind::Click, press.target, Some(button), position, modifiers);
+        event.click_count = click_count;
+        self.dispatch(world, event.clone());
+        if click_count == 2 {
+            event.kind = PointerEventKind::DoubleClick;
+            event.propagation = Propagation::default();
+            self.dispatch(world, event);
+        }
+    }
+
+    /// Hands `event` to its target and then its ancestors.
+    fn dispatch(&self, world: &mut World, event: PointerEvent) {
+        self.events.dispatch(world, event.target, &event);
+    }
+}
+
+/// The topmost visible UI node under `position`, in the order Bevy draws them.
+fn hit_test(world: &World, position: Vec2) -> Option<Entity> {
+    let stack = world.get_resource::<UiStack>()?;
+    stack.uinodes.iter().rev().copied().find(|entity| {
+        let Some(entity) = world.get_entity(*entity) else { return false; };
+        let (Some(node), Some(transform)) = (entity.get::<Node>(), entity.get::<GlobalTransform>()) else { return false; };
+        if entity.get::<ViewVisibility>().is_some_and(|visibility| !visibility.get()) {
+            return false;
+        }
+        let mut rect = Rect::from_center_size(transform.translation().truncate(), node.size());
+        if let Some(clip) = entity.get::<CalculatedClip>() {
+            rect = rect.intersect(clip.clip);
+        }
+        rect.contains(position)
+    })
+}
+
+/// Inserts the `PointerManager` and feeds it, and those of labelled graphs, the mouse of the
+/// primary window.
+#[derive(Default)]
+pub struct PointerPlugin;
+
+impl Plugin for PointerPlugin {
+    fn build(&self, app: &mut App) {
+        FgrPlugin::ensure(app);
+        let manager = PointerManager::new(app.world_mut());
+        app.insert_resource(manager)
+            .add_systems(Update, update_pointer);
+    }
+}
+
+fn update_pointer(world: &mut World) {
+    let cursor = world
+        .query_filtered::<&Window, With<PrimaryWindow>>()
+        .get_single(world)
+        .ok()
+        .and_then(Window::cursor_position);
+    let scale = world.get_resource::<UiScale>().map_or(1.0, |scale| scale.0);
+    let position = cursor.map(|cursor| cursor / scale);
+    let target = position.and_then(|position| hit_test(world, position));
+    let (pressed, released): (Vec<MouseButton>, Vec<MouseButton>) = match world.get_resource::<ButtonInput<MouseButton>>() {
+        Some(buttons) => (buttons.get_just_pressed().copied().collect(), buttons.get_just_released().copied().collect()),
+        None => Default::default(),
+    };
+    for (label, manager) in world.fgr_resources::<PointerManager>() {
+        world.fgr_in_label(label, |world| {
+            manager.events.prune(world);
+            let position = position.unwrap_or_else(|| manager.state.lock().unwrap().position);
+            manager.move_to(world, position, target);
+            for button in &pressed {
+                manager.press(world, *button);
+            }
+            for button in &released {
+                manager.release(world, *button);
+            }
+        });
+    }
+}
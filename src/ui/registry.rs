@@ -0,0 +1,328 @@
+use std::{collections::BTreeMap, fmt, sync::{Arc, RwLock, RwLockReadGuard}};
+
+use bevy::{
+    log::warn,
+    prelude::{DespawnRecursiveExt, Entity, NodeBundle, Resource, TextBundle, World},
+    color::Color,
+    reflect::{serde::TypedReflectDeserializer, std_traits::ReflectDefault, FromReflect, GetTypeRegistration, Reflect, TypeInfo, TypePath, TypeRegistration, TypeRegistry, Typed},
+    text::{Text, TextStyle},
+    ui::Val,
+};
+use serde::de::{DeserializeSeed, Deserializer};
+
+use crate::fgr::{Accessor, AccessorExt, FgrExtensionMethods};
+
+use super::{bind_style, theme_token, CheckBox, CheckBoxProps, Column, ColumnProps, Grid, GridItem, GridItemProps, GridProps, MaybeReactive, OptionalStyleProps, Panel, PanelProps, PointerProps, Row, RowProps, Slot, Spacer, SpacerProps, Stack, StackProps, StyleProps, StylePropsData, TextBox, TextBoxProps, UiComponent};
+
+type Construct = Arc<dyn Fn(&mut World, MaybeReactive<Box<dyn Reflect>>, Slot) -> Entity + Send + Sync>;
+
+struct RegisteredComponent {
+    props: &'static TypeInfo,
+    construct: Construct,
+}
+
+/// Components that can be built by name, with props given as reflected values or deserialized
+/// from RON or JSON. `UiDocument`s are built from it, and tooling can list what is registered
+/// along with the type of each component's props.
+///
+/// Clones share the same registrations.
+#[derive(Resource, Clone, Default)]
+pub struct UiComponentRegistry {
+    state: Arc<RwLock<RegistryState>>,
+}
+
+#[derive(Default)]
+pub(super) struct RegistryState {
+    components: BTreeMap<String, RegisteredComponent>,
+    pub(super) types: TypeRegistry,
+}
+
+impl RegistryState {
+    /// The registration of the props type of the component `name`.
+    pub(super) fn props_registration(&self, name: &str) -> Option<&TypeRegistration> {
+        let component = self.components.get(name)?;
+        self.types.get(component.props.type_id())
+    }
+}
+
+#[derive(Debug)]
+pub enum UiComponentError {
+    UnknownComponent(String),
+    Props(String),
+}
+
+impl fmt::Display for UiComponentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UiComponentError::UnknownComponent(name) => write!(f, "no component is registered as {:?}", name),
+            UiComponentError::Props(err) => write!(f, "invalid props: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for UiComponentError {}
+
+impl UiComponentRegistry {
+    /// A registry with the components of `ui`, each with the plain data version of its props
+    /// (e.g. `RowPropsData`), and the `Node` and `Text` bundles, with `StylePropsData` and
+    /// `TextProps`.
+    pub fn with_builtins() -> Self {
+        let registry = Self::default();
+        register_builtins(&registry);
+        registry
+    }
+
+    /// Registers `constructor` as the component `name`. Its props are reactive when they are
+    /// bound to signals in a document, `prop` and `optional_prop` turn their fields into props of
+    /// the component that is built. Fields missing from deserialized props keep their defaults.
+    pub fn register<P>(&self, name: impl Into<String>, constructor: impl Fn(&mut World, MaybeReactive<P>, Slot) -> Entity + Send + Sync + 'static)
+    where
+        P: Reflect + Typed + GetTypeRegistration + Default,
+    {
+        let construct: Construct = Arc::new(move |world, props, children| {
+            let props = match props {
+                MaybeReactive::Static(_) => MaybeReactive::from(to_props::<P>(&**props.value(world))),
+                MaybeReactive::Reactive(props) => MaybeReactive::derived(move |world| to_props::<P>(&**props.value(world))),
+            };
+            constructor(world, props, children)
+        });
+        let mut state = self.state.write().unwrap();
+        state.types.register::<P>();
+        state.components.insert(name.into(), RegisteredComponent {
+            props: P::type_info(),
+            construct,
+        });
+    }
+
+    /// Registers the `#[component(reflect)]` `C` as `name`, with the plain data version of its
+    /// props as the props type.
+    pub fn register_component<C, P>(&self, name: impl Into<String>)
+    where
+        C: UiComponent<P>,
+        P: DataProps,
+    {
+        self.register(name, |world, data: MaybeReactive<P::Data>, children| {
+            let props = P::from_data(world, &data, children);
+            C::run(world, props)
+        });
+    }
+
+    pub fn names(&self) -> Vec<String> {
+        self.state.read().unwrap().components.keys().cloned().collect()
+    }
+
+    /// The type of the props of the component `name`.
+    pub fn props_info(&self, name: &str) -> Option<&'static TypeInfo> {
+        Some(self.state.read().unwrap().components.get(name)?.props)
+    }
+
+    /// Builds the component `name` in the current scope. `props` may be the component's props
+    /// type or a dynamic value holding some of its fields.
+    pub fn construct(&self, world: &mut World, name: &str, props: &dyn Reflect, children: Slot) -> Result<Entity, UiComponentError> {
+        self.construct_reactive(world, name, props.clone_value().into(), children)
+    }
+
+    pub fn construct_from_ron(&self, world: &mut World, name: &str, props: &str, children: Slot) -> Result<Entity, UiComponentError> {
+        let mut deserializer = ron::Deserializer::from_str(props).map_err(|err| UiComponentError::Props(err.to_string()))?;
+        let props = self.deserialize_props(name, &mut deserializer)?;
+        self.construct(world, name, &*props, children)
+    }
+
+    pub fn construct_from_json(&self, world: &mut World, name: &str, props: &str, children: Slot) -> Result<Entity, UiComponentError> {
+        let mut deserializer = serde_json::Deserializer::from_str(props);
+        let props = self.deserialize_props(name, &mut deserializer)?;
+        self.construct(world, name, &*props, children)
+    }
+
+    /// Deserializes the props of the component `name` from any serde format, as a dynamic value
+    /// holding the fields that are present.
+    pub fn deserialize_props<'de, D: Deserializer<'de>>(&self, name: &str, deserializer: D) -> Result<Box<dyn Reflect>, UiComponentError> {
+        let state = self.state.read().unwrap();
+        let registration = state.props_registration(name).ok_or_else(|| UiComponentError::UnknownComponent(name.into()))?;
+        TypedReflectDeserializer::new(registration, &state.types)
+            .deserialize(deserializer)
+            .map_err(|err| UiComponentError::Props(err.to_string()))
+    }
+
+    pub(super) fn construct_reactive(&self, world: &mut World, name: &str, props: MaybeReactive<Box<dyn Reflect>>, children: Slot) -> Result<Entity, UiComponentError> {
+        let construct = self.state.read().unwrap().components.get(name).map(|component| Arc::clone(&component.construct));
+        let construct = construct.ok_or_else(|| UiComponentError::UnknownComponent(name.into()))?;
+        Ok(construct(world, props, children))
+    }
+
+    pub(super) fn read(&self) -> RwLockReadGuard<'_, RegistryState> {
+        self.state.read().unwrap()
+    }
+}
+
+fn to_props<P: Reflect + Default>(value: &dyn Reflect) -> P {
+    let mut props = P::default();
+    if let Err(err) = props.try_apply(value) {
+        warn!("ignoring props that do not apply to {}: {}", props.reflect_type_path(), err);
+    }
+    props
+}
+
+/// A field of a registered component's props, as a prop of the component it builds. Reactive
+/// if the props are, which takes a scope.
+pub fn prop<P, T>(world: &mut World, props: &MaybeReactive<P>, field: impl Fn(&P) -> T + Send + Sync + 'static) -> MaybeReactive<T>
+where
+    P: Send + Sync + 'static,
+    T: PartialEq + Send + Sync + 'static,
+{
+    if props.is_static() {
+        return field(&props.value(world)).into();
+    }
+    props.map(world, field).into()
+}
+
+/// Like `prop`, for optional fields, as an optional style field (see `OptionalStyleProps`).
+/// `None` if the props are static and leave the field unset, so the component's own value stays.
+pub fn optional_prop<P, T>(world: &mut World, props: &MaybeReactive<P>, field: impl Fn(&P) -> Option<T> + Send + Sync + 'static) -> Option<MaybeReactive<Option<T>>>
+where
+    P: Send + Sync + 'static,
+    T: PartialEq + Send + Sync + 'static,
+{
+    if props.is_static() {
+        return field(&props.value(world)).map(|value| Some(value).into());
+    }
+    Some(props.map(world, field).into())
+}
+
+/// Props of a `#[component(reflect)]`, built from a reflected plain data version with a field
+/// per prop. Fields that are `None` leave the prop at its default.
+pub trait DataProps: Sized {
+    type Data: Reflect + Typed + GetTypeRegistration + Default + Clone + PartialEq;
+
+    /// The props with the fields `data` sets, reactive if `data` is, which takes a scope.
+    /// `children` go to the `children` prop, if there is one.
+    fn from_data(world: &mut World, data: &MaybeReactive<Self::Data>, children: Slot) -> Self;
+}
+
+/// How a prop of a `#[component(reflect)]` is set from its field in `DataProps::Data`.
+///
+/// Reactive props take `Option`s of their value. Plain values do too but are read once, when
+/// the component is built. Props that are not data, such as slots, callbacks and pointer
+/// handlers, take `()` and keep their defaults.
+pub trait DataProp: Sized {
+    type Data: FromReflect + TypePath + GetTypeRegistration + Default + Clone + PartialEq + Send + Sync + 'static;
+
+    /// Sets `prop`, which holds its default, from `data`.
+    fn set_from_data(prop: &mut Self, world: &mut World, data: MaybeReactive<Self::Data>);
+}
+
+impl<T> DataProp for MaybeReactive<T>
+where
+    T: Clone + PartialEq + Send + Sync + 'static,
+    Option<T>: FromReflect + TypePath + GetTypeRegistration,
+{
+    type Data = Option<T>;
+
+    fn set_from_data(prop: &mut Self, world: &mut World, data: MaybeReactive<Option<T>>) {
+        if data.is_static() {
+            if let Some(value) = data.value(world).clone() {
+                *prop = value.into();
+            }
+            return;
+        }
+        let default = prop.clone();
+        *prop = MaybeReactive::derived(move |world| {
+            let value = data.value(world).clone();
+            value.unwrap_or_else(|| default.value(world).clone())
+        });
+    }
+}
+
+macro_rules! plain_data_props {
+    ($($ty:ty),*) => {
+        $(
+            impl DataProp for $ty {
+                type Data = Option<$ty>;
+
+                fn set_from_data(prop: &mut Self, world: &mut World, data: MaybeReactive<Option<$ty>>) {
+                    if let Some(value) = world.fgr_untrack(|world| data.value(world).clone()) {
+                        *prop = value;
+                    }
+                }
+            }
+        )*
+    };
+}
+
+plain_data_props!(String, bool, f32, f64, i32, u32, usize, Val, Color);
+
+macro_rules! non_data_props {
+    ($($ty:ty),*) => {
+        $(
+            impl DataProp for $ty {
+                type Data = ();
+
+                fn set_from_data(_prop: &mut Self, _world: &mut World, _data: MaybeReactive<()>) {}
+            }
+        )*
+    };
+}
+
+non_data_props!(Slot, PointerProps);
+
+impl<A: 'static> DataProp for Option<Box<dyn FnMut(&mut World, A) + Send + Sync>> {
+    type Data = ();
+
+    fn set_from_data(_prop: &mut Self, _world: &mut World, _data: MaybeReactive<()>) {}
+}
+
+/// Props of the registered `Text`.
+#[derive(Reflect, Clone, Default, PartialEq, Debug)]
+#[reflect(Default)]
+pub struct TextProps {
+    pub text: String,
+    pub font_size: Option<f32>,
+}
+
+fn register_builtins(registry: &UiComponentRegistry) {
+    registry.register("Node", |world, data: MaybeReactive<StylePropsData>, mut children| {
+        let node_id = world.spawn(NodeBundle::default()).id();
+        let mut style = StyleProps::new();
+        DataProp::set_from_data(&mut style, world, data);
+        bind_style(world, node_id, style);
+        children.mount(world, node_id);
+        world.fgr_on_cleanup(move |world| {
+            if let Some(entity) = world.get_entity_mut(node_id) {
+                entity.despawn_recursive();
+            }
+        });
+        node_id
+    });
+    registry.register("Text", |world, props: MaybeReactive<TextProps>, _children| {
+        let text = prop(world, &props, |props| props.text.clone());
+        let text_id = world.spawn(TextBundle::from_section("", TextStyle::default())).id();
+        world.fgr_create_effect_on(text, move |world, text, _| {
+            if let Some(mut contents) = world.get_mut::<Text>(text_id) {
+                contents.sections[0].value.clone_from(text);
+            }
+        });
+        let style = StyleProps::new().optional(OptionalStyleProps {
+            font_size: optional_prop(world, &props, |props| props.font_size),
+            ..Default::default()
+        });
+        let defaults = StyleProps::new()
+            .font_size(theme_token(world, |theme| theme.font_sizes.body))
+            .text_color(theme_token(world, |theme| theme.text));
+        bind_style(world, text_id, style.or(defaults));
+        world.fgr_on_cleanup(move |world| {
+            if let Some(entity) = world.get_entity_mut(text_id) {
+                entity.despawn_recursive();
+            }
+        });
+        text_id
+    });
+    registry.register_component::<Row, RowProps>("Row");
+    registry.register_component::<Column, ColumnProps>("Column");
+    registry.register_component::<Stack, StackProps>("Stack");
+    registry.register_component::<Spacer, SpacerProps>("Spacer");
+    registry.register_component::<Grid, GridProps>("Grid");
+    registry.register_component::<GridItem, GridItemProps>("GridItem");
+    registry.register_component::<Panel, PanelProps>("Panel");
+    registry.register_component::<CheckBox, CheckBoxProps>("CheckBox");
+    registry.register_component::<TextBox, TextBoxProps>("TextBox");
+}
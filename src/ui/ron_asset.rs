@@ -0,0 +1,54 @@
+use std::{fmt, sync::Arc};
+
+use bevy::asset::{io::Reader, Asset, AssetLoader, AsyncReadExt, LoadContext};
+
+type Parse<A> = Arc<dyn Fn(&[u8]) -> Result<A, ron::error::SpannedError> + Send + Sync>;
+
+/// Loads assets written in RON, such as themes and UI documents, by handing the file's bytes to
+/// a parse function.
+pub(super) struct RonAssetLoader<A> {
+    extensions: &'static [&'static str],
+    parse: Parse<A>,
+}
+
+impl<A> RonAssetLoader<A> {
+    pub(super) fn new(extensions: &'static [&'static str], parse: impl Fn(&[u8]) -> Result<A, ron::error::SpannedError> + Send + Sync + 'static) -> Self {
+        Self {
+            extensions,
+            parse: Arc::new(parse),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub(super) enum RonAssetLoadError {
+    Io(std::io::Error),
+    Ron(ron::error::SpannedError),
+}
+
+impl fmt::Display for RonAssetLoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RonAssetLoadError::Io(err) => write!(f, "could not read asset: {}", err),
+            RonAssetLoadError::Ron(err) => write!(f, "could not parse asset: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for RonAssetLoadError {}
+
+impl<A: Asset> AssetLoader for RonAssetLoader<A> {
+    type Asset = A;
+    type Settings = ();
+    type Error = RonAssetLoadError;
+
+    async fn load<'a>(&'a self, reader: &'a mut Reader<'_>, _settings: &'a (), _load_context: &'a mut LoadContext<'_>) -> Result<A, RonAssetLoadError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await.map_err(RonAssetLoadError::Io)?;
+        (self.parse)(&bytes).map_err(RonAssetLoadError::Ron)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        self.extensions
+    }
+}
@@ -0,0 +1,77 @@
+use std::sync::{Arc, Mutex};
+
+use bevy::prelude::{DespawnRecursiveExt, Entity, NodeBundle, World};
+
+use crate::fgr::{FgrExtensionMethods, RootScope};
+
+use super::{MaybeReactive, Slot, UiComponent, ViewElement};
+
+pub struct ShowProps {
+    pub when: MaybeReactive<bool>,
+    pub children: Slot,
+}
+
+impl Default for ShowProps {
+    fn default() -> Self {
+        Self {
+            when: false.into(),
+            children: Slot::default(),
+        }
+    }
+}
+
+/// Renders `children` while `when` is true. The children get a root scope of their own, which
+/// is disposed and their entities despawned as soon as `when` turns false.
+pub struct Show;
+
+struct Shown {
+    entities: Vec<Entity>,
+    scope: RootScope<World>,
+}
+
+fn hide(world: &mut World, shown: &Mutex<Option<Shown>>) {
+    let Some(mut shown) = shown.lock().unwrap().take() else { return; };
+    shown.scope.dispose(world);
+    for entity in shown.entities {
+        if let Some(entity) = world.get_entity_mut(entity) {
+            entity.despawn_recursive();
+        }
+    }
+}
+
+impl UiComponent<ShowProps> for Show {
+    fn run(world: &mut World, props: ShowProps) -> Entity {
+        let show_id = world.spawn(NodeBundle::default()).id();
+        let shown = Arc::new(Mutex::new(None));
+        let mut children = props.children;
+        world.fgr_create_effect_on(props.when, {
+            let shown = Arc::clone(&shown);
+            move |world, when, prev_when| {
+                if prev_when == Some(when) {
+                    return;
+                }
+                hide(world, &shown);
+                if !*when {
+                    return;
+                }
+                let (entities, scope) = world.fgr_create_root(|world, scope| (children.mount(world, show_id), scope));
+                *shown.lock().unwrap() = Some(Shown { entities, scope });
+            }
+        });
+        world.fgr_on_cleanup(move |world| {
+            hide(world, &shown);
+            if let Some(entity) = world.get_entity_mut(show_id) {
+                entity.despawn_recursive();
+            }
+        });
+        show_id
+    }
+}
+
+impl ViewElement for Show {
+    type Props = ShowProps;
+
+    fn build(world: &mut World, props: ShowProps) -> Entity {
+        Show::run(world, props)
+    }
+}
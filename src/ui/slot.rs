@@ -0,0 +1,56 @@
+use bevy::prelude::{BuildWorldChildren, Entity, World};
+
+/// Renders a list of children on demand.
+pub type ViewFn = Box<dyn FnMut(&mut World) -> Vec<Entity> + Send + Sync>;
+
+/// Entities handed to a component to place in its own hierarchy: its children or a named slot
+/// such as a panel's `header`.
+///
+/// Slots written in `view!` are rendered before the component runs, in the same scope, and
+/// handed over once. `Slot::new` renders on every call instead, which is what `Show` needs.
+pub struct Slot {
+    render: ViewFn,
+}
+
+impl Slot {
+    pub fn new(render: impl FnMut(&mut World) -> Vec<Entity> + Send + Sync + 'static) -> Self {
+        Self { render: Box::new(render) }
+    }
+
+    pub fn render(&mut self, world: &mut World) -> Vec<Entity> {
+        (self.render)(world)
+    }
+
+    /// Renders the children and appends them to `parent`.
+    pub fn mount(&mut self, world: &mut World, parent: Entity) -> Vec<Entity> {
+        let children = self.render(world);
+        if !children.is_empty() {
+            world.entity_mut(parent).push_children(&children);
+        }
+        children
+    }
+}
+
+impl Default for Slot {
+    fn default() -> Self {
+        Self::new(|_world| Vec::new())
+    }
+}
+
+impl From<Vec<Entity>> for Slot {
+    fn from(mut entities: Vec<Entity>) -> Self {
+        Self::new(move |_world| std::mem::take(&mut entities))
+    }
+}
+
+impl From<Entity> for Slot {
+    fn from(entity: Entity) -> Self {
+        vec![entity].into()
+    }
+}
+
+impl<F: FnMut(&mut World) -> Vec<Entity> + Send + Sync + 'static> From<F> for Slot {
+    fn from(render: F) -> Self {
+        Self::new(render)
+    }
+}
@@ -0,0 +1,206 @@
+use bevy::{color::Color, ecs::{change_detection::DetectChangesMut, component::Component}, prelude::{Entity, World}, reflect::{std_traits::ReflectDefault, Reflect}, text::Text, ui::{AlignItems, BackgroundColor, BorderColor, BorderRadius, Display, FlexDirection, FlexWrap, JustifyContent, Outline, RepeatedGridTrack, Style, UiRect, Val}};
+
+use crate::fgr::{Accessor, FgrExtensionMethods};
+
+use super::{optional_prop, DataProp, MaybeReactive};
+
+macro_rules! style_props {
+    ($($(#[doc = $doc:literal])* $field:ident: $ty:ty => |$component:ident: $component_ty:ty, $value:ident| $patch:expr;)*) => {
+        /// Styling accepted by every `ui` component. Unset fields keep the component's own
+        /// styling, set ones may be plain values or accessors.
+        #[derive(Clone, Default)]
+        pub struct StyleProps {
+            $($(#[doc = $doc])* pub $field: Option<MaybeReactive<$ty>>,)*
+            /// Fields that are only set while their value is `Some`, see `optional`.
+            pub optional: OptionalStyleProps,
+        }
+
+        /// Style fields whose value may be missing, such as ones bound to optional props. While a
+        /// value is `None` the field falls back to what `StyleProps::or` takes from the defaults.
+        /// Without a default the field is left alone while `None`, so it keeps the last value it
+        /// was set to rather than going back to the one the entity was spawned with.
+        #[derive(Clone, Default)]
+        pub struct OptionalStyleProps {
+            $(pub $field: Option<MaybeReactive<Option<$ty>>>,)*
+        }
+
+        /// `StyleProps` as plain data, for the `UiComponentRegistry`. Set fields are optional
+        /// style fields, so components keep their own styling where they are `None`.
+        #[derive(Reflect, Clone, Default, PartialEq, Debug)]
+        #[reflect(Default)]
+        pub struct StylePropsData {
+            $($(#[doc = $doc])* pub $field: Option<$ty>,)*
+        }
+
+        impl DataProp for StyleProps {
+            type Data = StylePropsData;
+
+            fn set_from_data(prop: &mut Self, world: &mut World, data: MaybeReactive<StylePropsData>) {
+                $(prop.optional.$field = optional_prop(world, &data, |data| data.$field.clone());)*
+            }
+        }
+
+        impl StyleProps {
+            pub fn new() -> Self {
+                Self::default()
+            }
+
+            $(
+                pub fn $field(mut self, $field: impl Into<MaybeReactive<$ty>>) -> Self {
+                    self.$field = Some($field.into());
+                    self
+                }
+            )*
+
+            /// Sets the fields of `optional`, leaving the fields set on `self` alone.
+            pub fn optional(mut self, optional: OptionalStyleProps) -> Self {
+                self.optional = optional;
+                self
+            }
+
+            /// Takes the fields `self` leaves unset from `defaults`, e.g. a component's own styling.
+            /// Optional fields use the default while their value is `None`.
+            pub fn or(self, defaults: StyleProps) -> Self {
+                let mut optional = OptionalStyleProps::default();
+                Self {
+                    $($field: or_field(self.$field, self.optional.$field, defaults.$field, defaults.optional.$field, &mut optional.$field),)*
+                    optional,
+                }
+            }
+        }
+
+        /// Applies `style` to `entity` for as long as the current scope lives. Every set field is
+        /// bound on its own and only written (and marked changed) when its value differs, so
+        /// changing one field leaves the other fields and components alone. Fields of components
+        /// the entity does not have are skipped.
+        pub fn bind_style(world: &mut World, entity: Entity, style: StyleProps) {
+            $(
+                bind_field(world, entity, style.$field, |$component: &mut $component_ty, $value: &$ty| $patch);
+                bind_optional_field(world, entity, style.optional.$field, |$component: &mut $component_ty, $value: &$ty| $patch);
+            )*
+        }
+    };
+}
+
+style_props! {
+    display: Display => |style: Style, value| set(&mut style.display, value);
+    width: Val => |style: Style, value| set(&mut style.width, value);
+    height: Val => |style: Style, value| set(&mut style.height, value);
+    min_width: Val => |style: Style, value| set(&mut style.min_width, value);
+    min_height: Val => |style: Style, value| set(&mut style.min_height, value);
+    max_width: Val => |style: Style, value| set(&mut style.max_width, value);
+    max_height: Val => |style: Style, value| set(&mut style.max_height, value);
+    flex_direction: FlexDirection => |style: Style, value| set(&mut style.flex_direction, value);
+    flex_wrap: FlexWrap => |style: Style, value| set(&mut style.flex_wrap, value);
+    flex_grow: f32 => |style: Style, value| set(&mut style.flex_grow, value);
+    align_items: AlignItems => |style: Style, value| set(&mut style.align_items, value);
+    justify_content: JustifyContent => |style: Style, value| set(&mut style.justify_content, value);
+    /// Both the row and the column gap.
+    gap: Val => |style: Style, value| set(&mut style.row_gap, value) | set(&mut style.column_gap, value);
+    grid_template_columns: Vec<RepeatedGridTrack> => |style: Style, value| set(&mut style.grid_template_columns, value);
+    grid_template_rows: Vec<RepeatedGridTrack> => |style: Style, value| set(&mut style.grid_template_rows, value);
+    padding: UiRect => |style: Style, value| set(&mut style.padding, value);
+    margin: UiRect => |style: Style, value| set(&mut style.margin, value);
+    /// The border widths.
+    border: UiRect => |style: Style, value| set(&mut style.border, value);
+    background_color: Color => |background_color: BackgroundColor, value| set(&mut background_color.0, value);
+    border_color: Color => |border_color: BorderColor, value| set(&mut border_color.0, value);
+    /// Only applies to entities with an `Outline`, such as focusable ones.
+    outline_color: Color => |outline: Outline, value| set(&mut outline.color, value);
+    /// The same radius for every corner.
+    border_radius: Val => |border_radius: BorderRadius, value| set(border_radius, &BorderRadius::all(*value));
+    /// Applies to every section of the entity's `Text`.
+    font_size: f32 => |text: Text, value| text.sections.iter_mut().fold(false, |changed, section| set(&mut section.style.font_size, value) | changed);
+    /// Applies to every section of the entity's `Text`.
+    text_color: Color => |text: Text, value| text.sections.iter_mut().fold(false, |changed, section| set(&mut section.style.color, value) | changed);
+}
+
+impl StyleProps {
+    /// Just the fields that apply to text, for components that bind them to their text
+    /// entities rather than to their root.
+    pub fn text(&self) -> StyleProps {
+        StyleProps {
+            font_size: self.font_size.clone(),
+            text_color: self.text_color.clone(),
+            optional: OptionalStyleProps {
+                font_size: self.optional.font_size.clone(),
+                text_color: self.optional.text_color.clone(),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+}
+
+fn set<T: PartialEq + Clone>(slot: &mut T, value: &T) -> bool {
+    if slot == value {
+        return false;
+    }
+    *slot = value.clone();
+    true
+}
+
+fn bind_field<C: Component, T: Clone + Send + Sync + 'static>(world: &mut World, entity: Entity, value: Option<MaybeReactive<T>>, patch: fn(&mut C, &T) -> bool) {
+    let Some(value) = value else { return; };
+    world.fgr_create_effect_on(value, move |world, value, _| {
+        let Some(mut component) = world.get_mut::<C>(entity) else { return; };
+        if patch(component.bypass_change_detection(), value) {
+            component.set_changed();
+        }
+    });
+}
+
+/// Like `bind_field`, skipping `None`s, which keeps the last value that was set.
+fn bind_optional_field<C: Component, T: Clone + Send + Sync + 'static>(world: &mut World, entity: Entity, value: Option<MaybeReactive<Option<T>>>, patch: fn(&mut C, &T) -> bool) {
+    let Some(value) = value else { return; };
+    world.fgr_create_effect_on(value, move |world, value, _| {
+        let Some(value) = value else { return; };
+        let Some(mut component) = world.get_mut::<C>(entity) else { return; };
+        if patch(component.bypass_change_detection(), value) {
+            component.set_changed();
+        }
+    });
+}
+
+/// One field of `StyleProps::or`: a set value wins, then an optional one falling back to the
+/// default. Without a default the optional value stays optional, in `optional_out`.
+fn or_field<T: Clone + Send + Sync + 'static>(
+    value: Option<MaybeReactive<T>>,
+    optional: Option<MaybeReactive<Option<T>>>,
+    default: Option<MaybeReactive<T>>,
+    optional_default: Option<MaybeReactive<Option<T>>>,
+    optional_out: &mut Option<MaybeReactive<Option<T>>>,
+) -> Option<MaybeReactive<T>> {
+    if value.is_some() {
+        return value;
+    }
+    let Some(optional) = optional else {
+        *optional_out = optional_default;
+        return default;
+    };
+    match (default, optional_default) {
+        (Some(default), _) => Some(fall_back(optional, default)),
+        (None, Some(optional_default)) => {
+            *optional_out = Some(MaybeReactive::derived(move |world| optional.value(world).clone().or_else(|| optional_default.value(world).clone())));
+            None
+        }
+        (None, None) => {
+            *optional_out = Some(optional);
+            None
+        }
+    }
+}
+
+/// `value` while it is `Some`, `fallback` otherwise.
+fn fall_back<T: Clone + Send + Sync + 'static>(value: MaybeReactive<Option<T>>, fallback: MaybeReactive<T>) -> MaybeReactive<T> {
+    match value {
+        MaybeReactive::Static(value) => match value.get() {
+            Some(value) => value.clone().into(),
+            None => fallback,
+        },
+        MaybeReactive::Reactive(value) => MaybeReactive::derived(move |world| match &*value.value(world) {
+            Some(value) => value.clone(),
+            None => fallback.value(world).clone(),
+        }),
+    }
+}
@@ -0,0 +1,201 @@
+use bevy::{
+    app::{App, Plugin, Update},
+    asset::{Asset, AssetApp, AssetEvent, AssetServer, Assets, Handle},
+    color::{palettes::css::{BLUE, GREEN, RED}, Color, Srgba},
+    ecs::event::{Events, ManualEventReader},
+    prelude::{Local, Resource, World},
+    reflect::TypePath,
+    ui::Val,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::fgr::{AccessorExt, FgrExtensionMethods, FgrLabelExtensionMethods, FgrPlugin, Signal};
+
+use super::{ron_asset::RonAssetLoader, MaybeReactive};
+
+/// The semantic design tokens components are styled with. Loadable from `.theme.ron` files,
+/// colors are written as hex strings, e.g. `surface: "#1e1e1e"`.
+#[derive(Asset, TypePath, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ThemeTokens {
+    /// Backgrounds of controls and panels.
+    #[serde(with = "hex_color")]
+    pub surface: Color,
+    /// Checked states, the text cursor and other highlights.
+    #[serde(with = "hex_color")]
+    pub accent: Color,
+    #[serde(with = "hex_color")]
+    pub text: Color,
+    #[serde(with = "hex_color")]
+    pub border: Color,
+    /// Spacing scale in logical pixels, see `ThemeTokens::space`.
+    pub spacing: Vec<f32>,
+    pub font_sizes: FontSizes,
+    /// Asset path of the font used for text.
+    pub font: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FontSizes {
+    pub small: f32,
+    pub body: f32,
+    pub heading: f32,
+}
+
+impl ThemeTokens {
+    pub fn dark() -> Self {
+        Self {
+            surface: Srgba::BLACK.into(),
+            accent: RED.into(),
+            text: Srgba::WHITE.into(),
+            border: GREEN.into(),
+            spacing: vec![0.0, 4.0, 8.0, 16.0, 24.0, 32.0],
+            font_sizes: FontSizes {
+                small: 14.0,
+                body: 20.0,
+                heading: 28.0,
+            },
+            font: "fonts/FiraSans-Bold.ttf".into(),
+        }
+    }
+
+    pub fn light() -> Self {
+        Self {
+            surface: Srgba::WHITE.into(),
+            accent: BLUE.into(),
+            text: Srgba::BLACK.into(),
+            border: Srgba::rgb(0.6, 0.6, 0.6).into(),
+            ..Self::dark()
+        }
+    }
+
+    /// Step `step` of the spacing scale, the last step for anything past the end.
+    pub fn space(&self, step: usize) -> Val {
+        match self.spacing.get(step).or(self.spacing.last()) {
+            Some(space) => Val::Px(*space),
+            None => Val::ZERO,
+        }
+    }
+}
+
+impl Default for ThemeTokens {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
+
+/// The active theme as a resource. Setting new tokens restyles every component reading them.
+/// Labelled graphs get a theme of their own, see `get`.
+#[derive(Resource, Clone)]
+pub struct Theme {
+    tokens: Signal<World, ThemeTokens>,
+    source: Option<Handle<ThemeTokens>>,
+}
+
+impl Theme {
+    pub fn new(world: &mut World, tokens: ThemeTokens) -> Self {
+        Self {
+            tokens: Signal::new(world, tokens),
+            source: None,
+        }
+    }
+
+    /// The `Theme` of the active graph, `None` without a `Theme` resource. A labelled graph gets
+    /// its own on first use, with the tokens the resource has then and following its asset.
+    pub fn get(world: &mut World) -> Option<Self> {
+        if let Some(theme) = world.fgr_resource::<Theme>() {
+            return Some(theme);
+        }
+        let unlabelled = world.get_resource::<Theme>().cloned()?;
+        let tokens = world.fgr_in_ctx::<(), _, _>(|world| world.fgr_untrack(|world| unlabelled.tokens.value(world).clone()));
+        let theme = Theme {
+            tokens: Signal::new(world, tokens),
+            source: unlabelled.source,
+        };
+        world.fgr_insert_resource(theme.clone());
+        Some(theme)
+    }
+
+    pub fn tokens(&self) -> Signal<World, ThemeTokens> {
+        self.tokens.clone()
+    }
+
+    pub fn set(&self, world: &mut World, tokens: ThemeTokens) {
+        self.tokens.clone().update_value(world, |x| *x = tokens);
+    }
+}
+
+/// A token of the active graph's `Theme`, memoized so it only changes when that token does. Without a
+/// `Theme` the token of the default theme is used as a plain value. Must be called inside a
+/// scope.
+pub fn theme_token<A>(world: &mut World, token: impl Fn(&ThemeTokens) -> A + Send + Sync + 'static) -> MaybeReactive<A>
+where
+    A: PartialEq + Send + Sync + 'static,
+{
+    match Theme::get(world) {
+        Some(theme) => theme.tokens().map(world, token).into(),
+        None => token(&ThemeTokens::default()).into(),
+    }
+}
+
+/// Inserts the `Theme` resource, optionally following a `.theme.ron` asset. Asset changes are
+/// applied as they are loaded. With the `dev` feature (on by default), which turns on Bevy's
+/// `file_watcher`, that includes edits to the file.
+#[derive(Default)]
+pub struct ThemePlugin {
+    pub tokens: ThemeTokens,
+    pub source: Option<String>,
+}
+
+impl ThemePlugin {
+    pub fn from_asset(path: impl Into<String>) -> Self {
+        Self {
+            source: Some(path.into()),
+            ..Default::default()
+        }
+    }
+}
+
+impl Plugin for ThemePlugin {
+    fn build(&self, app: &mut App) {
+        FgrPlugin::ensure(app);
+        app.init_asset::<ThemeTokens>()
+            .register_asset_loader(RonAssetLoader::<ThemeTokens>::new(&["theme.ron"], |bytes| ron::de::from_bytes(bytes)))
+            .add_systems(Update, apply_theme_asset);
+        let world = app.world_mut();
+        let mut theme = Theme::new(world, self.tokens.clone());
+        theme.source = self.source.as_ref().map(|path| world.resource::<AssetServer>().load(path.clone()));
+        world.insert_resource(theme);
+    }
+}
+
+fn apply_theme_asset(world: &mut World, mut reader: Local<ManualEventReader<AssetEvent<ThemeTokens>>>) {
+    let events = world.resource::<Events<AssetEvent<ThemeTokens>>>();
+    let changed: Vec<_> = reader
+        .read(events)
+        .filter(|event| matches!(event, AssetEvent::LoadedWithDependencies { .. } | AssetEvent::Modified { .. }))
+        .cloned()
+        .collect();
+    for (label, theme) in world.fgr_resources::<Theme>() {
+        let Some(source) = &theme.source else { continue; };
+        if !changed.iter().any(|event| event.is_loaded_with_dependencies(source) || event.is_modified(source)) {
+            continue;
+        }
+        if let Some(tokens) = world.resource::<Assets<ThemeTokens>>().get(source).cloned() {
+            world.fgr_in_label(label, |world| theme.set(world, tokens));
+        }
+    }
+}
+
+mod hex_color {
+    use bevy::color::{Color, Srgba};
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(color: &Color, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&Srgba::from(*color).to_hex())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Color, D::Error> {
+        let hex = String::deserialize(deserializer)?;
+        Srgba::hex(&hex).map(Color::from).map_err(D::Error::custom)
+    }
+}
@@ -1,5 +1,79 @@
 use bevy::prelude::{Entity, World};
 
+use super::Slot;
+
 pub trait UiComponent<P> {
     fn run(world: &mut World, props: P) -> Entity;
+
+    /// Places `children` (or a slot) passed in through the props in the component's own
+    /// hierarchy, under `parent`, which need not be the entity `run` returns. They are rendered
+    /// in the current scope, so they live as long as the component.
+    fn mount_children(world: &mut World, parent: Entity, children: &mut Slot) -> Vec<Entity> {
+        children.mount(world, parent)
+    }
+}
+
+/// Props taking the events they have no handler prop for from one of their fields, the prop
+/// marked `#[prop(events)]` of a `#[component]`. `view!` sets such events through the setter
+/// traits of the events type, see `PointerEvents`.
+pub trait EventProps {
+    type Events;
+
+    fn events_mut(&mut self) -> &mut Self::Events;
 }
+
+/// Turns a function building a component into the component itself.
+///
+/// ```no_run
+/// use bevy::prelude::*;
+/// use bevy_editor_experiment_lib::ui::{self, component, view, MaybeReactive, UiComponent};
+///
+/// /// A coloured square.
+/// #[component]
+/// pub fn swatch(
+///     world: &mut World,
+///     color: Color,
+///     #[prop(default = ui::px(10.0).into())] size: MaybeReactive<Val>,
+///     #[prop(optional)] label: Option<String>,
+/// ) -> Entity {
+///     world.spawn(NodeBundle { background_color: color.into(), ..default() }).id()
+/// }
+///
+/// fn build(world: &mut World) -> Entity {
+///     Swatch::run(world, SwatchProps::new(Color::WHITE).label("white".to_string()))
+/// }
+/// ```
+///
+/// generates
+/// - the unit struct `Swatch`, which gets the function's doc comment,
+/// - `SwatchProps` with a public field per prop, a `new` taking the props without a default and
+///   a setter per prop taking anything that converts into it,
+/// - `UiComponent<SwatchProps>` and `ViewElement` for `Swatch`, calling the function with the
+///   props,
+/// - `Default` for `SwatchProps`, if every prop has a default.
+///
+/// A prop named `children`, of type `Slot`, receives the children written inside the
+/// component in `view!`, other `Slot` props are filled with `<slot:name>`, see `Panel`.
+///
+/// The one prop marked `#[prop(events)]`, usually `pointer: PointerProps`, takes the events the
+/// component has no prop for: the props implement `EventProps` with it, so `on:click` in `view!`
+/// sets its `on_click`, unless the component has an `on_click` prop itself.
+///
+/// Props marked `#[prop(optional)]` default to `Default::default()`, `#[prop(default = expr)]`
+/// to `expr`. Components with required props can not be used in `view!`, which reports the
+/// props without a default:
+///
+/// ```compile_fail
+/// use bevy::prelude::*;
+/// use bevy_editor_experiment_lib::ui::{component, view};
+///
+/// #[component]
+/// fn swatch(world: &mut World, color: Color) -> Entity {
+///     world.spawn(NodeBundle { background_color: color.into(), ..default() }).id()
+/// }
+///
+/// fn build(world: &mut World) -> Entity {
+///     view!(world, <Swatch color={Color::WHITE} />)
+/// }
+/// ```
+pub use bevy_editor_experiment_macros::component;
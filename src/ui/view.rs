@@ -0,0 +1,233 @@
+use bevy::{ecs::bundle::Bundle, prelude::{DespawnRecursiveExt, Entity, World}};
+
+use crate::fgr::FgrExtensionMethods;
+
+use super::Slot;
+
+/// Builds a tree of components and bundles, returning the root entity.
+///
+/// ```no_run
+/// use bevy::prelude::*;
+/// use bevy_editor_experiment_lib::{cloned, fgr::{Accessor, ReactiveVec, Signal}, ui::{self, view, CheckBox, For, Show, TextBox, ValExt}};
+///
+/// fn build(world: &mut World) -> Entity {
+///     let checked = Signal::new(world, false);
+///     let names = ReactiveVec::new(world, vec!["a".to_string(), "b".to_string()]);
+///     view! { world,
+///         <NodeBundle style={Style { flex_direction: FlexDirection::Column, ..default() }}>
+///             <CheckBox on:changed={cloned!((checked) => move |world, value| checked.update_value(world, |x| *x = value))} />
+///             <TextBox width={200.0.px()} contents="hello" />
+///             <TextBox width={
+///                 let checked = checked.clone();
+///                 move |world| if *checked.value(world) { 300.0.px() } else { 200.0.px() }
+///             } />
+///             <Show when={checked.clone()}>
+///                 "only while checked"
+///             </Show>
+///             <For each={names} render={|world, name: &String| view!(world, <TextBox contents={name.clone()} />)} />
+///         </NodeBundle>
+///     }
+/// }
+/// ```
+///
+/// - `<Element attr=value>` sets the field `attr` of the element's props (see `ViewElement`) to
+///   `value.into()`, so props taking a `MaybeReactive` accept plain values and accessors alike.
+///   A closure value (`width={move |world| ..}`, or a block ending in one) is turned into the
+///   prop with `fgr::FromDeriveFn` instead, re-running the closure on every read.
+///   Values other than literals, paths and calls go in braces.
+/// - `on:event={handler}` calls `set_on_event(Some(Box::new(handler)))` on the props, which
+///   `#[component]` generates for its `on_event` props. Pointer events such as `on:click` or
+///   `on:drag_start` on components without an `on_click` of their own set the handler in their
+///   `#[prop(events)]` prop instead, see `EventProps` and `PointerEvents`.
+/// - `bind:field={accessor}` sets a bundle field to the current value of the accessor and inserts
+///   the component again whenever it changes.
+/// - Children are elements, string literals (spawned as text) or `{ blocks }` evaluating to
+///   anything implementing `ViewChildren`. They are rendered before the element and handed to
+///   it with `ViewElement::set_children`, elements without a `children` prop get them added as
+///   children of their entity.
+/// - `<slot:name> .. </slot:name>` inside an element renders its contents into the `Slot`
+///   prop `name`, e.g. the `header` of a `Panel`.
+/// - `<Show when=..>` renders its children lazily while `when` is true, the children are
+///   rendered in a closure so anything they use is moved in and has to be cloned there.
+/// - `<For each={reactive_vec} render={|world, item| ..} />` renders a `List`.
+/// - `For` and `Show` are recognised by name, so other types with those names can not be used as
+///   elements, it is an error to try.
+///
+/// Props are checked by the compiler, unknown ones are an error:
+///
+/// ```compile_fail
+/// use bevy::prelude::*;
+/// use bevy_editor_experiment_lib::ui::{view, TextBox};
+///
+/// fn build(world: &mut World) -> Entity {
+///     view!(world, <TextBox colour="red" />)
+/// }
+/// ```
+///
+/// as are values of the wrong type:
+///
+/// ```compile_fail
+/// use bevy::prelude::*;
+/// use bevy_editor_experiment_lib::ui::{view, TextBox};
+///
+/// fn build(world: &mut World) -> Entity {
+///     view!(world, <TextBox width=true />)
+/// }
+/// ```
+///
+/// and mismatched closing tags:
+///
+/// ```compile_fail
+/// use bevy::prelude::*;
+/// use bevy_editor_experiment_lib::ui::view;
+///
+/// fn build(world: &mut World) -> Entity {
+///     view!(world, <NodeBundle> </ButtonBundle>)
+/// }
+/// ```
+pub use bevy_editor_experiment_macros::view;
+
+/// Anything that can be used as an element in `view!`.
+///
+/// Implemented for every `Bundle` with a `Default`, whose props are the bundle itself, and for
+/// the `ui` components, whose props are their props struct.
+pub trait ViewElement {
+    type Props;
+
+    fn build(world: &mut World, props: Self::Props) -> Entity;
+
+    /// Hands the children written inside the element to its props. Elements that take no
+    /// children give them back, they are then appended to the built entity.
+    fn set_children(_props: &mut Self::Props, children: Slot) -> Option<Slot> {
+        Some(children)
+    }
+}
+
+impl<B: Bundle + Default> ViewElement for B {
+    type Props = B;
+
+    /// Spawns the bundle, which is despawned again with the current scope.
+    fn build(world: &mut World, props: B) -> Entity {
+        let entity = world.spawn(props).id();
+        world.fgr_on_cleanup(move |world| {
+            if let Some(entity) = world.get_entity_mut(entity) {
+                entity.despawn_recursive();
+            }
+        });
+        entity
+    }
+}
+
+/// What a `{ block }` child in `view!` may evaluate to.
+pub trait ViewChildren {
+    fn push_into(self, children: &mut Vec<Entity>);
+}
+
+impl ViewChildren for Entity {
+    fn push_into(self, children: &mut Vec<Entity>) {
+        children.push(self);
+    }
+}
+
+impl ViewChildren for Option<Entity> {
+    fn push_into(self, children: &mut Vec<Entity>) {
+        children.extend(self);
+    }
+}
+
+impl ViewChildren for Vec<Entity> {
+    fn push_into(mut self, children: &mut Vec<Entity>) {
+        children.append(&mut self);
+    }
+}
+
+impl<const N: usize> ViewChildren for [Entity; N] {
+    fn push_into(self, children: &mut Vec<Entity>) {
+        children.extend(self);
+    }
+}
+
+/// Used by the expansion of `view!`.
+#[doc(hidden)]
+pub mod __view {
+    use bevy::{ecs::component::Component, prelude::TextBundle, text::TextStyle};
+
+    use crate::{fgr::{EffectDeps, FgrExtensionMethods, FromDeriveFn, ReactiveVec}, ui::{For, List, ListProps, Show, Slot, UiComponent}};
+
+    use super::ViewElement;
+
+    pub use bevy::prelude::{Entity, World};
+
+    /// The elements `view!` expands itself rather than through `ViewElement`.
+    #[diagnostic::on_unimplemented(
+        message = "`view!` takes `<{Self}>` for `ui::For` or `ui::Show`, but it is neither",
+        note = "import it under another name to use it as an element"
+    )]
+    pub trait ControlFlow {}
+
+    impl ControlFlow for For {}
+
+    impl ControlFlow for Show {}
+
+    pub fn control_flow<T: ControlFlow>() {}
+
+    /// The setter traits `view!` sets the events of `EventProps` with.
+    pub mod events {
+        pub use crate::ui::PointerEvents;
+    }
+
+    /// The props `view!` sets the attributes of an element on. `#[component]`s with required
+    /// props shadow `default_props` with a function that fails to compile, naming them.
+    pub trait DefaultProps: ViewElement {
+        fn default_props<T>() -> Self::Props;
+    }
+
+    impl<E: ViewElement> DefaultProps for E
+    where
+        E::Props: Default,
+    {
+        fn default_props<T>() -> E::Props {
+            E::Props::default()
+        }
+    }
+
+    /// A prop of the same type as `prop` deriving its value with `derive_fn`, taking the prop
+    /// only to infer the closure's argument type from it.
+    pub fn derived<P: FromDeriveFn>(_prop: &P, derive_fn: impl Fn(&mut P::Ctx) -> P::Value + Send + Sync + 'static) -> P {
+        P::from_derive_fn(derive_fn)
+    }
+
+    pub fn text(world: &mut World, text: &str) -> Entity {
+        TextBundle::build(world, TextBundle::from_section(text, TextStyle::default()))
+    }
+
+    pub fn mount(world: &mut World, entity: Entity, children: Option<Slot>) {
+        if let Some(mut children) = children {
+            children.mount(world, entity);
+        }
+    }
+
+    pub fn read_untracked<D: EffectDeps<World>>(world: &mut World, deps: &D) -> D::Value {
+        world.fgr_untrack(|world| deps.read(world))
+    }
+
+    pub fn bind<D>(world: &mut World, entity: Entity, deps: D)
+    where
+        D: EffectDeps<World> + Send + Sync + 'static,
+        D::Value: Component + Clone,
+    {
+        world.fgr_create_effect_on_deferred(deps, move |world, value, _| {
+            if let Some(mut entity) = world.get_entity_mut(entity) {
+                entity.insert(value.clone());
+            }
+        });
+    }
+
+    pub fn for_each<T: Clone + Send + Sync + 'static>(
+        world: &mut World,
+        items: ReactiveVec<World, T>,
+        render: impl FnMut(&mut World, &T) -> Entity + Send + Sync + 'static,
+    ) -> Entity {
+        List::run(world, ListProps { items, render_item: Box::new(render) })
+    }
+}
@@ -0,0 +1,18 @@
+use bevy::prelude::*;
+use bevy_editor_experiment_lib::ui::{view, ShowProps, ViewElement};
+
+struct Show;
+
+impl ViewElement for Show {
+    type Props = ShowProps;
+
+    fn build(world: &mut World, _props: ShowProps) -> Entity {
+        world.spawn(NodeBundle::default()).id()
+    }
+}
+
+fn build(world: &mut World) -> Entity {
+    view!(world, <Show when=true>"shown"</Show>)
+}
+
+fn main() {}
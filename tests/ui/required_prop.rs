@@ -0,0 +1,13 @@
+use bevy::prelude::*;
+use bevy_editor_experiment_lib::ui::{component, view};
+
+#[component]
+fn swatch(world: &mut World, color: Color) -> Entity {
+    world.spawn(NodeBundle { background_color: color.into(), ..default() }).id()
+}
+
+fn build(world: &mut World) -> Entity {
+    view!(world, <Swatch color={Color::WHITE} />)
+}
+
+fn main() {}
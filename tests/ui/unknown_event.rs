@@ -0,0 +1,8 @@
+use bevy::prelude::*;
+use bevy_editor_experiment_lib::ui::{view, PointerEvent, TextBox};
+
+fn build(world: &mut World) -> Entity {
+    view!(world, <TextBox on:hover={|_world: &mut World, _event: &PointerEvent| {}} />)
+}
+
+fn main() {}
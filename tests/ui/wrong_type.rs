@@ -0,0 +1,8 @@
+use bevy::prelude::*;
+use bevy_editor_experiment_lib::ui::{view, TextBox};
+
+fn build(world: &mut World) -> Entity {
+    view!(world, <TextBox width=true />)
+}
+
+fn main() {}